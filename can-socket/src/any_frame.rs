@@ -0,0 +1,90 @@
+use crate::{CanErrorFrame, CanFdFrame, CanFrame, CanXlFrame};
+
+/// A CAN frame of any type that can be received from a CAN socket.
+///
+/// This is returned by [`CanSocket::recv_any()`][crate::CanSocket::recv_any] and related functions.
+/// To receive CAN FD or CAN XL frames, you have to enable them on the socket first.
+/// Error frames are only received for the error classes enabled with [`CanSocket::set_error_mask()`][crate::CanSocket::set_error_mask].
+#[derive(Clone, Debug)]
+pub enum AnyCanFrame {
+	/// A classic CAN frame.
+	Classic(CanFrame),
+
+	/// A CAN FD frame.
+	Fd(CanFdFrame),
+
+	/// A CAN XL frame.
+	///
+	/// CAN XL frames are boxed because they are much larger than the other frame types.
+	Xl(Box<CanXlFrame>),
+
+	/// An error frame generated by the CAN driver.
+	Error(CanErrorFrame),
+}
+
+impl AnyCanFrame {
+	pub(crate) fn from_sys(frame: crate::sys::AnyFrame) -> Self {
+		match frame {
+			crate::sys::AnyFrame::Can(inner) => Self::Classic(CanFrame { inner }),
+			crate::sys::AnyFrame::Fd(inner) => Self::Fd(CanFdFrame { inner }),
+			crate::sys::AnyFrame::Xl(inner) => Self::Xl(Box::new(CanXlFrame { inner: *inner })),
+			crate::sys::AnyFrame::Error(inner) => Self::Error(CanErrorFrame { inner }),
+		}
+	}
+
+	/// Get the frame as a classic CAN frame, or `None` if it is a different type of frame.
+	pub fn as_classic(&self) -> Option<&CanFrame> {
+		match self {
+			Self::Classic(frame) => Some(frame),
+			_ => None,
+		}
+	}
+
+	/// Get the frame as a CAN FD frame, or `None` if it is a different type of frame.
+	pub fn as_fd(&self) -> Option<&CanFdFrame> {
+		match self {
+			Self::Fd(frame) => Some(frame),
+			_ => None,
+		}
+	}
+
+	/// Get the frame as a CAN XL frame, or `None` if it is a different type of frame.
+	pub fn as_xl(&self) -> Option<&CanXlFrame> {
+		match self {
+			Self::Xl(frame) => Some(frame.as_ref()),
+			_ => None,
+		}
+	}
+
+	/// Get the frame as an error frame, or `None` if it is a different type of frame.
+	pub fn as_error(&self) -> Option<&CanErrorFrame> {
+		match self {
+			Self::Error(frame) => Some(frame),
+			_ => None,
+		}
+	}
+}
+
+impl From<CanFrame> for AnyCanFrame {
+	fn from(value: CanFrame) -> Self {
+		Self::Classic(value)
+	}
+}
+
+impl From<CanFdFrame> for AnyCanFrame {
+	fn from(value: CanFdFrame) -> Self {
+		Self::Fd(value)
+	}
+}
+
+impl From<CanXlFrame> for AnyCanFrame {
+	fn from(value: CanXlFrame) -> Self {
+		Self::Xl(Box::new(value))
+	}
+}
+
+impl From<CanErrorFrame> for AnyCanFrame {
+	fn from(value: CanErrorFrame) -> Self {
+		Self::Error(value)
+	}
+}
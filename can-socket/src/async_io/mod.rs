@@ -0,0 +1,4 @@
+//! Support for [`async-io`][::async_io], usable with `smol`, `async-std` and other executors.
+
+mod socket;
+pub use socket::CanSocket;
@@ -0,0 +1,109 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use async_io::Async;
+
+use crate::sys;
+use crate::AnyCanFrame;
+use crate::CanErrorMask;
+use crate::CanFdFrame;
+use crate::CanFilter;
+use crate::CanFrame;
+use crate::CanInterface;
+use crate::CanXlFrame;
+use crate::Deadline;
+use crate::RecvMetadata;
+use crate::TimestampMode;
+use crate::Timestamps;
+
+/// An asynchronous CAN socket for `async-io`.
+///
+/// The socket can be used with any executor, including `smol` and `async-std`.
+/// All functions take `&self`, so a socket can be shared between tasks with an [`Arc`][std::sync::Arc].
+///
+/// # Cancel safety
+/// All async functions to send or receive frames are cancel safe.
+/// If a receive future is dropped before it completes, no frame has been taken from the socket.
+/// If a send future is dropped before it completes, the frame has not been passed to the kernel.
+pub struct CanSocket {
+	io: Async<sys::Socket>,
+}
+
+crate::async_socket::impl_async_socket!(
+	"The timeout can be a [`std::time::Duration`], [`std::time::Instant`] or any other implementator of the [`Deadline`] trait."
+);
+
+impl CanSocket {
+	fn from_sys(inner: sys::Socket) -> std::io::Result<Self> {
+		let io = Async::new(inner)?;
+		Ok(Self { io })
+	}
+
+	async fn read_io<T>(&self, f: impl FnMut(&sys::Socket) -> std::io::Result<T>) -> std::io::Result<T> {
+		self.io.read_with(f).await
+	}
+
+	async fn write_io<T>(&self, f: impl FnMut(&sys::Socket) -> std::io::Result<T>) -> std::io::Result<T> {
+		self.io.write_with(f).await
+	}
+
+	fn try_read_io<T>(&self, f: impl FnOnce(&sys::Socket) -> std::io::Result<T>) -> std::io::Result<T> {
+		f(self.io.get_ref())
+	}
+
+	fn try_write_io<T>(&self, f: impl FnOnce(&sys::Socket) -> std::io::Result<T>) -> std::io::Result<T> {
+		f(self.io.get_ref())
+	}
+
+	fn poll_read_io<T>(&self, context: &mut Context<'_>, mut f: impl FnMut(&sys::Socket) -> std::io::Result<T>) -> Poll<std::io::Result<T>> {
+		loop {
+			std::task::ready!(self.io.poll_readable(context))?;
+			match f(self.io.get_ref()) {
+				Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+				result => return Poll::Ready(result),
+			}
+		}
+	}
+
+	fn poll_write_io<T>(&self, context: &mut Context<'_>, mut f: impl FnMut(&sys::Socket) -> std::io::Result<T>) -> Poll<std::io::Result<T>> {
+		loop {
+			std::task::ready!(self.io.poll_writable(context))?;
+			match f(self.io.get_ref()) {
+				Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+				result => return Poll::Ready(result),
+			}
+		}
+	}
+}
+
+/// Deregister the socket from the `async-io` reactor and return the file descriptor.
+impl TryFrom<CanSocket> for std::os::fd::OwnedFd {
+	type Error = std::io::Error;
+
+	fn try_from(value: CanSocket) -> std::io::Result<Self> {
+		Ok(value.io.into_inner()?.into())
+	}
+}
+
+impl TryFrom<std::os::fd::OwnedFd> for CanSocket {
+	type Error = std::io::Error;
+
+	fn try_from(value: std::os::fd::OwnedFd) -> std::io::Result<Self> {
+		CanSocket::from_sys(sys::Socket::from(value))
+	}
+}
+
+/// Wait for a future to complete, or fail with [`std::io::ErrorKind::TimedOut`] when the deadline expires.
+async fn timeout_at<T>(timeout: impl Deadline, future: impl Future<Output = std::io::Result<T>>) -> std::io::Result<T> {
+	let mut future = std::pin::pin!(future);
+	let mut timer = async_io::Timer::at(timeout.deadline());
+	std::future::poll_fn(|context| {
+		if let Poll::Ready(result) = future.as_mut().poll(context) {
+			return Poll::Ready(result);
+		}
+		if Pin::new(&mut timer).poll(context).is_ready() {
+			return Poll::Ready(Err(std::io::ErrorKind::TimedOut.into()));
+		}
+		Poll::Pending
+	}).await
+}
@@ -0,0 +1,811 @@
+//! The implementation of the async `CanSocket` that is shared by the `tokio` and `async-io` backends.
+//!
+//! Each backend defines a `CanSocket` struct with an `io` field that wraps a `sys::Socket`,
+//! and implements these private functions for it:
+//!
+//! * `from_sys()` to wrap a `sys::Socket`,
+//! * `read_io()` and `write_io()` to wait until the socket is ready and perform a non-blocking operation,
+//! * `try_read_io()` and `try_write_io()` to perform a non-blocking operation without waiting,
+//! * `poll_read_io()` and `poll_write_io()` to perform a non-blocking operation or register the waker of the current task.
+//!
+//! The backend must also have a `timeout_at()` function in scope.
+//! The [`impl_async_socket!`] macro then implements the public API of the socket on top of these functions.
+
+/// Implement the public API of an async `CanSocket`.
+///
+/// The argument is the documentation of the `timeout` parameter of the functions with a timeout.
+macro_rules! impl_async_socket {
+	($timeout_doc:literal) => {
+		impl std::fmt::Debug for CanSocket {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				let mut debug = f.debug_struct("CanSocket");
+				#[cfg(unix)]
+				{
+					use std::os::unix::io::AsRawFd;
+					debug.field("fd", &self.as_raw_fd());
+					debug.finish()
+				}
+
+				#[cfg(not(unix))]
+				debug.finish_non_exhaustive()
+			}
+		}
+
+		impl CanSocket {
+			/// Create a new socket bound to a named CAN interface.
+			///
+			/// This function is not async as it will either succeed or fail immediately.
+			pub fn bind(interface: impl AsRef<str>) -> std::io::Result<Self> {
+				let inner = sys::Socket::new(true)?;
+				let interface = inner.get_interface_by_name(interface.as_ref())?;
+				inner.bind(&interface)?;
+				Self::from_sys(inner)
+			}
+
+			/// Create a new socket bound to a interface by index.
+			///
+			/// This function is not async as it will either succeed or fail immediately.
+			pub fn bind_interface_index(index: u32) -> std::io::Result<Self> {
+				let inner = sys::Socket::new(true)?;
+				inner.bind(&crate::sys::CanInterface::from_index(index))?;
+				Self::from_sys(inner)
+			}
+
+			/// Create a new socket bound to all CAN interfaces on the system.
+			///
+			/// You can use [`Self::recv_from()`] if you need to know on which interface a frame was received,
+			/// and [`Self::send_to()`] to send a frame on a particular interface.
+			///
+			/// This function is not async as it will either succeed or fail immediately.
+			pub fn bind_all() -> std::io::Result<Self> {
+				Self::bind_interface_index(0)
+			}
+
+			/// Get the interface this socket is bound to.
+			///
+			/// If the socket is bound to all interfaces, the returned `CanInterface` will report index 0.
+			pub fn local_addr(&self) -> std::io::Result<CanInterface> {
+				Ok(CanInterface {
+					inner: self.io.get_ref().local_addr()?,
+				})
+			}
+
+			/// Send a frame over the socket.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			///
+			/// This function is cancel safe: if the future is dropped before it completes, the frame has not been sent.
+			pub async fn send(&self, frame: &CanFrame) -> std::io::Result<()> {
+				self.write_io(|inner| {
+					inner.send(&frame.inner)
+				}).await
+			}
+
+			/// Send a frame over the socket with a timeout.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_timeout(&self, frame: &CanFrame, timeout: impl Deadline) -> std::io::Result<()> {
+				timeout_at(timeout, self.send(frame)).await
+			}
+
+			/// Try to send a frame over the socket without waiting for the socket to become writable.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub fn try_send(&self, frame: &CanFrame) -> std::io::Result<()> {
+				self.try_write_io(|inner| {
+					inner.send(&frame.inner)
+				})
+			}
+
+			/// Poll to send a frame over the socket.
+			///
+			/// If the socket is not writable, the current task is scheduled to be woken up when it becomes writable,
+			/// and `Poll::Pending` is returned.
+			/// In that case, the frame has not been sent and you should try again with the same frame when woken up.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub fn poll_send(&self, context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>> {
+				self.poll_write_io(context, |inner| inner.send(&frame.inner))
+			}
+
+			/// Send a frame over a particular interface.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			pub async fn send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.write_io(|inner| {
+					inner.send_to(&frame.inner, &interface.inner)
+				}).await
+			}
+
+			/// Send a frame over a particular interface.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_to_timeout(&self, frame: &CanFrame, interface: &CanInterface, timeout: impl Deadline) -> std::io::Result<()> {
+				timeout_at(timeout, self.send_to(frame, interface)).await
+			}
+
+			/// Try to send a frame over the socket without waiting for the socket to become writable.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub fn try_send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.try_write_io(|inner| {
+					inner.send_to(&frame.inner, &interface.inner)
+				})
+			}
+
+			/// Send multiple frames over the socket with a single system call.
+			///
+			/// Returns the number of frames that were sent, which may be less than the number of frames given.
+			/// If no frame could be sent at all, an error is returned.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frames for transmission.
+			/// It does not mean the frames have been successfully transmitted over the CAN bus.
+			pub async fn send_batch(&self, frames: &[CanFrame]) -> std::io::Result<usize> {
+				self.write_io(|inner| {
+					inner.send_batch(frames)
+				}).await
+			}
+
+			/// Send multiple frames over the socket with a single system call and a timeout.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_batch_timeout(&self, frames: &[CanFrame], timeout: impl Deadline) -> std::io::Result<usize> {
+				timeout_at(timeout, self.send_batch(frames)).await
+			}
+
+			/// Try to send multiple frames over the socket without waiting for the socket to become writable.
+			pub fn try_send_batch(&self, frames: &[CanFrame]) -> std::io::Result<usize> {
+				self.try_write_io(|inner| {
+					inner.send_batch(frames)
+				})
+			}
+
+			/// Send a CAN FD frame over the socket.
+			///
+			/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+			/// and the interface must support CAN FD.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub async fn send_fd(&self, frame: &CanFdFrame) -> std::io::Result<()> {
+				self.write_io(|inner| {
+					inner.send_fd(&frame.inner)
+				}).await
+			}
+
+			/// Send a CAN FD frame over the socket with a timeout.
+			///
+			/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+			/// and the interface must support CAN FD.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_fd_timeout(&self, frame: &CanFdFrame, timeout: impl Deadline) -> std::io::Result<()> {
+				timeout_at(timeout, self.send_fd(frame)).await
+			}
+
+			/// Try to send a CAN FD frame over the socket without waiting for the socket to become writable.
+			///
+			/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+			/// and the interface must support CAN FD.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub fn try_send_fd(&self, frame: &CanFdFrame) -> std::io::Result<()> {
+				self.try_write_io(|inner| {
+					inner.send_fd(&frame.inner)
+				})
+			}
+
+			/// Send a CAN FD frame over a particular interface.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+			/// and the interface must support CAN FD.
+			pub async fn send_fd_to(&self, frame: &CanFdFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.write_io(|inner| {
+					inner.send_fd_to(&frame.inner, &interface.inner)
+				}).await
+			}
+
+			/// Send a CAN FD frame over a particular interface with a timeout.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+			/// and the interface must support CAN FD.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_fd_to_timeout(&self, frame: &CanFdFrame, interface: &CanInterface, timeout: impl Deadline) -> std::io::Result<()> {
+				timeout_at(timeout, self.send_fd_to(frame, interface)).await
+			}
+
+			/// Try to send a CAN FD frame over a particular interface without waiting for the socket to become writable.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+			/// and the interface must support CAN FD.
+			pub fn try_send_fd_to(&self, frame: &CanFdFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.try_write_io(|inner| {
+					inner.send_fd_to(&frame.inner, &interface.inner)
+				})
+			}
+
+			/// Send a CAN XL frame over the socket.
+			///
+			/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+			/// and the interface must support CAN XL.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub async fn send_xl(&self, frame: &CanXlFrame) -> std::io::Result<()> {
+				self.write_io(|inner| {
+					inner.send_xl(&frame.inner)
+				}).await
+			}
+
+			/// Send a CAN XL frame over the socket with a timeout.
+			///
+			/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+			/// and the interface must support CAN XL.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_xl_timeout(&self, frame: &CanXlFrame, timeout: impl Deadline) -> std::io::Result<()> {
+				timeout_at(timeout, self.send_xl(frame)).await
+			}
+
+			/// Try to send a CAN XL frame over the socket without waiting for the socket to become writable.
+			///
+			/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+			/// and the interface must support CAN XL.
+			///
+			/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+			/// It does not mean the frame has been successfully transmitted over the CAN bus.
+			pub fn try_send_xl(&self, frame: &CanXlFrame) -> std::io::Result<()> {
+				self.try_write_io(|inner| {
+					inner.send_xl(&frame.inner)
+				})
+			}
+
+			/// Send a CAN XL frame over a particular interface.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+			/// and the interface must support CAN XL.
+			pub async fn send_xl_to(&self, frame: &CanXlFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.write_io(|inner| {
+					inner.send_xl_to(&frame.inner, &interface.inner)
+				}).await
+			}
+
+			/// Send a CAN XL frame over a particular interface with a timeout.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+			/// and the interface must support CAN XL.
+			///
+			#[doc = $timeout_doc]
+			pub async fn send_xl_to_timeout(&self, frame: &CanXlFrame, interface: &CanInterface, timeout: impl Deadline) -> std::io::Result<()> {
+				timeout_at(timeout, self.send_xl_to(frame, interface)).await
+			}
+
+			/// Try to send a CAN XL frame over a particular interface without waiting for the socket to become writable.
+			///
+			/// The interface must match the interface the socket was bound to,
+			/// or the socket must have been bound to all interfaces.
+			///
+			/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+			/// and the interface must support CAN XL.
+			pub fn try_send_xl_to(&self, frame: &CanXlFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.try_write_io(|inner| {
+					inner.send_xl_to(&frame.inner, &interface.inner)
+				})
+			}
+
+			/// Receive a frame from the socket.
+			///
+			/// If CAN FD frames, CAN XL frames or error frames are enabled on the socket and such a frame is received,
+			/// this function returns an error and the frame is discarded.
+			/// Use [`Self::recv_any()`] to receive all types of frames.
+			///
+			/// This function is cancel safe: if the future is dropped before it completes, no frame has been received.
+			pub async fn recv(&self) -> std::io::Result<CanFrame> {
+				self.read_io(|inner| {
+					Ok(CanFrame {
+						inner: inner.recv()?,
+					})
+				}).await
+			}
+
+			/// Receive a frame from the socket with a timeout.
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<CanFrame> {
+				timeout_at(timeout, self.recv()).await
+			}
+
+			/// Receive a frame from the socket, without waiting for one to become available.
+			pub fn try_recv(&self) -> std::io::Result<CanFrame> {
+				self.try_read_io(|socket| {
+					Ok(CanFrame {
+						inner: socket.recv()?,
+					})
+				})
+			}
+
+			/// Receive a frame from the socket, including information about which interface the frame was received on.
+			pub async fn recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
+				self.read_io(|inner| {
+					let (frame, interface) = inner.recv_from()?;
+					let frame = CanFrame { inner: frame };
+					let interface = CanInterface { inner: interface };
+					Ok((frame, interface))
+				}).await
+			}
+
+			/// Receive a frame from the socket with a timeout, including information about which interface the frame was received on.
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_from_timeout(&self, timeout: impl Deadline) -> std::io::Result<(CanFrame, CanInterface)> {
+				timeout_at(timeout, self.recv_from()).await
+			}
+
+			/// Receive a frame from the socket, without waiting for one to become available.
+			pub fn try_recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
+				self.try_read_io(|socket| {
+					let (frame, interface) = socket.recv_from()?;
+					let frame = CanFrame { inner: frame };
+					let interface = CanInterface { inner: interface };
+					Ok((frame, interface))
+				})
+			}
+
+			/// Poll to receive a frame from the socket.
+			///
+			/// If no frame is available, the current task is scheduled to be woken up when the socket becomes readable,
+			/// and `Poll::Pending` is returned.
+			pub fn poll_recv(&self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>> {
+				self.poll_read_io(context, |inner| inner.recv())
+					.map_ok(|inner| CanFrame { inner })
+			}
+
+			/// Poll to receive a frame from the socket, including information about which interface the frame was received on.
+			///
+			/// If no frame is available, the current task is scheduled to be woken up when the socket becomes readable,
+			/// and `Poll::Pending` is returned.
+			pub fn poll_recv_from(&self, context: &mut Context<'_>) -> Poll<std::io::Result<(CanFrame, CanInterface)>> {
+				self.poll_read_io(context, |inner| inner.recv_from())
+					.map_ok(|(frame, interface)| (CanFrame { inner: frame }, CanInterface { inner: interface }))
+			}
+
+			/// Receive multiple frames from the socket with a single system call.
+			///
+			/// The received frames are written to the start of `frames`.
+			/// Returns the number of frames received.
+			///
+			/// This function waits until at least one frame is available,
+			/// and then returns all frames that are available without blocking (up to `frames.len()`).
+			///
+			/// Frames that are not classic CAN frames are discarded.
+			/// If all received frames are discarded, this function keeps waiting for more frames.
+			pub async fn recv_batch(&self, frames: &mut [CanFrame]) -> std::io::Result<usize> {
+				self.read_io(|inner| {
+					inner.recv_batch(frames, None)
+				}).await
+			}
+
+			/// Receive multiple frames from the socket with a single system call and a timeout.
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_batch_timeout(&self, frames: &mut [CanFrame], timeout: impl Deadline) -> std::io::Result<usize> {
+				timeout_at(timeout, self.recv_batch(frames)).await
+			}
+
+			/// Receive multiple frames from the socket, without waiting for one to become available.
+			pub fn try_recv_batch(&self, frames: &mut [CanFrame]) -> std::io::Result<usize> {
+				self.try_read_io(|inner| {
+					inner.recv_batch(frames, None)
+				})
+			}
+
+			/// Receive multiple frames from the socket with a single system call, including the interface of each frame.
+			///
+			/// The received frames are written to the start of `frames`,
+			/// and the interface of each frame is written to the same index in `interfaces`.
+			/// At most `min(frames.len(), interfaces.len())` frames are received.
+			/// Returns the number of frames received.
+			///
+			/// This function waits until at least one frame is available,
+			/// and then returns all frames that are available without blocking.
+			///
+			/// Frames that are not classic CAN frames are discarded.
+			/// If all received frames are discarded, this function keeps waiting for more frames.
+			pub async fn recv_batch_from(&self, frames: &mut [CanFrame], interfaces: &mut [CanInterface]) -> std::io::Result<usize> {
+				self.read_io(|inner| {
+					inner.recv_batch(frames, Some(interfaces))
+				}).await
+			}
+
+			/// Receive multiple frames from the socket with a timeout, including the interface of each frame.
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_batch_from_timeout(&self, frames: &mut [CanFrame], interfaces: &mut [CanInterface], timeout: impl Deadline) -> std::io::Result<usize> {
+				timeout_at(timeout, self.recv_batch_from(frames, interfaces)).await
+			}
+
+			/// Receive multiple frames from the socket including the interface of each frame, without waiting for one to become available.
+			pub fn try_recv_batch_from(&self, frames: &mut [CanFrame], interfaces: &mut [CanInterface]) -> std::io::Result<usize> {
+				self.try_read_io(|inner| {
+					inner.recv_batch(frames, Some(interfaces))
+				})
+			}
+
+			/// Receive a frame from the socket, including the timestamps of the frame.
+			///
+			/// Timestamps must be enabled with [`Self::set_timestamp_mode()`],
+			/// otherwise the returned timestamps are all `None`.
+			///
+			/// Like [`Self::recv()`], this function returns an error if it receives a frame that is not a classic CAN frame.
+			pub async fn recv_with_timestamp(&self) -> std::io::Result<(CanFrame, Timestamps)> {
+				self.read_io(|inner| {
+					let (frame, metadata) = inner.recv_with_metadata()?;
+					Ok((CanFrame { inner: frame }, metadata.timestamps))
+				}).await
+			}
+
+			/// Receive a frame from the socket with a timeout, including the timestamps of the frame.
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_with_timestamp_timeout(&self, timeout: impl Deadline) -> std::io::Result<(CanFrame, Timestamps)> {
+				timeout_at(timeout, self.recv_with_timestamp()).await
+			}
+
+			/// Receive a frame from the socket including the timestamps of the frame, without waiting for one to become available.
+			pub fn try_recv_with_timestamp(&self) -> std::io::Result<(CanFrame, Timestamps)> {
+				self.try_read_io(|socket| {
+					let (frame, metadata) = socket.recv_with_metadata()?;
+					Ok((CanFrame { inner: frame }, metadata.timestamps))
+				})
+			}
+
+			/// Receive a frame from the socket, including metadata such as the interface, timestamps and drop count.
+			///
+			/// Timestamps must be enabled with [`Self::set_timestamp_mode()`],
+			/// otherwise the returned timestamps are all `None`.
+			///
+			/// Like [`Self::recv()`], this function returns an error if it receives a frame that is not a classic CAN frame.
+			pub async fn recv_from_with_metadata(&self) -> std::io::Result<(CanFrame, RecvMetadata)> {
+				self.read_io(|inner| {
+					let (frame, metadata) = inner.recv_with_metadata()?;
+					Ok((CanFrame { inner: frame }, RecvMetadata::from_sys(metadata)))
+				}).await
+			}
+
+			/// Receive a frame from the socket with a timeout, including metadata such as the interface and timestamps of the frame.
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_from_with_metadata_timeout(&self, timeout: impl Deadline) -> std::io::Result<(CanFrame, RecvMetadata)> {
+				timeout_at(timeout, self.recv_from_with_metadata()).await
+			}
+
+			/// Receive a frame from the socket including metadata, without waiting for one to become available.
+			pub fn try_recv_from_with_metadata(&self) -> std::io::Result<(CanFrame, RecvMetadata)> {
+				self.try_read_io(|socket| {
+					let (frame, metadata) = socket.recv_with_metadata()?;
+					Ok((CanFrame { inner: frame }, RecvMetadata::from_sys(metadata)))
+				})
+			}
+
+			/// Receive a frame of any type from the socket.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			pub async fn recv_any(&self) -> std::io::Result<AnyCanFrame> {
+				self.read_io(|inner| {
+					Ok(AnyCanFrame::from_sys(inner.recv_any()?))
+				}).await
+			}
+
+			/// Receive a frame of any type from the socket with a timeout.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_any_timeout(&self, timeout: impl Deadline) -> std::io::Result<AnyCanFrame> {
+				timeout_at(timeout, self.recv_any()).await
+			}
+
+			/// Receive a frame of any type from the socket, without waiting for one to become available.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			pub fn try_recv_any(&self) -> std::io::Result<AnyCanFrame> {
+				self.try_read_io(|socket| {
+					Ok(AnyCanFrame::from_sys(socket.recv_any()?))
+				})
+			}
+
+			/// Receive a frame of any type from the socket, including information about which interface the frame was received on.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			pub async fn recv_any_from(&self) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+				self.read_io(|inner| {
+					let (frame, interface) = inner.recv_any_from()?;
+					let frame = AnyCanFrame::from_sys(frame);
+					let interface = CanInterface { inner: interface };
+					Ok((frame, interface))
+				}).await
+			}
+
+			/// Receive a frame of any type from the socket with a timeout, including information about which interface the frame was received on.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_any_from_timeout(&self, timeout: impl Deadline) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+				timeout_at(timeout, self.recv_any_from()).await
+			}
+
+			/// Receive a frame of any type from the socket, without waiting for one to become available.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			pub fn try_recv_any_from(&self) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+				self.try_read_io(|socket| {
+					let (frame, interface) = socket.recv_any_from()?;
+					let frame = AnyCanFrame::from_sys(frame);
+					let interface = CanInterface { inner: interface };
+					Ok((frame, interface))
+				})
+			}
+
+			/// Receive a frame of any type from the socket, including metadata such as the interface, timestamps and drop count.
+			///
+			/// Timestamps must be enabled with [`Self::set_timestamp_mode()`],
+			/// otherwise the returned timestamps are all `None`.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			pub async fn recv_any_with_metadata(&self) -> std::io::Result<(AnyCanFrame, RecvMetadata)> {
+				self.read_io(|inner| {
+					let (frame, metadata) = inner.recv_any_with_metadata()?;
+					Ok((AnyCanFrame::from_sys(frame), RecvMetadata::from_sys(metadata)))
+				}).await
+			}
+
+			/// Receive a frame of any type from the socket with a timeout, including metadata such as the interface, timestamps and drop count.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			///
+			#[doc = $timeout_doc]
+			pub async fn recv_any_with_metadata_timeout(&self, timeout: impl Deadline) -> std::io::Result<(AnyCanFrame, RecvMetadata)> {
+				timeout_at(timeout, self.recv_any_with_metadata()).await
+			}
+
+			/// Receive a frame of any type from the socket including metadata, without waiting for one to become available.
+			///
+			/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+			/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+			/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+			pub fn try_recv_any_with_metadata(&self) -> std::io::Result<(AnyCanFrame, RecvMetadata)> {
+				self.try_read_io(|socket| {
+					let (frame, metadata) = socket.recv_any_with_metadata()?;
+					Ok((AnyCanFrame::from_sys(frame), RecvMetadata::from_sys(metadata)))
+				})
+			}
+
+			/// Set the list of filters on the socket.
+			///
+			/// When a socket is created, it will receive all frames from the CAN interface.
+			/// You can restrict this by setting the filters with this function.
+			///
+			/// A frame has to match only one of the filters in the list to be received by the socket,
+			/// unless the filters are joined with [`Self::set_join_filters()`].
+			pub fn set_filters(&self, filters: &[CanFilter]) -> std::io::Result<()> {
+				self.io.get_ref().set_filters(filters)
+			}
+
+			/// Get the list of filters on the socket.
+			///
+			/// New sockets have a single filter that matches all frames.
+			pub fn get_filters(&self) -> std::io::Result<Vec<CanFilter>> {
+				let filters = self.io.get_ref().get_filters()?;
+				Ok(filters.into_iter().map(|filter| CanFilter { filter }).collect())
+			}
+
+			/// Check if the filters of the socket are joined.
+			///
+			/// When joined, a frame has to match all filters to be received by the socket.
+			pub fn get_join_filters(&self) -> std::io::Result<bool> {
+				self.io.get_ref().get_join_filters()
+			}
+
+			/// Set if the filters of the socket should be joined.
+			///
+			/// When disabled (the default for new sockets), a frame has to match only one of the filters to be received by the socket.
+			/// When enabled, a frame has to match all of the filters instead.
+			///
+			/// Joining filters allows you to express things like "ID in range A, but not in range B"
+			/// by combining a normal filter with an inverted filter.
+			pub fn set_join_filters(&self, enable: bool) -> std::io::Result<()> {
+				self.io.get_ref().set_join_filters(enable)
+			}
+
+			/// Check if CAN FD frames are enabled on the socket.
+			///
+			/// When disabled (the default for new sockets), the socket can only send and receive classic CAN frames.
+			pub fn get_fd_frames(&self) -> std::io::Result<bool> {
+				self.io.get_ref().get_fd_frames()
+			}
+
+			/// Enable or disable CAN FD frames on the socket.
+			///
+			/// When enabled, the socket can send CAN FD frames with [`Self::send_fd()`],
+			/// and it will receive CAN FD frames in addition to classic CAN frames.
+			/// Use [`Self::recv_any()`] to receive both types of frames.
+			///
+			/// Enabling CAN FD frames can fail if the kernel does not support CAN FD.
+			/// Sending CAN FD frames will fail if the interface does not support CAN FD.
+			pub fn set_fd_frames(&self, enable: bool) -> std::io::Result<()> {
+				self.io.get_ref().set_fd_frames(enable)
+			}
+
+			/// Get the timestamp mode of the socket.
+			pub fn get_timestamp_mode(&self) -> std::io::Result<TimestampMode> {
+				self.io.get_ref().get_timestamp_mode()
+			}
+
+			/// Set the timestamp mode of the socket.
+			///
+			/// When enabled, the kernel generates timestamps for received frames.
+			/// Use [`Self::recv_with_timestamp()`] or [`Self::recv_from_with_metadata()`] to receive the timestamps.
+			pub fn set_timestamp_mode(&self, mode: TimestampMode) -> std::io::Result<()> {
+				self.io.get_ref().set_timestamp_mode(mode)
+			}
+
+			/// Check if the drop count is reported for received frames.
+			pub fn get_drop_count_enabled(&self) -> std::io::Result<bool> {
+				self.io.get_ref().get_drop_count_enabled()
+			}
+
+			/// Enable or disable reporting the drop count for received frames (`SO_RXQ_OVFL`).
+			///
+			/// When enabled, the kernel reports the total number of frames dropped because the receive queue of the socket was full.
+			/// Use [`Self::recv_from_with_metadata()`] and [`RecvMetadata::drop_count()`] to get the drop count.
+			pub fn set_drop_count_enabled(&self, enable: bool) -> std::io::Result<()> {
+				self.io.get_ref().set_drop_count_enabled(enable)
+			}
+
+			/// Get the error mask of the socket.
+			///
+			/// The socket only receives error frames for the error classes in the mask.
+			pub fn get_error_mask(&self) -> std::io::Result<CanErrorMask> {
+				Ok(CanErrorMask::from_bits(self.io.get_ref().get_error_mask()?))
+			}
+
+			/// Set the error mask of the socket.
+			///
+			/// The socket will receive error frames for the error classes in the mask.
+			/// The default for new sockets is [`CanErrorMask::NONE`], so no error frames are received.
+			///
+			/// Error frames are received with [`Self::recv_any()`] as [`AnyCanFrame::Error`].
+			/// [`Self::recv()`] returns an error if it receives an error frame.
+			pub fn set_error_mask(&self, mask: CanErrorMask) -> std::io::Result<()> {
+				self.io.get_ref().set_error_mask(mask.bits())
+			}
+
+			/// Check if CAN XL frames are enabled on the socket.
+			///
+			/// When disabled (the default for new sockets), the socket can not send or receive CAN XL frames.
+			pub fn get_xl_frames(&self) -> std::io::Result<bool> {
+				self.io.get_ref().get_xl_frames()
+			}
+
+			/// Enable or disable CAN XL frames on the socket.
+			///
+			/// When enabled, the socket can send CAN XL frames with [`Self::send_xl()`],
+			/// and it will receive CAN XL frames in addition to other frames.
+			/// Use [`Self::recv_any()`] to receive all types of frames.
+			///
+			/// Enabling CAN XL frames can fail if the kernel does not support CAN XL.
+			/// Sending CAN XL frames will fail if the interface does not support CAN XL.
+			pub fn set_xl_frames(&self, enable: bool) -> std::io::Result<()> {
+				self.io.get_ref().set_xl_frames(enable)
+			}
+
+			/// Check if the loopback option of the socket is enabled.
+			///
+			/// When enabled (the default for new sockets),
+			/// frames sent on the same interface by other sockets are also received by this socket.
+			pub fn get_loopback(&self) -> std::io::Result<bool> {
+				self.io.get_ref().get_loopback()
+			}
+
+			/// Enable or disabling the loopback option of the socket.
+			///
+			/// When enabled (the default for new sockets),
+			/// frames sent on the same interface by other sockets are also received by this socket.
+			///
+			/// See `Self::set_receive_own_messages()` if you also want to receive messages sens on *this* socket.
+			pub fn set_loopback(&self, enable: bool) -> std::io::Result<()> {
+				self.io.get_ref().set_loopback(enable)
+			}
+
+			/// Check if the receive own messages option of the socket is enabled.
+			///
+			/// When this option is enabled, frames sent on this socket are also delivered to this socket.
+			///
+			/// Note that frames sent on this socket are subject to all the same filtering mechanisms as other frames.
+			/// To receive frames send on this socket, you must also to ensure that the loopback option is enabled ([`Self::get_loopback()`]),
+			/// and that the frame is not discarded by the filters ([`Self::set_filters()`]).
+			pub fn get_receive_own_messages(&self) -> std::io::Result<bool> {
+				self.io.get_ref().get_receive_own_messages()
+			}
+
+			/// Enable or disable the receive own messages option of the socket.
+			///
+			/// When this option is enabled, frames sent on this socket are also delivered to this socket.
+			///
+			/// Note that frames sent on this socket are subject to all the same filtering mechanisms as other frames.
+			/// To receive frames send on this socket, you must also to ensure that the loopback option is enabled ([`Self::set_loopback()`]),
+			/// and that the frame is not discarded by the filters ([`Self::set_filters()`]).
+			pub fn set_receive_own_messages(&self, enable: bool) -> std::io::Result<()> {
+				self.io.get_ref().set_receive_own_messages(enable)
+			}
+		}
+
+		impl std::os::fd::AsFd for CanSocket {
+			fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+				self.io.as_fd()
+			}
+		}
+
+		impl std::os::fd::AsRawFd for CanSocket {
+			fn as_raw_fd(&self) -> std::os::fd::RawFd {
+				self.io.as_raw_fd()
+			}
+		}
+	};
+}
+
+pub(crate) use impl_async_socket;
@@ -0,0 +1,198 @@
+//! Broadcast manager (BCM) sockets.
+//!
+//! The broadcast manager is a kernel service that can transmit frames cyclically,
+//! and filter received frames down to content changes and timeouts.
+//! Because the timers run in the kernel, cyclic transmission does not suffer from user space scheduling jitter.
+//!
+//! Transmission jobs are configured with [`TxSetup`], and receive jobs with [`RxSetup`].
+//! Jobs are identified by their CAN ID, and are removed when the socket is closed.
+//! Notifications from the jobs are received as [`BcmEvent`].
+//!
+//! The broadcast manager only supports classic CAN frames in this crate.
+//! This requires the `can-bcm` kernel module.
+
+use std::time::Duration;
+
+use crate::{CanFrame, CanId};
+
+pub(crate) mod socket;
+pub use socket::BcmSocket;
+
+/// Configuration for a cyclic transmission job.
+///
+/// The job is identified by the CAN ID.
+/// Setting up a job for a CAN ID that already has a job updates the existing job.
+#[derive(Debug, Clone)]
+pub struct TxSetup {
+	pub(crate) id: CanId,
+	pub(crate) frames: Vec<CanFrame>,
+	pub(crate) interval: Option<Duration>,
+	pub(crate) initial: Option<(u32, Duration)>,
+	pub(crate) announce: bool,
+	pub(crate) count_event: bool,
+}
+
+impl TxSetup {
+	/// Create a transmission job for a single frame.
+	///
+	/// The CAN ID of the frame identifies the job.
+	pub fn new(frame: CanFrame) -> Self {
+		Self::new_sequence(frame.id(), vec![frame])
+	}
+
+	/// Create a transmission job that cycles through a sequence of frames.
+	///
+	/// Each time the timer expires, the next frame in the sequence is sent.
+	/// All frames are sent with the given CAN ID, which also identifies the job.
+	/// The kernel supports up to 256 frames per job.
+	pub fn new_sequence(id: impl Into<CanId>, frames: impl Into<Vec<CanFrame>>) -> Self {
+		Self {
+			id: id.into(),
+			frames: frames.into(),
+			interval: None,
+			initial: None,
+			announce: false,
+			count_event: false,
+		}
+	}
+
+	/// Set the interval for cyclic transmission.
+	///
+	/// If [`Self::with_initial()`] is also used, this interval is used after the initial transmissions.
+	///
+	/// If neither an interval nor an initial count is set,
+	/// the timers of an existing job are left untouched.
+	/// This can be used to update the data of a running job.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_interval(mut self, interval: Duration) -> Self {
+		self.interval = Some(interval);
+		self
+	}
+
+	/// Transmit the frames `count` times with the given interval, before switching to the regular interval.
+	///
+	/// If no regular interval is set, the transmission stops after `count` frames.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_initial(mut self, count: u32, interval: Duration) -> Self {
+		self.initial = Some((count, interval));
+		self
+	}
+
+	/// Send the first frame immediately when the job is set up or updated.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_announce(mut self, announce: bool) -> Self {
+		self.announce = announce;
+		self
+	}
+
+	/// Receive a [`BcmEvent::TxExpired`] event when the initial transmissions are done.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_count_event(mut self, count_event: bool) -> Self {
+		self.count_event = count_event;
+		self
+	}
+
+	/// Get the CAN ID of the job.
+	pub fn id(&self) -> CanId {
+		self.id
+	}
+
+	/// Get the frames of the job.
+	pub fn frames(&self) -> &[CanFrame] {
+		&self.frames
+	}
+}
+
+/// Configuration for a receive job.
+///
+/// The job is identified by the CAN ID.
+/// Setting up a job for a CAN ID that already has a job updates the existing job.
+#[derive(Debug, Clone)]
+pub struct RxSetup {
+	pub(crate) id: CanId,
+	pub(crate) mask: Option<[u8; 8]>,
+	pub(crate) timeout: Option<Duration>,
+	pub(crate) throttle: Option<Duration>,
+	pub(crate) check_dlc: bool,
+	pub(crate) auto_timer: bool,
+	pub(crate) announce_resume: bool,
+}
+
+impl RxSetup {
+	/// Create a receive job for a CAN ID.
+	///
+	/// Without a content mask, every received frame with the CAN ID is reported as [`BcmEvent::RxChanged`].
+	pub fn new(id: impl Into<CanId>) -> Self {
+		Self {
+			id: id.into(),
+			mask: None,
+			timeout: None,
+			throttle: None,
+			check_dlc: false,
+			auto_timer: true,
+			announce_resume: false,
+		}
+	}
+
+	/// Only report frames when the data bits selected by the mask change.
+	///
+	/// The first received frame is always reported.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_mask(mut self, mask: [u8; 8]) -> Self {
+		self.mask = Some(mask);
+		self
+	}
+
+	/// Report a [`BcmEvent::RxTimeout`] if no frame is received within the timeout.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Report changes at most once per throttle interval.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_throttle(mut self, throttle: Duration) -> Self {
+		self.throttle = Some(throttle);
+		self
+	}
+
+	/// Also report frames when the data length code changes.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_check_dlc(mut self, check_dlc: bool) -> Self {
+		self.check_dlc = check_dlc;
+		self
+	}
+
+	/// Restart the timeout timer automatically when a frame is received (enabled by default).
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_auto_timer(mut self, auto_timer: bool) -> Self {
+		self.auto_timer = auto_timer;
+		self
+	}
+
+	/// Report the first frame after a timeout as changed, even if the data did not change.
+	#[must_use = "this function returns a new configuration, it does not modify self"]
+	pub fn with_announce_resume(mut self, announce_resume: bool) -> Self {
+		self.announce_resume = announce_resume;
+		self
+	}
+
+	/// Get the CAN ID of the job.
+	pub fn id(&self) -> CanId {
+		self.id
+	}
+}
+
+/// A notification from the broadcast manager.
+#[derive(Debug, Copy, Clone)]
+pub enum BcmEvent {
+	/// A frame was received with changed content.
+	RxChanged(CanFrame),
+
+	/// No frame was received with the CAN ID within the timeout.
+	RxTimeout(CanId),
+
+	/// The initial transmissions of a job with [`TxSetup::with_count_event()`] are done.
+	TxExpired(CanId),
+}
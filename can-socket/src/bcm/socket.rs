@@ -0,0 +1,117 @@
+use crate::{CanFrame, CanId};
+use super::{BcmEvent, RxSetup, TxSetup};
+
+/// A synchronous broadcast manager socket.
+///
+/// Used to configure transmission and receive jobs in the kernel,
+/// and to receive the notifications of those jobs.
+///
+/// Although the socket is synchronous,
+/// it can be put into non-blocking mode with [`Self::set_nonblocking()`].
+#[repr(transparent)]
+pub struct BcmSocket {
+	inner: crate::sys::BcmSocket,
+}
+
+impl std::fmt::Debug for BcmSocket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("BcmSocket");
+		#[cfg(unix)]
+		{
+			use std::os::unix::io::AsRawFd;
+			debug.field("fd", &self.as_raw_fd());
+			debug.finish()
+		}
+
+		#[cfg(not(unix))]
+		debug.finish_non_exhaustive()
+	}
+}
+
+impl BcmSocket {
+	/// Create a new broadcast manager socket connected to a named CAN interface.
+	pub fn connect(interface: impl AsRef<str>) -> std::io::Result<Self> {
+		let inner = crate::sys::BcmSocket::new(false)?;
+		let interface = crate::sys::CanInterface::from_name(interface.as_ref())?;
+		inner.connect(&interface)?;
+		Ok(Self { inner })
+	}
+
+	/// Set the socket in non-blocking or blocking mode.
+	///
+	/// If the socket is set in non-blocking mode, send and receive operations will never block.
+	/// Instead, if the operation can not be completed immediately, it will fail with a [`std::io::ErrorKind::WouldBlock`] error.
+	pub fn set_nonblocking(&self, non_blocking: bool) -> std::io::Result<()> {
+		self.inner.set_nonblocking(non_blocking)
+	}
+
+	/// Set up or update a cyclic transmission job.
+	pub fn tx_setup(&self, setup: &TxSetup) -> std::io::Result<()> {
+		self.inner.tx_setup(setup)
+	}
+
+	/// Delete the transmission job for a CAN ID.
+	pub fn tx_delete(&self, id: impl Into<CanId>) -> std::io::Result<()> {
+		self.inner.tx_delete(id.into())
+	}
+
+	/// Send a single frame through the broadcast manager.
+	pub fn tx_send(&self, frame: &CanFrame) -> std::io::Result<()> {
+		self.inner.tx_send(frame)
+	}
+
+	/// Set up or update a receive job.
+	pub fn rx_setup(&self, setup: &RxSetup) -> std::io::Result<()> {
+		self.inner.rx_setup(setup)
+	}
+
+	/// Delete the receive job for a CAN ID.
+	pub fn rx_delete(&self, id: impl Into<CanId>) -> std::io::Result<()> {
+		self.inner.rx_delete(id.into())
+	}
+
+	/// Receive a notification from the broadcast manager.
+	pub fn recv(&self) -> std::io::Result<BcmEvent> {
+		self.inner.recv()
+	}
+}
+
+impl std::os::fd::AsFd for BcmSocket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.inner.as_fd()
+	}
+}
+
+impl From<BcmSocket> for std::os::fd::OwnedFd {
+	fn from(value: BcmSocket) -> Self {
+		value.inner.into()
+	}
+}
+
+impl From<std::os::fd::OwnedFd> for BcmSocket {
+	fn from(value: std::os::fd::OwnedFd) -> Self {
+		Self {
+			inner: value.into(),
+		}
+	}
+}
+
+impl std::os::fd::AsRawFd for BcmSocket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.inner.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for BcmSocket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.inner.into_raw_fd()
+	}
+}
+
+impl std::os::fd::FromRawFd for BcmSocket {
+	unsafe fn from_raw_fd(fd: std::os::fd::RawFd) -> Self {
+		Self {
+			inner: crate::sys::BcmSocket::from_raw_fd(fd)
+		}
+	}
+}
@@ -67,13 +67,19 @@ impl std::fmt::Debug for ParseIdError {
 #[derive(Clone, Debug)]
 pub struct TryIntoCanDataError {
 	pub(crate) len: usize,
+	pub(crate) min_len: usize,
+	pub(crate) max_len: usize,
 }
 
 impl std::error::Error for TryIntoCanDataError {}
 
 impl std::fmt::Display for TryIntoCanDataError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "data to large for CAN frame, expected at most 8 bytes, got {}", self.len)
+		if self.len < self.min_len {
+			write!(f, "data to small for CAN frame, expected at least {} bytes, got {}", self.min_len, self.len)
+		} else {
+			write!(f, "data to large for CAN frame, expected at most {} bytes, got {}", self.max_len, self.len)
+		}
 	}
 }
 
@@ -147,3 +153,75 @@ impl std::fmt::Display for InvalidDataLengthCode {
 		write!(f, "invalid data length code: {}, maximum allowed value is 15", self.value)
 	}
 }
+
+/// Failed to parse an entry of a CAN log file.
+#[derive(Debug, Clone)]
+pub struct ParseLogEntryError {
+	pub(crate) message: &'static str,
+}
+
+impl std::error::Error for ParseLogEntryError {}
+
+impl std::fmt::Display for ParseLogEntryError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.message)
+	}
+}
+
+impl From<ParseLogEntryError> for std::io::Error {
+	fn from(value: ParseLogEntryError) -> Self {
+		std::io::Error::new(std::io::ErrorKind::InvalidData, value.to_string())
+	}
+}
+
+impl From<ParseFrameError> for ParseLogEntryError {
+	fn from(value: ParseFrameError) -> Self {
+		Self { message: value.message }
+	}
+}
+
+/// Failed to parse a CAN frame.
+#[derive(Debug, Clone)]
+pub struct ParseFrameError {
+	pub(crate) message: &'static str,
+}
+
+impl std::error::Error for ParseFrameError {}
+
+impl std::fmt::Display for ParseFrameError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.message)
+	}
+}
+
+/// Failed to parse a CAN filter or error mask.
+#[derive(Debug, Clone)]
+pub struct ParseFilterError {
+	pub(crate) message: &'static str,
+}
+
+impl std::error::Error for ParseFilterError {}
+
+impl std::fmt::Display for ParseFilterError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.message)
+	}
+}
+
+/// Failed to reunite the halves of a [`tokio::CanSocket`][crate::tokio::CanSocket] because they belong to different sockets.
+///
+/// The error holds both halves, so they can be used again.
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "tokio")))]
+#[derive(Debug)]
+pub struct ReuniteError(pub crate::tokio::OwnedReadHalf, pub crate::tokio::OwnedWriteHalf);
+
+#[cfg(feature = "tokio")]
+impl std::error::Error for ReuniteError {}
+
+#[cfg(feature = "tokio")]
+impl std::fmt::Display for ReuniteError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("tried to reunite halves that belong to different sockets")
+	}
+}
@@ -0,0 +1,553 @@
+//! Types for decoding CAN error frames.
+//!
+//! Error frames are generated by the CAN driver to report problems on the bus or in the CAN controller.
+//! A socket only receives error frames for the error classes enabled with
+//! [`CanSocket::set_error_mask()`][crate::CanSocket::set_error_mask].
+//!
+//! Received error frames are reported as [`AnyCanFrame::Error`][crate::AnyCanFrame::Error]
+//! by [`CanSocket::recv_any()`][crate::CanSocket::recv_any] and related functions.
+
+/// A CAN error frame generated by the CAN driver.
+///
+/// Each error frame can report multiple error classes at the same time.
+/// Use the accessor functions to check for specific errors and to decode the details.
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct CanErrorFrame {
+	pub(crate) inner: crate::sys::CanErrorFrame,
+}
+
+impl CanErrorFrame {
+	/// Get the error classes reported by the frame.
+	#[inline]
+	pub fn error_class(&self) -> CanErrorMask {
+		CanErrorMask::from_bits(self.inner.class())
+	}
+
+	/// Get the raw data of the frame.
+	///
+	/// The meaning of the bytes depends on the reported error classes.
+	#[inline]
+	pub fn data(&self) -> [u8; 8] {
+		self.inner.data()
+	}
+
+	/// Check if the frame reports a transmission timeout.
+	#[inline]
+	pub fn is_tx_timeout(&self) -> bool {
+		self.error_class().contains(CanErrorMask::TX_TIMEOUT)
+	}
+
+	/// Get the bit position at which arbitration was lost, if the frame reports lost arbitration.
+	///
+	/// A bit position of 0 means the position is unspecified.
+	#[inline]
+	pub fn lost_arbitration_bit(&self) -> Option<u8> {
+		self.inner.lost_arbitration_bit()
+	}
+
+	/// Get the status flags of the CAN controller, if the frame reports controller problems.
+	#[inline]
+	pub fn controller_status(&self) -> Option<ControllerStatus> {
+		self.inner.controller_status()
+	}
+
+	/// Get the error state of the CAN controller, if the frame reports it.
+	///
+	/// This is derived from the bus-off flag and the controller status.
+	#[inline]
+	pub fn controller_state(&self) -> Option<ControllerState> {
+		if self.is_bus_off() {
+			return Some(ControllerState::BusOff);
+		}
+		let status = self.controller_status()?;
+		if status.rx_passive || status.tx_passive {
+			Some(ControllerState::ErrorPassive)
+		} else if status.rx_warning || status.tx_warning {
+			Some(ControllerState::ErrorWarning)
+		} else if status.active {
+			Some(ControllerState::ErrorActive)
+		} else {
+			None
+		}
+	}
+
+	/// Get the details of a protocol violation, if the frame reports one.
+	#[inline]
+	pub fn protocol_violation(&self) -> Option<ProtocolViolation> {
+		self.inner.protocol_violation()
+	}
+
+	/// Get the status of the CAN transceiver, if the frame reports transceiver problems.
+	#[inline]
+	pub fn transceiver_status(&self) -> Option<TransceiverStatus> {
+		self.inner.transceiver_status()
+	}
+
+	/// Check if the frame reports that a transmitted frame was not acknowledged.
+	///
+	/// This usually means that there are no other active nodes on the bus.
+	#[inline]
+	pub fn is_no_ack(&self) -> bool {
+		self.error_class().contains(CanErrorMask::NO_ACK)
+	}
+
+	/// Check if the frame reports that the controller entered the bus-off state.
+	#[inline]
+	pub fn is_bus_off(&self) -> bool {
+		self.error_class().contains(CanErrorMask::BUS_OFF)
+	}
+
+	/// Check if the frame reports a bus error.
+	#[inline]
+	pub fn is_bus_error(&self) -> bool {
+		self.error_class().contains(CanErrorMask::BUS_ERROR)
+	}
+
+	/// Check if the frame reports that the controller was restarted.
+	#[inline]
+	pub fn is_restarted(&self) -> bool {
+		self.error_class().contains(CanErrorMask::RESTARTED)
+	}
+
+	/// Get the TX and RX error counters of the controller, if the frame reports them.
+	#[inline]
+	pub fn error_counters(&self) -> Option<ErrorCounters> {
+		self.inner.error_counters()
+	}
+}
+
+impl std::fmt::Debug for CanErrorFrame {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CanErrorFrame")
+			.field("error_class", &self.error_class())
+			.field("data", &format_args!("{:02X?}", self.data()))
+			.finish()
+	}
+}
+
+/// A set of error classes.
+///
+/// Used to select which error frames a socket receives with [`CanSocket::set_error_mask()`][crate::CanSocket::set_error_mask],
+/// and to report the error classes of a [`CanErrorFrame`].
+///
+/// Error classes can be combined with the `|` operator.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct CanErrorMask {
+	bits: u32,
+}
+
+impl CanErrorMask {
+	/// No error classes.
+	pub const NONE: Self = Self::from_bits(0);
+
+	/// Transmission timeout.
+	pub const TX_TIMEOUT: Self = Self::from_bits(0x0001);
+
+	/// Lost arbitration.
+	pub const LOST_ARBITRATION: Self = Self::from_bits(0x0002);
+
+	/// Controller problems.
+	pub const CONTROLLER: Self = Self::from_bits(0x0004);
+
+	/// Protocol violations.
+	pub const PROTOCOL: Self = Self::from_bits(0x0008);
+
+	/// Transceiver status.
+	pub const TRANSCEIVER: Self = Self::from_bits(0x0010);
+
+	/// No acknowledgement received after transmission.
+	pub const NO_ACK: Self = Self::from_bits(0x0020);
+
+	/// Bus-off.
+	pub const BUS_OFF: Self = Self::from_bits(0x0040);
+
+	/// Bus error.
+	pub const BUS_ERROR: Self = Self::from_bits(0x0080);
+
+	/// Controller restarted.
+	pub const RESTARTED: Self = Self::from_bits(0x0100);
+
+	/// TX and RX error counters.
+	pub const COUNTERS: Self = Self::from_bits(0x0200);
+
+	/// All error classes.
+	pub const ALL: Self = Self::from_bits(0x1FFF_FFFF);
+
+	/// Create an error mask from the raw bits used by the kernel.
+	///
+	/// Bits outside of the valid range are discarded.
+	#[inline]
+	pub const fn from_bits(bits: u32) -> Self {
+		Self { bits: bits & 0x1FFF_FFFF }
+	}
+
+	/// Get the raw bits of the error mask.
+	#[inline]
+	pub const fn bits(self) -> u32 {
+		self.bits
+	}
+
+	/// Check if all error classes in `other` are also in `self`.
+	#[inline]
+	pub const fn contains(self, other: Self) -> bool {
+		self.bits & other.bits == other.bits
+	}
+
+	/// Check if the mask is empty.
+	#[inline]
+	pub const fn is_empty(self) -> bool {
+		self.bits == 0
+	}
+}
+
+impl std::ops::BitOr for CanErrorMask {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self::Output {
+		Self::from_bits(self.bits | rhs.bits)
+	}
+}
+
+impl std::ops::BitOrAssign for CanErrorMask {
+	fn bitor_assign(&mut self, rhs: Self) {
+		*self = *self | rhs
+	}
+}
+
+impl std::ops::BitAnd for CanErrorMask {
+	type Output = Self;
+
+	fn bitand(self, rhs: Self) -> Self::Output {
+		Self::from_bits(self.bits & rhs.bits)
+	}
+}
+
+impl std::ops::BitAndAssign for CanErrorMask {
+	fn bitand_assign(&mut self, rhs: Self) {
+		*self = *self & rhs
+	}
+}
+
+impl std::fmt::Debug for CanErrorMask {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		const NAMES: [(CanErrorMask, &str); 10] = [
+			(CanErrorMask::TX_TIMEOUT, "TX_TIMEOUT"),
+			(CanErrorMask::LOST_ARBITRATION, "LOST_ARBITRATION"),
+			(CanErrorMask::CONTROLLER, "CONTROLLER"),
+			(CanErrorMask::PROTOCOL, "PROTOCOL"),
+			(CanErrorMask::TRANSCEIVER, "TRANSCEIVER"),
+			(CanErrorMask::NO_ACK, "NO_ACK"),
+			(CanErrorMask::BUS_OFF, "BUS_OFF"),
+			(CanErrorMask::BUS_ERROR, "BUS_ERROR"),
+			(CanErrorMask::RESTARTED, "RESTARTED"),
+			(CanErrorMask::COUNTERS, "COUNTERS"),
+		];
+
+		let mut remaining = self.bits;
+		let mut first = true;
+		for (flag, name) in NAMES {
+			if self.contains(flag) {
+				if !first {
+					f.write_str(" | ")?;
+				}
+				f.write_str(name)?;
+				remaining &= !flag.bits;
+				first = false;
+			}
+		}
+		if remaining != 0 || first {
+			if !first {
+				f.write_str(" | ")?;
+			}
+			write!(f, "0x{remaining:X}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Format the mask in the syntax used by `candump`, like `#00000004`.
+impl std::fmt::Display for CanErrorMask {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "#{:08X}", self.bits)
+	}
+}
+
+/// Parse a mask in the syntax used by `candump`, like `#FFFFFFFF`.
+///
+/// Bits outside of the valid range are discarded.
+impl std::str::FromStr for CanErrorMask {
+	type Err = crate::error::ParseFilterError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		crate::syntax::parse_error_mask(input)
+	}
+}
+
+/// The error state of a CAN controller.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ControllerState {
+	/// The controller is error active: both error counters are below 96.
+	ErrorActive,
+
+	/// One of the error counters reached the warning level (96).
+	ErrorWarning,
+
+	/// One of the error counters reached the error passive level (128).
+	ErrorPassive,
+
+	/// The controller is in the bus-off state and does not participate in bus traffic.
+	BusOff,
+}
+
+/// Status flags reported by the CAN controller.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ControllerStatus {
+	/// The RX buffer of the controller overflowed.
+	pub rx_overflow: bool,
+
+	/// The TX buffer of the controller overflowed.
+	pub tx_overflow: bool,
+
+	/// The RX error counter reached the warning level.
+	pub rx_warning: bool,
+
+	/// The TX error counter reached the warning level.
+	pub tx_warning: bool,
+
+	/// The RX error counter reached the error passive level.
+	pub rx_passive: bool,
+
+	/// The TX error counter reached the error passive level.
+	pub tx_passive: bool,
+
+	/// The controller recovered to the error active state.
+	pub active: bool,
+}
+
+/// The TX and RX error counters of a CAN controller.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ErrorCounters {
+	/// The transmit error counter.
+	pub tx: u8,
+
+	/// The receive error counter.
+	pub rx: u8,
+}
+
+/// Details of a protocol violation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ProtocolViolation {
+	/// The type of the protocol violation.
+	pub kind: ProtocolViolationKind,
+
+	/// The location in the frame where the violation occurred.
+	pub location: ProtocolViolationLocation,
+}
+
+/// The type of a protocol violation.
+///
+/// Multiple flags can be set at the same time.
+/// If no flags are set, the type is unspecified.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ProtocolViolationKind {
+	/// Single bit error.
+	pub bit: bool,
+
+	/// Frame format error.
+	pub form: bool,
+
+	/// Bit stuffing error.
+	pub stuff: bool,
+
+	/// Unable to send a dominant bit.
+	pub bit0: bool,
+
+	/// Unable to send a recessive bit.
+	pub bit1: bool,
+
+	/// Bus overload.
+	pub overload: bool,
+
+	/// Active error announcement.
+	pub active: bool,
+
+	/// The error occurred during transmission.
+	pub tx: bool,
+}
+
+/// The location in a frame where a protocol violation occurred.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ProtocolViolationLocation {
+	/// The location is unspecified.
+	Unspecified,
+
+	/// Start of frame.
+	StartOfFrame,
+
+	/// ID bits 28 to 21 (standard ID bits 10 to 3).
+	Id28To21,
+
+	/// ID bits 20 to 18 (standard ID bits 2 to 0).
+	Id20To18,
+
+	/// Substitute RTR bit (standard RTR bit for standard frames).
+	SubstituteRtr,
+
+	/// Identifier extension bit.
+	IdentifierExtension,
+
+	/// ID bits 17 to 13.
+	Id17To13,
+
+	/// ID bits 12 to 5.
+	Id12To5,
+
+	/// ID bits 4 to 0.
+	Id4To0,
+
+	/// RTR bit.
+	Rtr,
+
+	/// Reserved bit 1.
+	Reserved1,
+
+	/// Reserved bit 0.
+	Reserved0,
+
+	/// Data length code.
+	DataLengthCode,
+
+	/// Data section.
+	Data,
+
+	/// CRC sequence.
+	CrcSequence,
+
+	/// CRC delimiter.
+	CrcDelimiter,
+
+	/// ACK slot.
+	AckSlot,
+
+	/// ACK delimiter.
+	AckDelimiter,
+
+	/// End of frame.
+	EndOfFrame,
+
+	/// Intermission.
+	Intermission,
+
+	/// A location code that is not known by this library.
+	Other(u8),
+}
+
+/// The status of a CAN transceiver.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TransceiverStatus {
+	/// The status is unspecified.
+	Unspecified,
+
+	/// No wire connected to CAN high.
+	CanHighNoWire,
+
+	/// CAN high is shorted to the battery voltage.
+	CanHighShortToBattery,
+
+	/// CAN high is shorted to the supply voltage.
+	CanHighShortToVcc,
+
+	/// CAN high is shorted to ground.
+	CanHighShortToGround,
+
+	/// No wire connected to CAN low.
+	CanLowNoWire,
+
+	/// CAN low is shorted to the battery voltage.
+	CanLowShortToBattery,
+
+	/// CAN low is shorted to the supply voltage.
+	CanLowShortToVcc,
+
+	/// CAN low is shorted to ground.
+	CanLowShortToGround,
+
+	/// CAN low is shorted to CAN high.
+	CanLowShortToCanHigh,
+
+	/// A status code that is not known by this library.
+	Other(u8),
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	fn error_frame(class: CanErrorMask, data: [u8; 8]) -> CanErrorFrame {
+		CanErrorFrame {
+			inner: crate::sys::CanErrorFrame::from_raw(class.bits(), data),
+		}
+	}
+
+	#[test]
+	fn controller_problems() {
+		let frame = error_frame(CanErrorMask::CONTROLLER | CanErrorMask::COUNTERS, [0, 0x08 | 0x20, 0, 0, 0, 0, 130, 12]);
+		assert!(frame.error_class() == CanErrorMask::CONTROLLER | CanErrorMask::COUNTERS);
+		let_assert!(Some(status) = frame.controller_status());
+		assert!(status.tx_warning);
+		assert!(status.tx_passive);
+		assert!(!status.rx_warning);
+		assert!(frame.controller_state() == Some(ControllerState::ErrorPassive));
+		assert!(frame.error_counters() == Some(ErrorCounters { tx: 130, rx: 12 }));
+		assert!(let None = frame.protocol_violation());
+		assert!(!frame.is_bus_off());
+	}
+
+	#[test]
+	fn bus_off() {
+		let frame = error_frame(CanErrorMask::BUS_OFF, [0; 8]);
+		assert!(frame.is_bus_off());
+		assert!(let None = frame.controller_status());
+		assert!(frame.controller_state() == Some(ControllerState::BusOff));
+	}
+
+	#[test]
+	fn protocol_violation() {
+		let frame = error_frame(CanErrorMask::PROTOCOL | CanErrorMask::BUS_ERROR, [0, 0, 0x04 | 0x80, 0x19, 0, 0, 0, 0]);
+		assert!(frame.is_bus_error());
+		let_assert!(Some(violation) = frame.protocol_violation());
+		assert!(violation.kind.stuff);
+		assert!(violation.kind.tx);
+		assert!(!violation.kind.form);
+		assert!(violation.location == ProtocolViolationLocation::AckSlot);
+	}
+
+	#[test]
+	fn lost_arbitration_and_transceiver() {
+		let frame = error_frame(CanErrorMask::LOST_ARBITRATION | CanErrorMask::TRANSCEIVER | CanErrorMask::NO_ACK, [7, 0, 0, 0, 0x70, 0, 0, 0]);
+		assert!(frame.lost_arbitration_bit() == Some(7));
+		assert!(frame.transceiver_status() == Some(TransceiverStatus::CanLowShortToGround));
+		assert!(frame.is_no_ack());
+		assert!(!frame.is_tx_timeout());
+	}
+
+	#[test]
+	fn error_mask_debug() {
+		assert!(format!("{:?}", CanErrorMask::NONE) == "0x0");
+		assert!(format!("{:?}", CanErrorMask::BUS_OFF | CanErrorMask::NO_ACK) == "NO_ACK | BUS_OFF");
+		assert!(format!("{:?}", CanErrorMask::BUS_OFF | CanErrorMask::from_bits(0x1000)) == "BUS_OFF | 0x1000");
+	}
+
+	#[test]
+	fn parse_and_format_mask() {
+		let_assert!(Ok(mask) = "#00000024".parse::<CanErrorMask>());
+		assert!(mask == CanErrorMask::CONTROLLER | CanErrorMask::NO_ACK);
+		assert!(mask.to_string() == "#00000024");
+		let_assert!(Ok(mask) = "#FFFFFFFF".parse::<CanErrorMask>());
+		assert!(mask == CanErrorMask::ALL);
+		assert!(let Err(_) = "FFFFFFFF".parse::<CanErrorMask>());
+		assert!(let Err(_) = "#".parse::<CanErrorMask>());
+	}
+}
@@ -0,0 +1,436 @@
+use crate::{CanData, CanId};
+use crate::error;
+
+/// A CAN FD frame as transmitted over a CAN socket.
+///
+/// CAN FD frames can carry up to 64 bytes of data.
+/// Only a limited set of data lengths can be encoded in a CAN FD frame:
+/// 0 to 8, 12, 16, 20, 24, 32, 48 or 64 bytes.
+/// When constructing a frame, the data is padded with zeroes to the next valid length.
+///
+/// CAN FD frames can not be remote transmission requests (RTR frames).
+///
+/// To send or receive CAN FD frames, you have to enable CAN FD frames on the socket
+/// with [`CanSocket::set_fd_frames()`][crate::CanSocket::set_fd_frames].
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct CanFdFrame {
+	pub(crate) inner: crate::sys::CanFdFrame,
+}
+
+impl CanFdFrame {
+	/// Create a new CAN FD frame with the given CAN ID and data payload.
+	///
+	/// If the length of the data is not a valid CAN FD data length,
+	/// it is padded with zeroes to the next valid length.
+	///
+	/// To create a new CAN FD frame with a potentially invalid ID or data payload,
+	/// use [`Self::try_new()`].
+	#[inline]
+	pub fn new(id: impl Into<CanId>, data: impl Into<CanFdData>) -> Self {
+		Self {
+			inner: crate::sys::CanFdFrame::new(id, &data.into())
+		}
+	}
+
+	/// Create a new CAN FD frame with the given CAN ID and data payload.
+	///
+	/// Will report an error if the ID or data is invalid.
+	///
+	/// You should normally prefer [`Self::new()`] if you can guarantee that the ID and data are valid.
+	#[inline]
+	pub fn try_new<Id, Data>(id: Id, data: Data) -> Result<Self, error::TryNewCanFrameError>
+	where
+		Id: TryInto<CanId>,
+		error::TryNewCanFrameError: From<Id::Error>,
+		Data: TryInto<CanFdData>,
+		error::TryNewCanFrameError: From<Data::Error>,
+	{
+		Ok(Self::new(id.try_into()?, data.try_into()?))
+	}
+
+	/// Get the CAN ID of the frame.
+	#[inline]
+	pub fn id(&self) -> CanId {
+		self.inner.id()
+	}
+
+	/// Get the data of the frame.
+	///
+	/// The length of the data is always a valid CAN FD data length.
+	#[inline]
+	pub fn data(&self) -> CanFdData {
+		self.inner.data()
+	}
+
+	/// Get the data length code of the frame.
+	///
+	/// For CAN FD frames, the data length code is fully determined by the length of the data.
+	/// Use [`Self::data_length_code_to_len()`] to convert a data length code to a data length.
+	#[inline]
+	pub fn data_length_code(&self) -> u8 {
+		self.inner.data_length_code()
+	}
+
+	/// Get the data length in bytes for a CAN FD data length code.
+	///
+	/// Returns `None` if the data length code is larger than 15.
+	#[inline]
+	pub const fn data_length_code_to_len(dlc: u8) -> Option<u8> {
+		crate::sys::fd_dlc_to_len(dlc)
+	}
+
+	/// Check if the bit rate switch (BRS) flag is set.
+	///
+	/// If the flag is set, the data phase of the frame is transmitted with the data bit rate of the CAN controller.
+	#[inline]
+	pub fn bit_rate_switch(&self) -> bool {
+		self.inner.bit_rate_switch()
+	}
+
+	/// Set or clear the bit rate switch (BRS) flag.
+	///
+	/// If the flag is set, the data phase of the frame is transmitted with the data bit rate of the CAN controller.
+	#[inline]
+	pub fn set_bit_rate_switch(&mut self, enable: bool) {
+		self.inner.set_bit_rate_switch(enable)
+	}
+
+	/// Create a copy of the frame with the bit rate switch (BRS) flag set or cleared.
+	#[inline]
+	#[must_use = "this function returns a new frame, it does not modify self"]
+	pub fn with_bit_rate_switch(mut self, enable: bool) -> Self {
+		self.set_bit_rate_switch(enable);
+		self
+	}
+
+	/// Check if the error state indicator (ESI) flag is set.
+	///
+	/// The flag is set by the transmitting node if it is in the error passive state.
+	#[inline]
+	pub fn error_state_indicator(&self) -> bool {
+		self.inner.error_state_indicator()
+	}
+
+	/// Set or clear the error state indicator (ESI) flag.
+	///
+	/// Note that the CAN controller may override the flag based on its own error state.
+	#[inline]
+	pub fn set_error_state_indicator(&mut self, enable: bool) {
+		self.inner.set_error_state_indicator(enable)
+	}
+
+	/// Create a copy of the frame with the error state indicator (ESI) flag set or cleared.
+	#[inline]
+	#[must_use = "this function returns a new frame, it does not modify self"]
+	pub fn with_error_state_indicator(mut self, enable: bool) -> Self {
+		self.set_error_state_indicator(enable);
+		self
+	}
+}
+
+impl std::fmt::Debug for CanFdFrame {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CanFdFrame")
+			.field("id", &format_args!("{:?}", self.id()))
+			.field("bit_rate_switch", &self.bit_rate_switch())
+			.field("error_state_indicator", &self.error_state_indicator())
+			.field("data_length_code", &self.data_length_code())
+			.field("data", &format_args!("{:02X?}", self.data()))
+			.finish()
+	}
+}
+
+/// Format the frame in the syntax used by `cansend` and `candump`, like `123##1DEADBEEF`.
+impl std::fmt::Display for CanFdFrame {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		crate::syntax::write_fd_frame(f, self)
+	}
+}
+
+/// Parse a frame in the syntax used by `cansend` and `candump`, like `123##1DEADBEEF`.
+///
+/// The digit after `##` holds the CAN FD flags: `1` for bit rate switch and `2` for the error state indicator.
+impl std::str::FromStr for CanFdFrame {
+	type Err = error::ParseFrameError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		match crate::syntax::parse_frame(input)? {
+			crate::AnyCanFrame::Fd(frame) => Ok(frame),
+			_ => Err(error::ParseFrameError { message: "expected a CAN FD frame" }),
+		}
+	}
+}
+
+/// The data payload of a CAN FD frame.
+///
+/// Can hold up to 64 bytes.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CanFdData {
+	pub(crate) data: [u8; 64],
+	pub(crate) len: u8,
+}
+
+impl CanFdData {
+	/// Construct a CAN FD data object from a supported fixed size array.
+	///
+	/// Also allows construction from any other type if it implements [`Into<CanFdData>`].
+	pub fn new(data: impl Into<CanFdData>) -> Self {
+		data.into()
+	}
+
+	/// Construct a CAN FD data object from a supported fixed size array.
+	///
+	/// Also allows construction from any other type if it implements [`Into<CanFdData>`].
+	pub fn try_new<E>(data: impl TryInto<CanFdData, Error = E>) -> Result<Self, E> {
+		data.try_into()
+	}
+
+	/// Get the data as a slice of bytes.
+	#[inline]
+	pub fn as_slice(&self) -> &[u8] {
+		&self.data[..self.len.into()]
+	}
+
+	/// Get the data as a mutable slice of bytes.
+	#[inline]
+	pub fn as_slice_mut(&mut self) -> &mut [u8] {
+		&mut self.data[..self.len.into()]
+	}
+}
+
+impl std::fmt::Debug for CanFdData {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		std::fmt::Debug::fmt(self.as_slice(), f)
+	}
+}
+
+impl std::ops::Deref for CanFdData {
+	type Target = [u8];
+
+	fn deref(&self) -> &Self::Target {
+		self.as_slice()
+	}
+}
+
+impl std::ops::DerefMut for CanFdData {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.as_slice_mut()
+	}
+}
+
+impl std::borrow::Borrow<[u8]> for CanFdData {
+	fn borrow(&self) -> &[u8] {
+		self.as_slice()
+	}
+}
+
+impl std::borrow::BorrowMut<[u8]> for CanFdData {
+	fn borrow_mut(&mut self) -> &mut [u8] {
+		self.as_slice_mut()
+	}
+}
+
+impl AsRef<[u8]> for CanFdData {
+	fn as_ref(&self) -> &[u8] {
+		self.as_slice()
+	}
+}
+
+impl AsMut<[u8]> for CanFdData {
+	fn as_mut(&mut self) -> &mut [u8] {
+		self.as_slice_mut()
+	}
+}
+
+impl PartialEq<[u8]> for CanFdData {
+	fn eq(&self, other: &[u8]) -> bool {
+		self.as_slice() == other
+	}
+}
+
+impl PartialEq<CanFdData> for [u8] {
+	fn eq(&self, other: &CanFdData) -> bool {
+		self == other.as_slice()
+	}
+}
+
+macro_rules! impl_from_array {
+	($($n:literal)*) => {
+		$(
+			impl From<[u8; $n]> for CanFdData {
+				fn from(value: [u8; $n]) -> Self {
+					let mut data = [0; 64];
+					data[..value.len()].copy_from_slice(&value);
+					Self {
+						data,
+						len: $n,
+					}
+				}
+			}
+
+			impl<'a> From<&'a [u8; $n]> for CanFdData {
+				fn from(value: &'a [u8; $n]) -> Self {
+					let mut data = [0; 64];
+					data[..value.len()].copy_from_slice(value);
+					Self {
+						data,
+						len: $n,
+					}
+				}
+			}
+
+			impl TryFrom<CanFdData> for [u8; $n] {
+				type Error = core::array::TryFromSliceError;
+
+				fn try_from(other: CanFdData) -> Result<Self, Self::Error> {
+					other.as_slice().try_into()
+				}
+			}
+
+			impl<'a> TryFrom<&'a CanFdData> for [u8; $n] {
+				type Error = core::array::TryFromSliceError;
+
+				fn try_from(other: &'a CanFdData) -> Result<Self, Self::Error> {
+					other.as_slice().try_into()
+				}
+			}
+
+			impl PartialEq<[u8; $n]> for CanFdData {
+				fn eq(&self, other: &[u8; $n]) -> bool {
+					if self.len == $n {
+						&self.data[..$n] == other
+					} else {
+						false
+					}
+				}
+			}
+
+			impl PartialEq<CanFdData> for [u8; $n] {
+				fn eq(&self, other: &CanFdData) -> bool {
+					other == self
+				}
+			}
+		)*
+	}
+}
+
+impl_from_array!(
+	0 1 2 3 4 5 6 7 8 9
+	10 11 12 13 14 15 16 17 18 19
+	20 21 22 23 24 25 26 27 28 29
+	30 31 32 33 34 35 36 37 38 39
+	40 41 42 43 44 45 46 47 48 49
+	50 51 52 53 54 55 56 57 58 59
+	60 61 62 63 64
+);
+
+impl From<CanData> for CanFdData {
+	fn from(value: CanData) -> Self {
+		let mut data = [0; 64];
+		data[..value.len()].copy_from_slice(&value);
+		Self {
+			data,
+			len: value.len,
+		}
+	}
+}
+
+impl TryFrom<&[u8]> for CanFdData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+		if value.len() > 64 {
+			Err(error::TryIntoCanDataError {
+				len: value.len(),
+				min_len: 0,
+				max_len: 64,
+			})
+		} else {
+			let mut data = [0; 64];
+			data[..value.len()].copy_from_slice(value);
+			Ok(Self {
+				data,
+				len: value.len() as u8,
+			})
+		}
+	}
+}
+
+impl TryFrom<&Vec<u8>> for CanFdData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &Vec<u8>) -> Result<Self, Self::Error> {
+		value.as_slice().try_into()
+	}
+}
+
+impl TryFrom<&Box<[u8]>> for CanFdData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &Box<[u8]>) -> Result<Self, Self::Error> {
+		let value: &[u8] = value;
+		value.try_into()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::can_id;
+
+	#[test]
+	fn can_fd_frame_is_copy() {
+		let frame = CanFdFrame::new(1u8, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+		let copy = frame;
+		assert!(copy.id() == can_id!(1));
+		assert!(copy.data() == [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+		assert!(copy.data_length_code() == 9);
+	}
+
+	#[test]
+	fn data_is_padded_to_valid_length() {
+		let frame = CanFdFrame::new(1u8, [1; 9]);
+		assert!(frame.data() == [1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0]);
+		assert!(frame.data_length_code() == 9);
+
+		let frame = CanFdFrame::new(1u8, [2; 33]);
+		assert!(frame.data().len() == 48);
+		assert!(frame.data()[..33] == [2; 33]);
+		assert!(frame.data()[33..] == [0; 15]);
+		assert!(frame.data_length_code() == 14);
+	}
+
+	#[test]
+	fn data_length_code_to_len() {
+		let expected = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+		for (dlc, len) in expected.into_iter().enumerate() {
+			assert!(CanFdFrame::data_length_code_to_len(dlc as u8) == Some(len));
+			let_assert!(Ok(frame) = CanFdFrame::try_new(1u8, &vec![0; len.into()]));
+			assert!(frame.data_length_code() == dlc as u8);
+		}
+		assert!(let None = CanFdFrame::data_length_code_to_len(16));
+	}
+
+	#[test]
+	fn flags() {
+		let frame = CanFdFrame::new(1u8, [1, 2, 3]);
+		assert!(!frame.bit_rate_switch());
+		assert!(!frame.error_state_indicator());
+
+		let frame = frame.with_bit_rate_switch(true);
+		assert!(frame.bit_rate_switch());
+		assert!(!frame.error_state_indicator());
+
+		let frame = frame.with_error_state_indicator(true).with_bit_rate_switch(false);
+		assert!(!frame.bit_rate_switch());
+		assert!(frame.error_state_indicator());
+	}
+
+	#[test]
+	fn can_fd_data_too_long() {
+		let_assert!(Err(e) = CanFdData::try_from([0; 65].as_slice()));
+		assert!(e.to_string() == "data to large for CAN frame, expected at most 64 bytes, got 65");
+	}
+}
@@ -1,4 +1,4 @@
-use crate::{sys, StandardId, ExtendedId, CanFrame, CanId};
+use crate::{sys, StandardId, ExtendedId, CanFdFrame, CanFrame, CanId};
 
 /// A CAN filter.
 ///
@@ -7,7 +7,7 @@ use crate::{sys, StandardId, ExtendedId, CanFrame, CanId};
 #[repr(transparent)]
 #[derive(Copy, Clone)]
 pub struct CanFilter {
-	filter: crate::sys::CanFilter,
+	pub(crate) filter: crate::sys::CanFilter,
 }
 
 impl CanFilter {
@@ -134,23 +134,175 @@ impl CanFilter {
 		self.filter.is_inverted()
 	}
 
+	/// Get the ID of the filter.
+	///
+	/// Only the bits that are set in the [mask][Self::id_mask()] are compared against the ID of a frame.
+	#[inline]
+	pub const fn id(self) -> u32 {
+		self.filter.id()
+	}
+
+	/// Get the ID mask of the filter.
+	#[inline]
+	pub const fn id_mask(self) -> u32 {
+		self.filter.id_mask()
+	}
+
+	/// Check if the filter matches frames with a standard ID.
+	///
+	/// Note that the result is not affected by [`Self::is_inverted()`].
+	#[inline]
+	pub const fn matches_standard_frames(self) -> bool {
+		self.filter.matches_standard_frames()
+	}
+
+	/// Check if the filter matches frames with an extended ID.
+	///
+	/// Note that the result is not affected by [`Self::is_inverted()`].
+	#[inline]
+	pub const fn matches_extended_frames(self) -> bool {
+		self.filter.matches_extended_frames()
+	}
+
+	/// Check if the filter matches data frames.
+	///
+	/// Note that the result is not affected by [`Self::is_inverted()`].
+	#[inline]
+	pub const fn matches_data_frames(self) -> bool {
+		self.filter.matches_data_frames()
+	}
+
+	/// Check if the filter matches RTR frames.
+	///
+	/// Note that the result is not affected by [`Self::is_inverted()`].
+	#[inline]
+	pub const fn matches_rtr_frames(self) -> bool {
+		self.filter.matches_rtr_frames()
+	}
+
 	/// Test if a frame matches the filter.
 	#[inline]
 	pub const fn test(&self, frame: &CanFrame) -> bool {
 		self.filter.test(&frame.inner)
 	}
+
+	/// Test if a CAN FD frame matches the filter.
+	#[inline]
+	pub const fn test_fd(&self, frame: &CanFdFrame) -> bool {
+		self.filter.test_fd(&frame.inner)
+	}
 }
 
 impl std::fmt::Debug for CanFilter {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("CanFilter")
-			.field("id", &format_args!("{:02X}", self.filter.id()))
-			.field("mask", &format_args!("{:02X}", self.filter.id_mask()))
-			.field("extended_frames", &self.filter.matches_extended_frames())
-			.field("standard_frames", &self.filter.matches_standard_frames())
-			.field("data_frames", &self.filter.matches_data_frames())
-			.field("rtr_frames", &self.filter.matches_rtr_frames())
-			.field("inverted", &self.filter.is_inverted())
+			.field("id", &format_args!("{:02X}", self.id()))
+			.field("mask", &format_args!("{:02X}", self.id_mask()))
+			.field("extended_frames", &self.matches_extended_frames())
+			.field("standard_frames", &self.matches_standard_frames())
+			.field("data_frames", &self.matches_data_frames())
+			.field("rtr_frames", &self.matches_rtr_frames())
+			.field("inverted", &self.is_inverted())
 			.finish()
 	}
 }
+
+/// Format the filter in the syntax used by `candump`, like `123:7FF` or `123~7FF` for an inverted filter.
+///
+/// Filters for extended IDs are written with 8 hexadecimal digits.
+impl std::fmt::Display for CanFilter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		crate::syntax::write_filter(f, self)
+	}
+}
+
+/// Parse a filter in the syntax used by `candump`, like `123:7FF` or `123~7FF` for an inverted filter.
+///
+/// An ID with 8 hexadecimal digits creates a filter for extended IDs.
+/// Error masks (`#<mask>`) are not filters: parse them as [`CanErrorMask`][crate::CanErrorMask] instead.
+impl std::str::FromStr for CanFilter {
+	type Err = crate::error::ParseFilterError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		crate::syntax::parse_filter(input)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_frame() {
+		let filter = CanFilter::new(8u8.into()).match_exact_id();
+		assert!(filter.test(&CanFrame::new(8u8, [1])));
+		assert!(!filter.test(&CanFrame::new(9u8, [1])));
+		assert!(filter.test_fd(&CanFdFrame::new(8u8, [1; 12])));
+		assert!(!filter.test_fd(&CanFdFrame::new(9u8, [1; 12])));
+
+		let filter = filter.inverted(true);
+		assert!(!filter.test(&CanFrame::new(8u8, [1])));
+		assert!(filter.test(&CanFrame::new(9u8, [1])));
+	}
+
+	#[test]
+	fn accessors() {
+		let filter = CanFilter::new_standard(0x12.into());
+		assert!(filter.id() == 0x12);
+		assert!(filter.id_mask() == 0);
+		assert!(filter.matches_standard_frames());
+		assert!(filter.matches_extended_frames());
+		assert!(filter.matches_data_frames());
+		assert!(filter.matches_rtr_frames());
+		assert!(!filter.is_inverted());
+
+		let filter = filter.match_exact_id().match_data_only();
+		assert!(filter.id_mask() == 0x1FFF_FFFF);
+		assert!(filter.matches_standard_frames());
+		assert!(!filter.matches_extended_frames());
+		assert!(filter.matches_data_frames());
+		assert!(!filter.matches_rtr_frames());
+
+		let filter = CanFilter::new_extended(0x1234u16.into()).match_frame_format().match_rtr_only();
+		assert!(filter.id() == 0x1234);
+		assert!(!filter.matches_standard_frames());
+		assert!(filter.matches_extended_frames());
+		assert!(!filter.matches_data_frames());
+		assert!(filter.matches_rtr_frames());
+	}
+
+	#[test]
+	fn extended_filter_matches_extended_frames() {
+		let filter = CanFilter::new_extended(0x1234u16.into()).match_exact_id();
+		assert!(filter.test(&CanFrame::new(ExtendedId::from(0x1234u16), [1])));
+		assert!(!filter.test(&CanFrame::new(StandardId::from(0x34u8), [1])));
+		assert!(!filter.test(&CanFrame::new(ExtendedId::from(0x1235u16), [1])));
+	}
+
+	#[test]
+	fn parse_and_format() {
+		let filter: CanFilter = "123:7FF".parse().unwrap();
+		assert!(filter.id() == 0x123);
+		assert!(filter.id_mask() == 0x7FF);
+		assert!(!filter.is_inverted());
+		assert!(filter.test(&CanFrame::new(StandardId::new(0x123).unwrap(), [1])));
+		assert!(!filter.test(&CanFrame::new(StandardId::new(0x124).unwrap(), [1])));
+		assert!(filter.to_string() == "123:7FF");
+
+		let filter: CanFilter = "123~7FF".parse().unwrap();
+		assert!(filter.is_inverted());
+		assert!(!filter.test(&CanFrame::new(StandardId::new(0x123).unwrap(), [1])));
+		assert!(filter.test(&CanFrame::new(StandardId::new(0x124).unwrap(), [1])));
+		assert!(filter.to_string() == "123~7FF");
+
+		let filter: CanFilter = "00001234:1FFFFFFF".parse().unwrap();
+		assert!(filter.id() == 0x1234);
+		assert!(filter.test(&CanFrame::new(ExtendedId::from(0x1234u16), [1])));
+		assert!(filter.to_string() == "00001234:1FFFFFFF");
+
+		for input in ["", "123", "#FFFFFFFF", "800:7FF", "20000000:1FFFFFFF", "123:", "123:XYZ", "X:7FF"] {
+			assert!(let Err(_) = input.parse::<CanFilter>(), "{input:?}");
+		}
+	}
+}
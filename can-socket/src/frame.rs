@@ -128,6 +128,27 @@ impl std::fmt::Debug for CanFrame {
 	}
 }
 
+/// Format the frame in the syntax used by `cansend` and `candump`, like `123#DEADBEEF` or `123#R`.
+impl std::fmt::Display for CanFrame {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		crate::syntax::write_can_frame(f, self)
+	}
+}
+
+/// Parse a frame in the syntax used by `cansend` and `candump`, like `123#DEADBEEF`, `1F334455#R` or `123#R3`.
+///
+/// An ID with 8 hexadecimal digits is parsed as an extended ID.
+impl std::str::FromStr for CanFrame {
+	type Err = error::ParseFrameError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		match crate::syntax::parse_frame(input)? {
+			crate::AnyCanFrame::Classic(frame) => Ok(frame),
+			_ => Err(error::ParseFrameError { message: "expected a classic CAN frame" }),
+		}
+	}
+}
+
 /// The data payload of a CAN frame.
 ///
 /// Can hold up to 8 bytes.
@@ -295,6 +316,8 @@ impl TryFrom<&[u8]> for CanData {
 		if value.len() > 8 {
 			Err(error::TryIntoCanDataError {
 				len: value.len(),
+				min_len: 0,
+				max_len: 8,
 			})
 		} else {
 			let mut data = [0; 8];
@@ -352,4 +375,26 @@ mod test {
 		assert!(CanData::from([1, 2]) != [1]);
 		assert!(CanData::from([1]) != [1, 2]);
 	}
+
+	#[test]
+	fn parse_and_format() {
+		let frame: CanFrame = "123#11223344".parse().unwrap();
+		assert!(frame.id() == can_id!(0x123));
+		assert!(frame.data() == Some(CanData::new([0x11, 0x22, 0x33, 0x44])));
+		assert!(frame.to_string() == "123#11223344");
+
+		let frame: CanFrame = "1F334455#R".parse().unwrap();
+		assert!(frame.id() == can_id!(extended: 0x1F33_4455));
+		assert!(frame.is_rtr());
+		assert!(frame.to_string() == "1F334455#R");
+
+		let frame: CanFrame = "123#R3".parse().unwrap();
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 3);
+		assert!(frame.to_string() == "123#R3");
+
+		for input in ["", "123", "123#1", "123##1DEADBEEF", "20000004#0004"] {
+			assert!(let Err(_) = input.parse::<CanFrame>(), "{input:?}");
+		}
+	}
 }
@@ -25,6 +25,25 @@ impl CanInterface {
 		})
 	}
 
+	/// List all CAN interfaces on the system.
+	///
+	/// This returns all interfaces with the CAN link type,
+	/// including virtual (`vcan`) and tunnel (`vxcan`) interfaces.
+	///
+	/// The list is retrieved with a new [`NetlinkSocket`][crate::netlink::NetlinkSocket].
+	/// Use [`NetlinkSocket::list_interfaces()`][crate::netlink::NetlinkSocket::list_interfaces] to reuse an existing socket.
+	pub fn list() -> std::io::Result<Vec<crate::netlink::CanInterfaceInfo>> {
+		crate::netlink::NetlinkSocket::new()?.list_interfaces()
+	}
+
+	/// Get the controller state, error counters and statistics of the interface.
+	///
+	/// The statistics are retrieved with a new [`NetlinkSocket`][crate::netlink::NetlinkSocket].
+	/// Use [`NetlinkSocket::get_stats()`][crate::netlink::NetlinkSocket::get_stats] to reuse an existing socket.
+	pub fn stats(&self) -> std::io::Result<crate::netlink::CanInterfaceStats> {
+		crate::netlink::NetlinkSocket::new()?.get_stats(self)
+	}
+
 	/// Get the index of the interface.
 	pub fn index(&self) -> u32 {
 		self.inner.index()
@@ -0,0 +1,312 @@
+//! ISO-TP (ISO 15765-2) transport protocol sockets.
+//!
+//! ISO-TP is used to transfer messages larger than a single CAN frame,
+//! for example for diagnostic protocols like UDS (ISO 14229).
+//! The kernel takes care of segmenting and reassembling the messages, and of the flow control.
+//!
+//! This requires the `can-isotp` kernel module (part of the mainline kernel since Linux 5.10).
+
+pub(crate) mod socket;
+pub use socket::IsoTpSocket;
+
+/// The default time between two consecutive frames in nanoseconds.
+const DEFAULT_FRAME_TXTIME: u32 = 50_000;
+
+/// The default padding byte.
+const DEFAULT_PAD_CONTENT: u8 = 0xCC;
+
+/// Flags for the ISO-TP socket options.
+///
+/// Flags can be combined with the `|` operator.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[repr(transparent)]
+pub struct IsoTpFlags {
+	bits: u32,
+}
+
+impl IsoTpFlags {
+	/// No flags.
+	pub const NONE: Self = Self::from_bits(0);
+
+	/// Listen only: do not send flow control frames.
+	pub const LISTEN_MODE: Self = Self::from_bits(0x0001);
+
+	/// Use extended addressing with [`IsoTpOptions::ext_address`].
+	pub const EXTEND_ADDR: Self = Self::from_bits(0x0002);
+
+	/// Pad transmitted frames with [`IsoTpOptions::txpad_content`].
+	pub const TX_PADDING: Self = Self::from_bits(0x0004);
+
+	/// Expect received frames to be padded.
+	pub const RX_PADDING: Self = Self::from_bits(0x0008);
+
+	/// Check the length of padded received frames.
+	pub const CHK_PAD_LEN: Self = Self::from_bits(0x0010);
+
+	/// Check the padding bytes of received frames against [`IsoTpOptions::rxpad_content`].
+	pub const CHK_PAD_DATA: Self = Self::from_bits(0x0020);
+
+	/// Half duplex mode: do not send and receive at the same time.
+	pub const HALF_DUPLEX: Self = Self::from_bits(0x0040);
+
+	/// Ignore the separation time requested by the receiver and use the configured TX separation time instead.
+	pub const FORCE_TXSTMIN: Self = Self::from_bits(0x0080);
+
+	/// Ignore received frames that arrive faster than the configured RX separation time.
+	pub const FORCE_RXSTMIN: Self = Self::from_bits(0x0100);
+
+	/// Use a different extended address for received frames ([`IsoTpOptions::rx_ext_address`]).
+	pub const RX_EXT_ADDR: Self = Self::from_bits(0x0200);
+
+	/// Wait for the transmission to complete before returning from a send call.
+	pub const WAIT_TX_DONE: Self = Self::from_bits(0x0400);
+
+	/// Send single frames to a functional (1-to-N) address.
+	pub const SF_BROADCAST: Self = Self::from_bits(0x0800);
+
+	/// Send consecutive frames to a functional (1-to-N) address without waiting for flow control.
+	pub const CF_BROADCAST: Self = Self::from_bits(0x1000);
+
+	/// Use the block size and separation time from every received flow control frame, instead of only the first one.
+	pub const DYN_FC_PARMS: Self = Self::from_bits(0x2000);
+
+	/// Create flags from the raw bits used by the kernel.
+	#[inline]
+	pub const fn from_bits(bits: u32) -> Self {
+		Self { bits }
+	}
+
+	/// Get the raw bits of the flags.
+	#[inline]
+	pub const fn bits(self) -> u32 {
+		self.bits
+	}
+
+	/// Check if all flags in `other` are also in `self`.
+	#[inline]
+	pub const fn contains(self, other: Self) -> bool {
+		self.bits & other.bits == other.bits
+	}
+
+	/// Check if no flags are set.
+	#[inline]
+	pub const fn is_empty(self) -> bool {
+		self.bits == 0
+	}
+}
+
+impl std::ops::BitOr for IsoTpFlags {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self::Output {
+		Self::from_bits(self.bits | rhs.bits)
+	}
+}
+
+impl std::ops::BitOrAssign for IsoTpFlags {
+	fn bitor_assign(&mut self, rhs: Self) {
+		*self = *self | rhs
+	}
+}
+
+impl std::ops::BitAnd for IsoTpFlags {
+	type Output = Self;
+
+	fn bitand(self, rhs: Self) -> Self::Output {
+		Self::from_bits(self.bits & rhs.bits)
+	}
+}
+
+impl std::ops::BitAndAssign for IsoTpFlags {
+	fn bitand_assign(&mut self, rhs: Self) {
+		*self = *self & rhs
+	}
+}
+
+impl std::fmt::Debug for IsoTpFlags {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		const NAMES: [(IsoTpFlags, &str); 14] = [
+			(IsoTpFlags::LISTEN_MODE, "LISTEN_MODE"),
+			(IsoTpFlags::EXTEND_ADDR, "EXTEND_ADDR"),
+			(IsoTpFlags::TX_PADDING, "TX_PADDING"),
+			(IsoTpFlags::RX_PADDING, "RX_PADDING"),
+			(IsoTpFlags::CHK_PAD_LEN, "CHK_PAD_LEN"),
+			(IsoTpFlags::CHK_PAD_DATA, "CHK_PAD_DATA"),
+			(IsoTpFlags::HALF_DUPLEX, "HALF_DUPLEX"),
+			(IsoTpFlags::FORCE_TXSTMIN, "FORCE_TXSTMIN"),
+			(IsoTpFlags::FORCE_RXSTMIN, "FORCE_RXSTMIN"),
+			(IsoTpFlags::RX_EXT_ADDR, "RX_EXT_ADDR"),
+			(IsoTpFlags::WAIT_TX_DONE, "WAIT_TX_DONE"),
+			(IsoTpFlags::SF_BROADCAST, "SF_BROADCAST"),
+			(IsoTpFlags::CF_BROADCAST, "CF_BROADCAST"),
+			(IsoTpFlags::DYN_FC_PARMS, "DYN_FC_PARMS"),
+		];
+
+		let mut remaining = self.bits;
+		let mut first = true;
+		for (flag, name) in NAMES {
+			if self.contains(flag) {
+				if !first {
+					f.write_str(" | ")?;
+				}
+				f.write_str(name)?;
+				remaining &= !flag.bits;
+				first = false;
+			}
+		}
+		if remaining != 0 || first {
+			if !first {
+				f.write_str(" | ")?;
+			}
+			write!(f, "0x{remaining:X}")?;
+		}
+		Ok(())
+	}
+}
+
+/// General options of an ISO-TP socket (`CAN_ISOTP_OPTS`).
+///
+/// These match the fields of `struct can_isotp_options` used by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct IsoTpOptions {
+	/// Flags to control the behaviour of the socket.
+	pub flags: IsoTpFlags,
+
+	/// The time between two consecutive frames in nanoseconds.
+	pub frame_txtime: u32,
+
+	/// The extended address for transmitted (and received) frames, used with [`IsoTpFlags::EXTEND_ADDR`].
+	pub ext_address: u8,
+
+	/// The padding byte for transmitted frames, used with [`IsoTpFlags::TX_PADDING`].
+	pub txpad_content: u8,
+
+	/// The expected padding byte of received frames, used with [`IsoTpFlags::CHK_PAD_DATA`].
+	pub rxpad_content: u8,
+
+	/// The extended address for received frames, used with [`IsoTpFlags::RX_EXT_ADDR`].
+	pub rx_ext_address: u8,
+}
+
+impl Default for IsoTpOptions {
+	fn default() -> Self {
+		Self {
+			flags: IsoTpFlags::NONE,
+			frame_txtime: DEFAULT_FRAME_TXTIME,
+			ext_address: 0,
+			txpad_content: DEFAULT_PAD_CONTENT,
+			rxpad_content: DEFAULT_PAD_CONTENT,
+			rx_ext_address: 0,
+		}
+	}
+}
+
+/// Flow control options of an ISO-TP socket (`CAN_ISOTP_RECV_FC`).
+///
+/// These are sent to the other side in flow control frames when receiving a segmented message.
+/// They match the fields of `struct can_isotp_fc_options` used by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(C)]
+pub struct FlowControlOptions {
+	/// The block size: the number of consecutive frames to send before waiting for the next flow control frame.
+	///
+	/// A value of 0 means the sender should not wait for more flow control frames.
+	pub bs: u8,
+
+	/// The minimum separation time between consecutive frames.
+	///
+	/// Values 0x00 to 0x7F are milliseconds, values 0xF1 to 0xF9 are 100 to 900 microseconds.
+	pub stmin: u8,
+
+	/// The maximum number of wait frames to send (0 means wait frames are disabled).
+	pub wftmax: u8,
+}
+
+/// Link layer options of an ISO-TP socket (`CAN_ISOTP_LL_OPTS`).
+///
+/// These determine whether classic CAN frames or CAN FD frames are used.
+/// They match the fields of `struct can_isotp_ll_options` used by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct LinkLayerOptions {
+	/// The MTU of the frames: 16 for classic CAN frames or 72 for CAN FD frames.
+	pub mtu: u8,
+
+	/// The maximum data length of transmitted frames: 8 for classic CAN, or 8, 12, 16, 20, 24, 32, 48 or 64 for CAN FD.
+	pub tx_dl: u8,
+
+	/// The flags for transmitted CAN FD frames, like the bitrate switch flag (0x01).
+	pub tx_flags: u8,
+}
+
+impl LinkLayerOptions {
+	/// Link layer options for classic CAN frames (the default).
+	pub const CLASSIC: Self = Self {
+		mtu: 16,
+		tx_dl: 8,
+		tx_flags: 0,
+	};
+
+	/// Link layer options for CAN FD frames with the given maximum data length.
+	///
+	/// If `bitrate_switch` is true, transmitted frames have the bitrate switch flag set.
+	pub const fn fd(tx_dl: u8, bitrate_switch: bool) -> Self {
+		Self {
+			mtu: 72,
+			tx_dl,
+			tx_flags: if bitrate_switch { 0x01 } else { 0x00 },
+		}
+	}
+}
+
+impl Default for LinkLayerOptions {
+	fn default() -> Self {
+		Self::CLASSIC
+	}
+}
+
+/// The configuration of an ISO-TP socket.
+///
+/// The configuration must be applied before the socket is bound,
+/// so it is passed to [`IsoTpSocket::bind_with_config()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct IsoTpConfig {
+	/// The general options.
+	pub options: IsoTpOptions,
+
+	/// The flow control options.
+	pub flow_control: FlowControlOptions,
+
+	/// The link layer options.
+	pub link_layer: LinkLayerOptions,
+
+	/// The separation time between consecutive frames to use instead of the time requested by the receiver.
+	///
+	/// Only used with [`IsoTpFlags::FORCE_TXSTMIN`].
+	pub tx_stmin: Option<std::time::Duration>,
+
+	/// The minimum time between received consecutive frames: frames that arrive faster are ignored.
+	///
+	/// Only used with [`IsoTpFlags::FORCE_RXSTMIN`].
+	pub rx_stmin: Option<std::time::Duration>,
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn option_layout() {
+		assert!(std::mem::size_of::<IsoTpOptions>() == 12);
+		assert!(std::mem::size_of::<FlowControlOptions>() == 3);
+		assert!(std::mem::size_of::<LinkLayerOptions>() == 3);
+	}
+
+	#[test]
+	fn flags_debug() {
+		assert!(format!("{:?}", IsoTpFlags::NONE) == "0x0");
+		assert!(format!("{:?}", IsoTpFlags::TX_PADDING | IsoTpFlags::RX_PADDING) == "TX_PADDING | RX_PADDING");
+	}
+}
@@ -0,0 +1,158 @@
+use crate::{CanId, CanInterface};
+use super::{FlowControlOptions, IsoTpConfig, IsoTpOptions, LinkLayerOptions};
+
+/// A synchronous ISO-TP socket.
+///
+/// Used to send and receive complete ISO-TP messages.
+/// Segmentation, reassembly and flow control are handled by the kernel.
+///
+/// Although the socket is synchronous,
+/// it can be put into non-blocking mode with [`Self::set_nonblocking()`].
+#[repr(transparent)]
+pub struct IsoTpSocket {
+	inner: crate::sys::IsoTpSocket,
+}
+
+impl std::fmt::Debug for IsoTpSocket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("IsoTpSocket");
+		#[cfg(unix)]
+		{
+			use std::os::unix::io::AsRawFd;
+			debug.field("fd", &self.as_raw_fd());
+			debug.finish()
+		}
+
+		#[cfg(not(unix))]
+		debug.finish_non_exhaustive()
+	}
+}
+
+impl IsoTpSocket {
+	/// Create a new ISO-TP socket bound to a named CAN interface.
+	///
+	/// The socket receives messages sent with CAN ID `rx_id`,
+	/// and sends messages (and flow control frames) with CAN ID `tx_id`.
+	pub fn bind(interface: impl AsRef<str>, rx_id: impl Into<CanId>, tx_id: impl Into<CanId>) -> std::io::Result<Self> {
+		Self::bind_with_config(interface, rx_id, tx_id, &IsoTpConfig::default())
+	}
+
+	/// Create a new ISO-TP socket with a custom configuration, bound to a named CAN interface.
+	///
+	/// The socket receives messages sent with CAN ID `rx_id`,
+	/// and sends messages (and flow control frames) with CAN ID `tx_id`.
+	pub fn bind_with_config(interface: impl AsRef<str>, rx_id: impl Into<CanId>, tx_id: impl Into<CanId>, config: &IsoTpConfig) -> std::io::Result<Self> {
+		let inner = crate::sys::IsoTpSocket::new(false)?;
+		configure(&inner, config)?;
+		let interface = crate::sys::CanInterface::from_name(interface.as_ref())?;
+		inner.bind(&interface, rx_id.into(), tx_id.into())?;
+		Ok(Self { inner })
+	}
+
+	/// Get the interface this socket is bound to.
+	pub fn local_addr(&self) -> std::io::Result<CanInterface> {
+		Ok(CanInterface {
+			inner: self.inner.local_addr()?,
+		})
+	}
+
+	/// Set the socket in non-blocking or blocking mode.
+	///
+	/// If the socket is set in non-blocking mode, send and receive operations will never block.
+	/// Instead, if the operation can not be completed immediately, it will fail with a [`std::io::ErrorKind::WouldBlock`] error.
+	pub fn set_nonblocking(&self, non_blocking: bool) -> std::io::Result<()> {
+		self.inner.set_nonblocking(non_blocking)
+	}
+
+	/// Send a complete message over the socket.
+	///
+	/// Unless the [`IsoTpFlags::WAIT_TX_DONE`][super::IsoTpFlags::WAIT_TX_DONE] flag is set,
+	/// this returns as soon as the kernel accepted the message for transmission.
+	pub fn send(&self, data: &[u8]) -> std::io::Result<()> {
+		self.inner.send(data)
+	}
+
+	/// Receive a complete message from the socket.
+	pub fn recv(&self) -> std::io::Result<Vec<u8>> {
+		self.inner.recv()
+	}
+
+	/// Get the general options of the socket.
+	pub fn get_options(&self) -> std::io::Result<IsoTpOptions> {
+		self.inner.get_options()
+	}
+
+	/// Get the flow control options of the socket.
+	pub fn get_flow_control_options(&self) -> std::io::Result<FlowControlOptions> {
+		self.inner.get_flow_control_options()
+	}
+
+	/// Get the link layer options of the socket.
+	pub fn get_link_layer_options(&self) -> std::io::Result<LinkLayerOptions> {
+		self.inner.get_link_layer_options()
+	}
+
+	/// Get the forced separation time between transmitted consecutive frames.
+	pub fn get_tx_stmin(&self) -> std::io::Result<std::time::Duration> {
+		self.inner.get_tx_stmin()
+	}
+
+	/// Get the minimum separation time between received consecutive frames.
+	pub fn get_rx_stmin(&self) -> std::io::Result<std::time::Duration> {
+		self.inner.get_rx_stmin()
+	}
+}
+
+/// Apply the configuration to an unbound ISO-TP socket.
+pub(crate) fn configure(socket: &crate::sys::IsoTpSocket, config: &IsoTpConfig) -> std::io::Result<()> {
+	socket.set_options(&config.options)?;
+	socket.set_flow_control_options(&config.flow_control)?;
+	socket.set_link_layer_options(&config.link_layer)?;
+	if let Some(stmin) = config.tx_stmin {
+		socket.set_tx_stmin(stmin)?;
+	}
+	if let Some(stmin) = config.rx_stmin {
+		socket.set_rx_stmin(stmin)?;
+	}
+	Ok(())
+}
+
+impl std::os::fd::AsFd for IsoTpSocket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.inner.as_fd()
+	}
+}
+
+impl From<IsoTpSocket> for std::os::fd::OwnedFd {
+	fn from(value: IsoTpSocket) -> Self {
+		value.inner.into()
+	}
+}
+
+impl From<std::os::fd::OwnedFd> for IsoTpSocket {
+	fn from(value: std::os::fd::OwnedFd) -> Self {
+		Self {
+			inner: value.into(),
+		}
+	}
+}
+
+impl std::os::fd::AsRawFd for IsoTpSocket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.inner.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for IsoTpSocket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.inner.into_raw_fd()
+	}
+}
+
+impl std::os::fd::FromRawFd for IsoTpSocket {
+	unsafe fn from_raw_fd(fd: std::os::fd::RawFd) -> Self {
+		Self {
+			inner: crate::sys::IsoTpSocket::from_raw_fd(fd)
+		}
+	}
+}
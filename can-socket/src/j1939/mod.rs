@@ -0,0 +1,314 @@
+//! SAE J1939 sockets.
+//!
+//! J1939 is used in trucks, agricultural and other heavy-duty vehicles.
+//! The kernel implements the J1939 transport protocols for messages up to 1785 bytes (and larger with the extended transport protocol).
+//! It also keeps track of address claims, so sockets can be bound to a NAME instead of a fixed address.
+//! Note that the kernel does not claim addresses by itself:
+//! you have to send the address claim message ([`PGN_ADDRESS_CLAIMED`]) yourself.
+//!
+//! This requires the `can-j1939` kernel module (part of the mainline kernel since Linux 5.4).
+//!
+//! You can use [`J1939Id`] to decompose the CAN ID of a J1939 message into the priority, PGN and addresses
+//! if you receive J1939 messages with a raw [`CanSocket`][crate::CanSocket].
+
+use crate::{CanId, ExtendedId};
+
+pub(crate) mod socket;
+pub use socket::J1939Socket;
+
+/// Special value for [`J1939Address::name`] meaning "no name".
+pub const NO_NAME: u64 = 0;
+
+/// Special value for [`J1939Address::pgn`] meaning "no PGN".
+pub const NO_PGN: u32 = 0x40000;
+
+/// Special value for [`J1939Address::address`] meaning "no address".
+///
+/// As destination address, this is the global (broadcast) address.
+pub const NO_ADDR: u8 = 0xFF;
+
+/// The address used by ECUs that did not claim an address (yet).
+pub const IDLE_ADDR: u8 = 0xFE;
+
+/// The PGN of the request message.
+pub const PGN_REQUEST: u32 = 0x0EA00;
+
+/// The PGN of the address claimed message.
+pub const PGN_ADDRESS_CLAIMED: u32 = 0x0EE00;
+
+/// The PGN of the commanded address message.
+pub const PGN_ADDRESS_COMMANDED: u32 = 0x0FED8;
+
+/// The maximum size of a message using the (non-extended) transport protocol.
+pub const MAX_TP_MESSAGE_SIZE: usize = 1785;
+
+/// A J1939 socket address.
+///
+/// Used to bind a socket, to connect it to a peer and as destination or source of messages.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct J1939Address {
+	/// The 64-bit NAME of the ECU, or [`NO_NAME`].
+	///
+	/// If a name is used, the kernel translates it to the address claimed by the ECU with that name.
+	pub name: u64,
+
+	/// The parameter group number, or [`NO_PGN`].
+	pub pgn: u32,
+
+	/// The 8-bit address of the ECU, or [`NO_ADDR`].
+	pub address: u8,
+}
+
+impl J1939Address {
+	/// An address without name, PGN or address.
+	pub const NONE: Self = Self::new(NO_NAME, NO_PGN, NO_ADDR);
+
+	/// Create a new J1939 address.
+	pub const fn new(name: u64, pgn: u32, address: u8) -> Self {
+		Self { name, pgn, address }
+	}
+
+	/// Create a J1939 address with only an 8-bit address set.
+	pub const fn from_address(address: u8) -> Self {
+		Self::NONE.with_address(address)
+	}
+
+	/// Create a J1939 address with only a NAME set.
+	pub const fn from_name(name: u64) -> Self {
+		Self::NONE.with_name(name)
+	}
+
+	/// Set the NAME of the address.
+	#[must_use = "this function returns a new address, it does not modify self"]
+	pub const fn with_name(mut self, name: u64) -> Self {
+		self.name = name;
+		self
+	}
+
+	/// Set the PGN of the address.
+	#[must_use = "this function returns a new address, it does not modify self"]
+	pub const fn with_pgn(mut self, pgn: u32) -> Self {
+		self.pgn = pgn;
+		self
+	}
+
+	/// Set the 8-bit address.
+	#[must_use = "this function returns a new address, it does not modify self"]
+	pub const fn with_address(mut self, address: u8) -> Self {
+		self.address = address;
+		self
+	}
+}
+
+impl Default for J1939Address {
+	fn default() -> Self {
+		Self::NONE
+	}
+}
+
+/// A message received on a [`J1939Socket`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct J1939Message {
+	/// The data of the message.
+	pub data: Vec<u8>,
+
+	/// The source of the message, including the PGN.
+	pub source: J1939Address,
+
+	/// The destination address of the message.
+	///
+	/// This is `None` if the message was not sent to a unicast address, for example if it was a broadcast message.
+	pub destination_address: Option<u8>,
+
+	/// The NAME of the destination of the message, if known by the kernel.
+	pub destination_name: Option<u64>,
+
+	/// The priority of the message (0 is the highest priority, 7 the lowest).
+	pub priority: Option<u8>,
+}
+
+/// Information about a message received with [`J1939Socket::recv_into()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct J1939MessageInfo {
+	/// The length of the message in bytes.
+	pub len: usize,
+
+	/// The source of the message, including the PGN.
+	pub source: J1939Address,
+
+	/// The destination address of the message.
+	///
+	/// This is `None` if the message was not sent to a unicast address, for example if it was a broadcast message.
+	pub destination_address: Option<u8>,
+
+	/// The NAME of the destination of the message, if known by the kernel.
+	pub destination_name: Option<u64>,
+
+	/// The priority of the message (0 is the highest priority, 7 the lowest).
+	pub priority: Option<u8>,
+}
+
+/// A CAN ID decomposed into the J1939 fields.
+///
+/// A J1939 CAN ID is always an extended ID, consisting of:
+/// * a 3 bit priority,
+/// * an 18 bit parameter group number (PGN),
+/// * an 8 bit source address.
+///
+/// If the PDU format (bits 8 to 15 of the PGN) is below 240,
+/// the lowest 8 bits of the PGN are the destination address instead.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct J1939Id {
+	id: ExtendedId,
+}
+
+impl J1939Id {
+	/// Create a J1939 ID from the priority, PGN and source address.
+	///
+	/// For PGNs with a PDU format below 240, use [`Self::with_destination_address()`] to set the destination address.
+	///
+	/// The priority is truncated to 3 bits and the PGN to 18 bits.
+	pub const fn new(priority: u8, pgn: u32, source_address: u8) -> Self {
+		let id = ((priority as u32 & 0x7) << 26) | ((pgn & 0x3FFFF) << 8) | source_address as u32;
+		// SAFETY: The ID is at most 29 bits.
+		Self { id: unsafe { ExtendedId::new_unchecked(id) } }
+	}
+
+	/// Decompose an extended CAN ID into the J1939 fields.
+	pub const fn from_id(id: ExtendedId) -> Self {
+		Self { id }
+	}
+
+	/// Get the extended CAN ID.
+	pub const fn id(self) -> ExtendedId {
+		self.id
+	}
+
+	/// Get the priority (0 is the highest priority, 7 the lowest).
+	pub const fn priority(self) -> u8 {
+		(self.id.as_u32() >> 26) as u8 & 0x7
+	}
+
+	/// Get the parameter group number (PGN).
+	///
+	/// For messages with a destination address, the lowest 8 bits of the PGN are always 0.
+	pub const fn pgn(self) -> u32 {
+		let pgn = (self.id.as_u32() >> 8) & 0x3FFFF;
+		if self.is_pdu1() {
+			pgn & 0x3FF00
+		} else {
+			pgn
+		}
+	}
+
+	/// Get the PDU format: bits 8 to 15 of the PGN.
+	pub const fn pdu_format(self) -> u8 {
+		(self.id.as_u32() >> 16) as u8
+	}
+
+	/// Get the PDU specific field: the destination address or the lowest 8 bits of the PGN.
+	pub const fn pdu_specific(self) -> u8 {
+		(self.id.as_u32() >> 8) as u8
+	}
+
+	/// Check if the message uses the PDU1 format: a PDU format below 240, with a destination address.
+	pub const fn is_pdu1(self) -> bool {
+		self.pdu_format() < 240
+	}
+
+	/// Get the destination address, if the message uses the PDU1 format.
+	pub const fn destination_address(self) -> Option<u8> {
+		if self.is_pdu1() {
+			Some(self.pdu_specific())
+		} else {
+			None
+		}
+	}
+
+	/// Get the source address.
+	pub const fn source_address(self) -> u8 {
+		self.id.as_u32() as u8
+	}
+
+	/// Set the destination address.
+	///
+	/// This overwrites the PDU specific field, so it should only be used for PDU1 messages.
+	#[must_use = "this function returns a new ID, it does not modify self"]
+	pub const fn with_destination_address(self, address: u8) -> Self {
+		let id = (self.id.as_u32() & !0xFF00) | ((address as u32) << 8);
+		// SAFETY: The ID was valid and we only changed bits 8 to 15.
+		Self { id: unsafe { ExtendedId::new_unchecked(id) } }
+	}
+}
+
+impl From<ExtendedId> for J1939Id {
+	fn from(id: ExtendedId) -> Self {
+		Self::from_id(id)
+	}
+}
+
+impl From<J1939Id> for ExtendedId {
+	fn from(id: J1939Id) -> Self {
+		id.id
+	}
+}
+
+impl From<J1939Id> for CanId {
+	fn from(id: J1939Id) -> Self {
+		id.id.into()
+	}
+}
+
+impl std::fmt::Debug for J1939Id {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("J1939Id");
+		debug.field("priority", &self.priority());
+		debug.field("pgn", &format_args!("0x{:05X}", self.pgn()));
+		if let Some(destination) = self.destination_address() {
+			debug.field("destination_address", &format_args!("0x{destination:02X}"));
+		}
+		debug.field("source_address", &format_args!("0x{:02X}", self.source_address()));
+		debug.finish()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn decompose_pdu2_id() {
+		// Electronic engine controller 1 from the engine, priority 3.
+		let id = J1939Id::from(ExtendedId::new(0x0CF0_0400).unwrap());
+		assert!(id.priority() == 3);
+		assert!(id.pgn() == 0xF004);
+		assert!(!id.is_pdu1());
+		assert!(let None = id.destination_address());
+		assert!(id.source_address() == 0x00);
+		assert!(J1939Id::new(3, 0xF004, 0x00) == id);
+	}
+
+	#[test]
+	fn decompose_pdu1_id() {
+		// Request from address 0xF9 to address 0x00, priority 6.
+		let id = J1939Id::from(ExtendedId::new(0x18EA_00F9).unwrap());
+		assert!(id.priority() == 6);
+		assert!(id.pgn() == PGN_REQUEST);
+		assert!(id.is_pdu1());
+		assert!(id.destination_address() == Some(0x00));
+		assert!(id.source_address() == 0xF9);
+
+		let id = J1939Id::new(6, PGN_REQUEST, 0xF9).with_destination_address(0x21);
+		assert!(id.id().as_u32() == 0x18EA_21F9);
+		assert!(id.pgn() == PGN_REQUEST);
+		assert!(id.destination_address() == Some(0x21));
+	}
+
+	#[test]
+	fn truncate_fields() {
+		let id = J1939Id::new(0xFF, 0xFFFF_FFFF, 0xFF);
+		assert!(id.id().as_u32() == 0x1FFF_FFFF);
+		assert!(id.priority() == 7);
+	}
+}
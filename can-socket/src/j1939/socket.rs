@@ -0,0 +1,177 @@
+use crate::CanInterface;
+use super::{J1939Address, J1939Message, J1939MessageInfo};
+
+/// A synchronous J1939 socket.
+///
+/// Used to send and receive complete J1939 messages.
+/// Messages larger than 8 bytes are transferred with the transport protocol by the kernel.
+///
+/// Although the socket is synchronous,
+/// it can be put into non-blocking mode with [`Self::set_nonblocking()`].
+#[repr(transparent)]
+pub struct J1939Socket {
+	inner: crate::sys::J1939Socket,
+}
+
+impl std::fmt::Debug for J1939Socket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("J1939Socket");
+		#[cfg(unix)]
+		{
+			use std::os::unix::io::AsRawFd;
+			debug.field("fd", &self.as_raw_fd());
+			debug.finish()
+		}
+
+		#[cfg(not(unix))]
+		debug.finish_non_exhaustive()
+	}
+}
+
+impl J1939Socket {
+	/// Create a new J1939 socket bound to a named CAN interface.
+	///
+	/// The local address determines the source of sent messages,
+	/// and which messages are received by the socket:
+	/// * If the address has a NAME, the socket uses the address claimed for that NAME.
+	/// * If the address has a PGN, the socket only receives messages with that PGN.
+	/// * If the 8-bit address is [`NO_ADDR`][super::NO_ADDR], the socket can only receive messages.
+	pub fn bind(interface: impl AsRef<str>, address: &J1939Address) -> std::io::Result<Self> {
+		let inner = crate::sys::J1939Socket::new(false)?;
+		let interface = crate::sys::CanInterface::from_name(interface.as_ref())?;
+		inner.bind(&interface, address)?;
+		Ok(Self { inner })
+	}
+
+	/// Set the default destination of the socket.
+	///
+	/// Messages sent with [`Self::send()`] go to this destination.
+	/// The PGN of the address is used as PGN for sent messages.
+	pub fn connect(&self, address: &J1939Address) -> std::io::Result<()> {
+		self.inner.connect(address)
+	}
+
+	/// Get the interface and the J1939 address this socket is bound to.
+	pub fn local_addr(&self) -> std::io::Result<(CanInterface, J1939Address)> {
+		let (interface, address) = self.inner.local_addr()?;
+		Ok((CanInterface { inner: interface }, address))
+	}
+
+	/// Get the address this socket is connected to.
+	pub fn peer_addr(&self) -> std::io::Result<J1939Address> {
+		self.inner.peer_addr()
+	}
+
+	/// Set the socket in non-blocking or blocking mode.
+	///
+	/// If the socket is set in non-blocking mode, send and receive operations will never block.
+	/// Instead, if the operation can not be completed immediately, it will fail with a [`std::io::ErrorKind::WouldBlock`] error.
+	pub fn set_nonblocking(&self, non_blocking: bool) -> std::io::Result<()> {
+		self.inner.set_nonblocking(non_blocking)
+	}
+
+	/// Send a message to the destination the socket is connected to.
+	pub fn send(&self, data: &[u8]) -> std::io::Result<()> {
+		self.inner.send(data)
+	}
+
+	/// Send a message to a specific destination.
+	///
+	/// The PGN of the address is used as PGN for the message.
+	/// To send to the global address ([`NO_ADDR`][super::NO_ADDR]), broadcast must be enabled with [`Self::set_broadcast()`].
+	pub fn send_to(&self, data: &[u8], address: &J1939Address) -> std::io::Result<()> {
+		self.inner.send_to(data, address)
+	}
+
+	/// Receive a message from the socket.
+	///
+	/// Messages larger than [`MAX_TP_MESSAGE_SIZE`][super::MAX_TP_MESSAGE_SIZE] bytes are discarded and reported as an error.
+	/// Use [`Self::recv_into()`] to receive larger messages sent with the extended transport protocol.
+	pub fn recv(&self) -> std::io::Result<J1939Message> {
+		self.inner.recv()
+	}
+
+	/// Receive a message from the socket into a buffer.
+	///
+	/// Returns the length of the message and its addresses.
+	/// Messages that do not fit in the buffer are discarded and reported as an error.
+	pub fn recv_into(&self, buffer: &mut [u8]) -> std::io::Result<J1939MessageInfo> {
+		self.inner.recv_into(buffer)
+	}
+
+	/// Check if promiscuous mode is enabled on the socket.
+	pub fn get_promiscuous(&self) -> std::io::Result<bool> {
+		self.inner.get_promiscuous()
+	}
+
+	/// Enable or disable promiscuous mode.
+	///
+	/// In promiscuous mode, the socket receives all messages on the bus,
+	/// regardless of the destination address and the PGN the socket is bound to.
+	pub fn set_promiscuous(&self, enable: bool) -> std::io::Result<()> {
+		self.inner.set_promiscuous(enable)
+	}
+
+	/// Get the priority of sent messages.
+	pub fn get_send_priority(&self) -> std::io::Result<u8> {
+		self.inner.get_send_priority()
+	}
+
+	/// Set the priority of sent messages (0 is the highest priority, 7 the lowest).
+	///
+	/// The default priority is 6.
+	/// Setting a priority of 0 or 1 requires the `CAP_NET_ADMIN` capability.
+	pub fn set_send_priority(&self, priority: u8) -> std::io::Result<()> {
+		self.inner.set_send_priority(priority)
+	}
+
+	/// Check if sending to the global address is allowed.
+	pub fn get_broadcast(&self) -> std::io::Result<bool> {
+		self.inner.get_broadcast()
+	}
+
+	/// Allow or disallow sending messages to the global address.
+	pub fn set_broadcast(&self, enable: bool) -> std::io::Result<()> {
+		self.inner.set_broadcast(enable)
+	}
+}
+
+impl std::os::fd::AsFd for J1939Socket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.inner.as_fd()
+	}
+}
+
+impl From<J1939Socket> for std::os::fd::OwnedFd {
+	fn from(value: J1939Socket) -> Self {
+		value.inner.into()
+	}
+}
+
+impl From<std::os::fd::OwnedFd> for J1939Socket {
+	fn from(value: std::os::fd::OwnedFd) -> Self {
+		Self {
+			inner: value.into(),
+		}
+	}
+}
+
+impl std::os::fd::AsRawFd for J1939Socket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.inner.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for J1939Socket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.inner.into_raw_fd()
+	}
+}
+
+impl std::os::fd::FromRawFd for J1939Socket {
+	unsafe fn from_raw_fd(fd: std::os::fd::RawFd) -> Self {
+		Self {
+			inner: crate::sys::J1939Socket::from_raw_fd(fd)
+		}
+	}
+}
@@ -5,6 +5,7 @@
 //!
 //! The is a standard blocking or non-blocking [`CanSocket`],
 //! and an asynchronous [`tokio::CanSocket`].
+//! With the `async-io` feature, there is also an asynchronous [`async_io::CanSocket`] that works with any executor.
 //!
 //! This library uses the `SocketCAN` interface and only works on Linux.
 //!
@@ -13,6 +14,24 @@
 //! * Bind sockets to *all* CAN interfaces at the same time.
 //! * Send and receive data frames and RTR frames.
 //! * Send and receive standard frames and extended frames.
+//! * Send and receive CAN FD frames.
+//! * Send and receive CAN XL frames.
+//! * Receive and decode error frames.
+//! * Software and hardware timestamps for received frames.
+//! * Detect frames dropped by the kernel because the receive queue was full.
+//! * Send and receive multiple frames with a single system call.
+//! * ISO-TP (ISO 15765-2) transport protocol sockets.
+//! * SAE J1939 sockets.
+//! * Cyclic transmission and change detection in the kernel with broadcast manager (BCM) sockets.
+//! * List the CAN interfaces on the system.
+//! * Configure the bitrate and other settings of CAN interfaces using netlink.
+//! * Monitor the controller state, error counters and statistics of CAN interfaces.
+//! * Create and delete virtual CAN interfaces (`vcan` and `vxcan`).
+//! * Manage the routing rules of the kernel CAN gateway.
+//! * Read and write `candump`, Vector ASC and pcapng log files.
+//! * Replay recorded traces on a socket with the original timing.
+//! * Test without CAN interfaces using an in-memory virtual bus.
+//! * Use the async socket as a `futures` `Stream` and `Sink` (with the `futures` feature).
 //! * Setting per-socket filters.
 //! * Control over the `loopback` and `recv_own_msgs` options.
 //! * Constructing compile-time checked CAN IDs.
@@ -22,12 +41,31 @@
 #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
 
+pub mod bcm;
+pub use bcm::BcmSocket;
 pub mod error;
+pub mod error_frame;
+pub use error_frame::{CanErrorFrame, CanErrorMask};
+pub mod isotp;
+pub use isotp::IsoTpSocket;
+pub mod j1939;
+pub use j1939::J1939Socket;
+pub mod log;
+pub mod netlink;
+pub mod transport;
+pub mod virtual_bus;
 
 #[cfg(feature = "tokio")]
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
+#[cfg(feature = "async-io")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "async-io")))]
+pub mod async_io;
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+mod async_socket;
+
 mod id;
 pub use id::{ExtendedId, CanId, StandardId, MAX_EXTENDED_ID, MAX_STANDARD_ID};
 
@@ -37,15 +75,31 @@ pub use filter::CanFilter;
 mod frame;
 pub use frame::{CanFrame, CanData};
 
+mod fd_frame;
+pub use fd_frame::{CanFdFrame, CanFdData};
+
+mod xl_frame;
+pub use xl_frame::{CanXlFrame, CanXlData, MAX_XL_DATA_LEN};
+
+mod any_frame;
+pub use any_frame::AnyCanFrame;
+
 mod interface;
 pub use interface::CanInterface;
 
+mod metadata;
+pub use metadata::{DropTracker, RecvMetadata, TimestampMode, Timestamps};
+
 mod socket;
 pub use socket::CanSocket;
 
 mod sys;
+mod syntax;
 
 /// Trait for types that can be used as a timeout or deadline.
+///
+/// The trait does not depend on any async runtime:
+/// it is used by the async sockets, the blocking [`virtual_bus::BusSocket`] and the [`tokio::TraceReplayer`].
 pub trait Deadline {
 	/// Get the instant at which the timeout/deadline expires.
 	fn deadline(&self) -> std::time::Instant;
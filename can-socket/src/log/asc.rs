@@ -0,0 +1,648 @@
+use std::io::{BufRead, Write};
+use std::time::{Duration, SystemTime};
+
+use crate::error::ParseLogEntryError;
+use crate::{AnyCanFrame, CanData, CanErrorFrame, CanErrorMask, CanFdData, CanFdFrame, CanFrame, CanId};
+use super::{error, parse_seconds, Direction, LogEntry};
+
+/// Flag in the CAN FD message flags of an ASC file for remote frames.
+const ASC_F_RTR: u32 = 0x0010;
+
+/// Flag in the CAN FD message flags of an ASC file for CAN FD frames.
+const ASC_F_FDF: u32 = 0x1000;
+
+/// Flag in the CAN FD message flags of an ASC file for the bit rate switch.
+const ASC_F_BRS: u32 = 0x2000;
+
+/// Flag in the CAN FD message flags of an ASC file for the error state indicator.
+const ASC_F_ESI: u32 = 0x4000;
+
+/// A single frame in a Vector ASC file.
+#[derive(Debug, Clone)]
+pub struct AscEntry {
+	/// The time of the frame relative to the start of the measurement.
+	pub timestamp: Duration,
+
+	/// The channel number of the frame (the first channel is 1).
+	pub channel: u16,
+
+	/// The direction of the frame.
+	pub direction: Direction,
+
+	/// The logged frame.
+	pub frame: AnyCanFrame,
+}
+
+impl AscEntry {
+	/// Create a new entry.
+	pub fn new(timestamp: Duration, channel: u16, direction: Direction, frame: impl Into<AnyCanFrame>) -> Self {
+		Self {
+			timestamp,
+			channel,
+			direction,
+			frame: frame.into(),
+		}
+	}
+
+	/// Create an entry from a [`LogEntry`].
+	///
+	/// The timestamp is made relative to `start`, and the direction is set to [`Direction::Rx`].
+	/// Entries from before `start` get a timestamp of 0.
+	pub fn from_log_entry(entry: LogEntry, start: SystemTime, channel: u16) -> Self {
+		Self {
+			timestamp: entry.timestamp.duration_since(start).unwrap_or_default(),
+			channel,
+			direction: Direction::Rx,
+			frame: entry.frame,
+		}
+	}
+
+	/// Convert the entry to a [`LogEntry`] with an absolute timestamp and an interface name.
+	pub fn into_log_entry(self, start: SystemTime, interface: impl Into<String>) -> LogEntry {
+		LogEntry::new(start + self.timestamp, interface, self.frame)
+	}
+}
+
+/// Reader for Vector ASC files, as written by CANalyzer and CANoe.
+///
+/// The reader supports classic CAN frames, remote frames, error frames and CAN FD frames,
+/// with IDs and data in hexadecimal or decimal notation and with absolute or relative timestamps.
+/// Timestamps are always reported relative to the start of the measurement.
+///
+/// Other events in the file (like statistics and status messages) are skipped.
+/// The reader can also be used as an iterator over the entries in the file.
+#[derive(Debug)]
+pub struct AscReader<R> {
+	inner: R,
+	line: String,
+	line_number: usize,
+	pending: bool,
+	date: Option<String>,
+	radix: u32,
+	relative_timestamps: bool,
+	last_timestamp: Duration,
+}
+
+impl<R: BufRead> AscReader<R> {
+	/// Create a new reader and parse the header of the file.
+	pub fn new(inner: R) -> std::io::Result<Self> {
+		let mut reader = Self {
+			inner,
+			line: String::new(),
+			line_number: 0,
+			pending: false,
+			date: None,
+			radix: 16,
+			relative_timestamps: false,
+			last_timestamp: Duration::ZERO,
+		};
+
+		while reader.read_line()? {
+			let line = reader.line.trim();
+			if let Some(date) = line.strip_prefix("date ") {
+				reader.date = Some(date.trim().into());
+			} else if line.starts_with("base ") {
+				let mut fields = line.split_ascii_whitespace();
+				while let Some(field) = fields.next() {
+					match (field, fields.next()) {
+						("base", Some("dec")) => reader.radix = 10,
+						("base", Some("hex")) => reader.radix = 16,
+						("timestamps", Some("relative")) => reader.relative_timestamps = true,
+						("timestamps", Some("absolute")) => reader.relative_timestamps = false,
+						_ => return Err(reader.error(error("invalid `base` line in header"))),
+					}
+				}
+			} else if !is_ignored_line(line) && !line.ends_with("internal events logged") {
+				reader.pending = true;
+				break;
+			}
+		}
+		Ok(reader)
+	}
+
+	/// Get the date of the start of the measurement, as written in the header of the file.
+	///
+	/// The date is not parsed, because its format depends on the language settings of the program that wrote the file.
+	pub fn date(&self) -> Option<&str> {
+		self.date.as_deref()
+	}
+
+	/// Read the next entry from the file.
+	///
+	/// Returns `Ok(None)` at the end of the file.
+	/// If a frame can not be parsed, an error of kind [`std::io::ErrorKind::InvalidData`] is returned.
+	/// The next call will continue with the following line.
+	pub fn read_entry(&mut self) -> std::io::Result<Option<AscEntry>> {
+		loop {
+			if !std::mem::take(&mut self.pending) && !self.read_line()? {
+				return Ok(None);
+			}
+
+			let line = std::mem::take(&mut self.line);
+			let result = self.parse_line(line.trim());
+			self.line = line;
+			match result {
+				Ok(Some(entry)) => return Ok(Some(entry)),
+				Ok(None) => continue,
+				Err(e) => return Err(self.error(e)),
+			}
+		}
+	}
+
+	/// Get a reference to the wrapped reader.
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	/// Get a mutable reference to the wrapped reader.
+	pub fn get_mut(&mut self) -> &mut R {
+		&mut self.inner
+	}
+
+	/// Consume the reader and return the wrapped reader.
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+
+	/// Read the next line into `self.line`, returning `false` at the end of the file.
+	fn read_line(&mut self) -> std::io::Result<bool> {
+		self.line.clear();
+		if self.inner.read_line(&mut self.line)? == 0 {
+			return Ok(false);
+		}
+		self.line_number += 1;
+		Ok(true)
+	}
+
+	fn error(&self, error: ParseLogEntryError) -> std::io::Error {
+		std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: {error}", self.line_number))
+	}
+
+	/// Parse a line from the file, returning `None` if it does not hold a frame.
+	fn parse_line(&mut self, line: &str) -> Result<Option<AscEntry>, ParseLogEntryError> {
+		if is_ignored_line(line) {
+			return Ok(None);
+		}
+
+		let mut fields = line.split_ascii_whitespace();
+		let Some(timestamp) = fields.next().and_then(parse_seconds) else {
+			return Ok(None);
+		};
+		if self.relative_timestamps {
+			self.last_timestamp += timestamp;
+		} else {
+			self.last_timestamp = timestamp;
+		}
+		let timestamp = self.last_timestamp;
+
+		let fields: Vec<&str> = fields.collect();
+		let (channel, direction, frame) = match fields.as_slice() {
+			["CANFD", channel, direction, "ErrorFrame", ..] => (*channel, *direction, error_frame()),
+			["CANFD", channel, direction, id, fields @ ..] => (*channel, *direction, self.parse_fd_frame(id, fields)?),
+			[channel, "ErrorFrame", ..] => (*channel, "Rx", error_frame()),
+			[channel, id, direction @ ("Rx" | "Tx"), fields @ ..] => (*channel, *direction, self.parse_can_frame(id, fields)?),
+			_ => return Ok(None),
+		};
+
+		let channel = channel.parse().map_err(|_| error("invalid channel number"))?;
+		let direction = match direction {
+			"Rx" => Direction::Rx,
+			"Tx" => Direction::Tx,
+			_ => return Err(error("invalid direction, expected `Rx` or `Tx`")),
+		};
+
+		Ok(Some(AscEntry {
+			timestamp,
+			channel,
+			direction,
+			frame,
+		}))
+	}
+
+	/// Parse a classic CAN frame from the fields after the direction.
+	fn parse_can_frame(&self, id: &str, fields: &[&str]) -> Result<AnyCanFrame, ParseLogEntryError> {
+		let id = self.parse_id(id)?;
+		match fields {
+			["r", fields @ ..] => {
+				// The data length code of remote frames is optional.
+				let dlc = fields.first()
+					.and_then(|dlc| u8::from_str_radix(dlc, self.radix).ok())
+					.unwrap_or(0);
+				let frame = CanFrame::new_rtr(id)
+					.with_data_length_code(dlc)
+					.map_err(|_| error("invalid data length code"))?;
+				Ok(frame.into())
+			},
+			["d", dlc, fields @ ..] => {
+				let dlc = u8::from_str_radix(dlc, self.radix)
+					.ok()
+					.filter(|&dlc| dlc <= 15)
+					.ok_or(error("invalid data length code"))?;
+				let data = self.parse_data(fields, dlc.min(8).into())?;
+				let data = CanData::try_from(data.as_slice())
+					.map_err(|_| error("too much data for a classic CAN frame"))?;
+				let frame = CanFrame::new(id, data)
+					.with_data_length_code(dlc)
+					.map_err(|_| error("invalid data length code"))?;
+				Ok(frame.into())
+			},
+			_ => Err(error("invalid frame type, expected `d` or `r`")),
+		}
+	}
+
+	/// Parse a frame from the fields after the ID of a `CANFD` line.
+	///
+	/// These lines can also hold classic CAN frames, which is indicated by the message flags.
+	fn parse_fd_frame(&self, id: &str, fields: &[&str]) -> Result<AnyCanFrame, ParseLogEntryError> {
+		let id = self.parse_id(id)?;
+
+		// The ID may be followed by a symbolic name.
+		let fields = match fields {
+			[name, fields @ ..] if !matches!(*name, "0" | "1") => fields,
+			fields => fields,
+		};
+		let [brs, esi, dlc, len, fields @ ..] = fields else {
+			return Err(error("incomplete CAN FD frame"));
+		};
+		let dlc = u8::from_str_radix(dlc, 16)
+			.ok()
+			.filter(|&dlc| dlc <= 15)
+			.ok_or(error("invalid data length code"))?;
+		let len: usize = len.parse().map_err(|_| error("invalid data length"))?;
+		let data = self.parse_data(fields, len)?;
+
+		// The data is followed by the message duration, the message length and the message flags.
+		let flags = match fields.get(len + 2) {
+			Some(flags) => u32::from_str_radix(flags, 16).map_err(|_| error("invalid message flags"))?,
+			None => ASC_F_FDF,
+		};
+
+		if flags & ASC_F_FDF != 0 {
+			let data = CanFdData::try_from(data.as_slice())
+				.map_err(|_| error("too much data for a CAN FD frame"))?;
+			let frame = CanFdFrame::new(id, data)
+				.with_bit_rate_switch(*brs == "1" || flags & ASC_F_BRS != 0)
+				.with_error_state_indicator(*esi == "1" || flags & ASC_F_ESI != 0);
+			Ok(frame.into())
+		} else if flags & ASC_F_RTR != 0 {
+			let frame = CanFrame::new_rtr(id)
+				.with_data_length_code(dlc)
+				.map_err(|_| error("invalid data length code"))?;
+			Ok(frame.into())
+		} else {
+			let data = CanData::try_from(data.as_slice())
+				.map_err(|_| error("too much data for a classic CAN frame"))?;
+			let frame = CanFrame::new(id, data)
+				.with_data_length_code(dlc)
+				.map_err(|_| error("invalid data length code"))?;
+			Ok(frame.into())
+		}
+	}
+
+	/// Parse a CAN ID, where extended IDs have an `x` suffix.
+	fn parse_id(&self, input: &str) -> Result<CanId, ParseLogEntryError> {
+		if let Some(id) = input.strip_suffix(['x', 'X']) {
+			u32::from_str_radix(id, self.radix)
+				.ok()
+				.and_then(|id| CanId::new_extended(id).ok())
+				.ok_or(error("invalid extended CAN ID"))
+		} else {
+			u16::from_str_radix(input, self.radix)
+				.ok()
+				.and_then(|id| CanId::new_standard(id).ok())
+				.ok_or(error("invalid standard CAN ID"))
+		}
+	}
+
+	/// Parse `len` data bytes from the start of `fields`.
+	fn parse_data(&self, fields: &[&str], len: usize) -> Result<Vec<u8>, ParseLogEntryError> {
+		let fields = fields.get(..len).ok_or(error("missing data bytes"))?;
+		fields.iter()
+			.map(|byte| u8::from_str_radix(byte, self.radix).map_err(|_| error("invalid data byte")))
+			.collect()
+	}
+}
+
+impl<R: BufRead> Iterator for AscReader<R> {
+	type Item = std::io::Result<AscEntry>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_entry().transpose()
+	}
+}
+
+/// Writer for Vector ASC files.
+///
+/// The header is written when the writer is created.
+/// You must call [`Self::finish()`] after the last entry to close the trigger block.
+///
+/// IDs and data are written in hexadecimal notation, with timestamps relative to the start of the measurement.
+/// Error frames are written as a plain `ErrorFrame` event, because the ASC format can not represent the error details.
+/// CAN XL frames are not supported by the ASC format.
+#[derive(Debug)]
+pub struct AscWriter<W> {
+	inner: W,
+}
+
+impl<W: Write> AscWriter<W> {
+	/// Create a new writer and write the header of the file.
+	///
+	/// The start time is written in the header in UTC.
+	pub fn new(mut inner: W, start: SystemTime) -> std::io::Result<Self> {
+		let date = format_date(start);
+		writeln!(inner, "date {date}")?;
+		writeln!(inner, "base hex  timestamps absolute")?;
+		writeln!(inner, "no internal events logged")?;
+		writeln!(inner, "Begin Triggerblock {date}")?;
+		writeln!(inner, "   0.000000 Start of measurement")?;
+		Ok(Self { inner })
+	}
+
+	/// Write a single entry to the file.
+	///
+	/// Writing a CAN XL frame fails with an error of kind [`std::io::ErrorKind::InvalidInput`].
+	pub fn write_entry(&mut self, entry: &AscEntry) -> std::io::Result<()> {
+		let timestamp = format!("{:4}.{:06}", entry.timestamp.as_secs(), entry.timestamp.subsec_micros());
+		let direction = match entry.direction {
+			Direction::Rx => "Rx",
+			Direction::Tx => "Tx",
+		};
+		let channel = entry.channel;
+
+		match &entry.frame {
+			AnyCanFrame::Classic(frame) => {
+				let id = format_id(frame.id());
+				let dlc = frame.data_length_code();
+				match frame.data() {
+					Some(data) => writeln!(self.inner, "{timestamp} {channel:<2} {id:<15} {direction}   d {dlc:X}{}", format_data(&data)),
+					None => writeln!(self.inner, "{timestamp} {channel:<2} {id:<15} {direction}   r {dlc:X}"),
+				}
+			},
+			AnyCanFrame::Fd(frame) => {
+				let mut flags = ASC_F_FDF;
+				if frame.bit_rate_switch() {
+					flags |= ASC_F_BRS;
+				}
+				if frame.error_state_indicator() {
+					flags |= ASC_F_ESI;
+				}
+				let data = frame.data();
+				writeln!(
+					self.inner,
+					"{timestamp} CANFD {channel:>3} {direction} {:>11} {} {} {:X} {:>2}{} 0 0 {flags:>8X} 0 0 0 0 0",
+					format_id(frame.id()),
+					u8::from(frame.bit_rate_switch()),
+					u8::from(frame.error_state_indicator()),
+					frame.data_length_code(),
+					data.len(),
+					format_data(&data),
+				)
+			},
+			AnyCanFrame::Error(_) => writeln!(self.inner, "{timestamp} {channel:<2} ErrorFrame"),
+			AnyCanFrame::Xl(_) => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "CAN XL frames can not be written to ASC files")),
+		}
+	}
+
+	/// Close the trigger block and return the wrapped writer.
+	pub fn finish(mut self) -> std::io::Result<W> {
+		writeln!(self.inner, "End TriggerBlock")?;
+		self.inner.flush()?;
+		Ok(self.inner)
+	}
+
+	/// Flush the wrapped writer.
+	pub fn flush(&mut self) -> std::io::Result<()> {
+		self.inner.flush()
+	}
+
+	/// Get a reference to the wrapped writer.
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+
+	/// Get a mutable reference to the wrapped writer.
+	pub fn get_mut(&mut self) -> &mut W {
+		&mut self.inner
+	}
+}
+
+/// Check if a line holds no information for the reader.
+fn is_ignored_line(line: &str) -> bool {
+	line.is_empty()
+		|| line.starts_with("//")
+		|| line.starts_with("Begin Triggerblock")
+		|| line.starts_with("End TriggerBlock")
+}
+
+/// Create the error frame used for `ErrorFrame` events.
+///
+/// The ASC format does not record the error details, so the frame reports a generic bus error.
+fn error_frame() -> AnyCanFrame {
+	CanErrorFrame {
+		inner: crate::sys::CanErrorFrame::from_raw(CanErrorMask::BUS_ERROR.bits(), [0; 8]),
+	}.into()
+}
+
+fn format_id(id: CanId) -> String {
+	match id {
+		CanId::Standard(id) => format!("{:X}", id.as_u16()),
+		CanId::Extended(id) => format!("{:X}x", id.as_u32()),
+	}
+}
+
+fn format_data(data: &[u8]) -> String {
+	data.iter().map(|byte| format!(" {byte:02X}")).collect()
+}
+
+/// Format a time in UTC like CANalyzer does, for example `Tue Nov 14 10:13:20.000 pm 2023`.
+fn format_date(time: SystemTime) -> String {
+	// The UNIX epoch was on a Thursday.
+	const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+	const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+	let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+	let days = since_epoch.as_secs() / 86400;
+	let seconds = since_epoch.as_secs() % 86400;
+	let (year, month, day) = civil_from_days(days);
+	let (hour, am_pm) = match seconds / 3600 {
+		0 => (12, "am"),
+		hour @ 1..=11 => (hour, "am"),
+		12 => (12, "pm"),
+		hour => (hour - 12, "pm"),
+	};
+	format!(
+		"{} {} {:02} {:02}:{:02}:{:02}.{:03} {am_pm} {year}",
+		WEEKDAYS[(days % 7) as usize],
+		MONTHS[month - 1],
+		day,
+		hour,
+		seconds / 60 % 60,
+		seconds % 60,
+		since_epoch.subsec_millis(),
+	)
+}
+
+/// Convert a number of days since the UNIX epoch to a year, month and day.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: u64) -> (u64, usize, u64) {
+	let z = days + 719_468;
+	let era = z / 146_097;
+	let day_of_era = z % 146_097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+	let year = era * 400 + year_of_era + u64::from(month <= 2);
+	(year, month as usize, day)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::{ExtendedId, StandardId};
+
+	const TRACE: &str = "\
+date Tue Nov 14 10:13:20.000 pm 2023
+base hex  timestamps absolute
+internal events logged
+// version 9.0.0
+Begin Triggerblock Tue Nov 14 10:13:20.000 pm 2023
+   0.000000 Start of measurement
+   0.001000 1  123             Rx   d 8 01 02 03 04 05 06 07 08  Length = 230000 BitCount = 118 ID = 291
+   0.002000 2  1234567x        Tx   d 2 AA BB
+   0.003000 1  Statistic: D 0 R 0 XD 0 XR 0 E 0 O 0 B 0.00%
+   0.004000 1  7FF             Rx   r 4
+   0.005000 1  ErrorFrame
+   0.006000 CANFD   1 Rx        456  SomeMessage                      1 0 9 12 01 02 03 04 05 06 07 08 09 0A 0B 0C   130000  130     3000 0 0 0 0 0
+   0.007000 CANFD   1 Tx        100                                   0 0 8  8 11 22 33 44 55 66 77 88   130000  130        0 0 0 0 0 0
+End TriggerBlock
+";
+
+	#[test]
+	fn read_trace() {
+		let_assert!(Ok(mut reader) = AscReader::new(TRACE.as_bytes()));
+		assert!(reader.date() == Some("Tue Nov 14 10:13:20.000 pm 2023"));
+
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		assert!(entry.timestamp == Duration::from_millis(1));
+		assert!(entry.channel == 1);
+		assert!(entry.direction == Direction::Rx);
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.id() == StandardId::new(0x123).unwrap());
+		assert!(frame.data() == Some(CanData::new([1, 2, 3, 4, 5, 6, 7, 8])));
+
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		assert!(entry.channel == 2);
+		assert!(entry.direction == Direction::Tx);
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.id() == ExtendedId::new(0x123_4567).unwrap());
+		assert!(frame.data() == Some(CanData::new([0xAA, 0xBB])));
+
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		assert!(entry.timestamp == Duration::from_millis(4));
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 4);
+
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		let_assert!(AnyCanFrame::Error(frame) = entry.frame);
+		assert!(frame.is_bus_error());
+
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		let_assert!(AnyCanFrame::Fd(frame) = entry.frame);
+		assert!(frame.id() == StandardId::new(0x456).unwrap());
+		assert!(frame.bit_rate_switch());
+		assert!(frame.data().as_slice() == [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+
+		// A classic CAN frame in a `CANFD` line.
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		assert!(entry.direction == Direction::Tx);
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.data() == Some(CanData::new([0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88])));
+
+		let_assert!(Ok(None) = reader.read_entry());
+	}
+
+	#[test]
+	fn read_decimal_relative_trace() {
+		let trace = "base dec  timestamps relative\n0.500000 1 291 Rx d 2 1 255\n0.250000 1 100x Rx d 0\n";
+		let_assert!(Ok(reader) = AscReader::new(trace.as_bytes()));
+		let_assert!(Ok(entries) = reader.collect::<Result<Vec<_>, _>>());
+		assert!(entries.len() == 2);
+		assert!(entries[0].timestamp == Duration::from_millis(500));
+		assert!(entries[1].timestamp == Duration::from_millis(750));
+		let_assert!(AnyCanFrame::Classic(frame) = &entries[0].frame);
+		assert!(frame.id() == StandardId::new(0x123).unwrap());
+		assert!(frame.data() == Some(CanData::new([1, 255])));
+		let_assert!(AnyCanFrame::Classic(frame) = &entries[1].frame);
+		assert!(frame.id() == ExtendedId::new(100).unwrap());
+	}
+
+	#[test]
+	fn read_invalid_frame() {
+		let trace = "base hex  timestamps absolute\n0.1 1 800 Rx d 0\n0.2 1 123 Rx d 2 01\n0.3 1 123 Rx d 1 01\n";
+		let_assert!(Ok(mut reader) = AscReader::new(trace.as_bytes()));
+		let_assert!(Err(e) = reader.read_entry());
+		assert!(e.to_string().starts_with("line 2: "));
+		let_assert!(Err(e) = reader.read_entry());
+		assert!(e.to_string().starts_with("line 3: "));
+		let_assert!(Ok(Some(_)) = reader.read_entry());
+	}
+
+	#[test]
+	fn write_and_read_back() {
+		let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+		let_assert!(Ok(mut writer) = AscWriter::new(Vec::new(), start));
+		let entries = [
+			AscEntry::new(Duration::from_micros(1500), 1, Direction::Rx, CanFrame::new(0x123u16, [1, 2, 3])),
+			AscEntry::new(Duration::from_micros(2500), 2, Direction::Tx, CanFrame::new(ExtendedId::new(0x1234).unwrap(), [0xFF])),
+			AscEntry::new(Duration::from_micros(3500), 1, Direction::Rx, CanFrame::new_rtr(0x7FFu16).with_data_length_code(2).unwrap()),
+			AscEntry::new(Duration::from_micros(4500), 1, Direction::Rx, CanFdFrame::new(0x10u16, [9; 16]).with_bit_rate_switch(true)),
+		];
+		for entry in &entries {
+			let_assert!(Ok(()) = writer.write_entry(entry));
+		}
+		let_assert!(Ok(output) = writer.finish());
+		let_assert!(Ok(output) = String::from_utf8(output));
+		assert!(output.starts_with("date Tue Nov 14 10:13:20.000 pm 2023\n"));
+		assert!(output.ends_with("End TriggerBlock\n"));
+
+		let_assert!(Ok(reader) = AscReader::new(output.as_bytes()));
+		let_assert!(Ok(read) = reader.collect::<Result<Vec<_>, _>>());
+		assert!(read.len() == entries.len());
+		for (read, written) in read.iter().zip(&entries) {
+			assert!(read.timestamp == written.timestamp);
+			assert!(read.channel == written.channel);
+			assert!(read.direction == written.direction);
+		}
+		let_assert!(AnyCanFrame::Classic(frame) = &read[1].frame);
+		assert!(frame.id() == ExtendedId::new(0x1234).unwrap());
+		let_assert!(AnyCanFrame::Classic(frame) = &read[2].frame);
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 2);
+		let_assert!(AnyCanFrame::Fd(frame) = &read[3].frame);
+		assert!(frame.bit_rate_switch());
+		assert!(frame.data().as_slice() == [9; 16]);
+	}
+
+	#[test]
+	fn convert_log_entry() {
+		let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+		let entry = AscEntry::new(Duration::from_millis(250), 1, Direction::Rx, CanFrame::new(0x123u16, [1]));
+		let entry = entry.into_log_entry(start, "can0");
+		assert!(entry.timestamp == start + Duration::from_millis(250));
+		assert!(entry.interface == "can0");
+		let entry = AscEntry::from_log_entry(entry, start, 3);
+		assert!(entry.timestamp == Duration::from_millis(250));
+		assert!(entry.channel == 3);
+	}
+
+	#[test]
+	fn dates() {
+		assert!(format_date(SystemTime::UNIX_EPOCH) == "Thu Jan 01 12:00:00.000 am 1970");
+		assert!(format_date(SystemTime::UNIX_EPOCH + Duration::from_millis(951_825_600_250)) == "Tue Feb 29 12:00:00.250 pm 2000");
+	}
+}
@@ -0,0 +1,310 @@
+use std::io::{BufRead, Write};
+use std::time::SystemTime;
+
+use crate::error::ParseLogEntryError;
+use crate::syntax::{parse_frame, write_frame};
+use super::{error, parse_seconds, LogEntry};
+
+/// Reader for log files in the format of `candump -l`.
+///
+/// Each line holds a timestamp, an interface name and a frame, for example:
+/// `(1700000000.123456) can0 123#DEADBEEF`.
+/// Empty lines are skipped.
+///
+/// The reader can also be used as an iterator over the entries in the file.
+#[derive(Debug)]
+pub struct CandumpReader<R> {
+	inner: R,
+	line: String,
+	line_number: usize,
+}
+
+impl<R: BufRead> CandumpReader<R> {
+	/// Create a new reader.
+	pub fn new(inner: R) -> Self {
+		Self {
+			inner,
+			line: String::new(),
+			line_number: 0,
+		}
+	}
+
+	/// Read the next entry from the log file.
+	///
+	/// Returns `Ok(None)` at the end of the file.
+	/// If a line can not be parsed, an error of kind [`std::io::ErrorKind::InvalidData`] is returned.
+	/// The next call will continue with the following line.
+	pub fn read_entry(&mut self) -> std::io::Result<Option<LogEntry>> {
+		loop {
+			self.line.clear();
+			if self.inner.read_line(&mut self.line)? == 0 {
+				return Ok(None);
+			}
+			self.line_number += 1;
+
+			let line = self.line.trim();
+			if line.is_empty() {
+				continue;
+			}
+
+			return line.parse()
+				.map(Some)
+				.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: {e}", self.line_number)));
+		}
+	}
+
+	/// Get a reference to the wrapped reader.
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	/// Get a mutable reference to the wrapped reader.
+	pub fn get_mut(&mut self) -> &mut R {
+		&mut self.inner
+	}
+
+	/// Consume the reader and return the wrapped reader.
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: BufRead> Iterator for CandumpReader<R> {
+	type Item = std::io::Result<LogEntry>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_entry().transpose()
+	}
+}
+
+/// Writer for log files in the format of `candump -l`.
+///
+/// Timestamps are written with microsecond resolution, like `candump` does.
+/// The writer does not buffer its output,
+/// so you may want to wrap the inner writer in a [`std::io::BufWriter`].
+#[derive(Debug)]
+pub struct CandumpWriter<W> {
+	inner: W,
+}
+
+impl<W: Write> CandumpWriter<W> {
+	/// Create a new writer.
+	pub fn new(inner: W) -> Self {
+		Self { inner }
+	}
+
+	/// Write a single entry to the log file.
+	pub fn write_entry(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+		writeln!(self.inner, "{entry}")
+	}
+
+	/// Flush the wrapped writer.
+	pub fn flush(&mut self) -> std::io::Result<()> {
+		self.inner.flush()
+	}
+
+	/// Get a reference to the wrapped writer.
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+
+	/// Get a mutable reference to the wrapped writer.
+	pub fn get_mut(&mut self) -> &mut W {
+		&mut self.inner
+	}
+
+	/// Consume the writer and return the wrapped writer.
+	pub fn into_inner(self) -> W {
+		self.inner
+	}
+}
+
+/// Parse a single line in the format of `candump -l`.
+impl std::str::FromStr for LogEntry {
+	type Err = ParseLogEntryError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		let mut fields = input.split_ascii_whitespace();
+		let timestamp = parse_timestamp(fields.next().ok_or(error("missing timestamp"))?)?;
+		let interface = fields.next().ok_or(error("missing interface name"))?;
+		let frame = parse_frame(fields.next().ok_or(error("missing frame"))?)?;
+
+		// Newer versions of `candump` may add a direction marker after the frame.
+		if !matches!(fields.next(), None | Some("R") | Some("T")) || fields.next().is_some() {
+			return Err(error("unexpected data after the frame"));
+		}
+
+		Ok(Self {
+			timestamp,
+			interface: interface.into(),
+			frame,
+		})
+	}
+}
+
+/// Format the entry as a single line in the format of `candump -l`, without a trailing newline.
+///
+/// Timestamps before the UNIX epoch can not be represented and are written as 0.
+impl std::fmt::Display for LogEntry {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let timestamp = self.timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+		write!(f, "({:010}.{:06}) {} ", timestamp.as_secs(), timestamp.subsec_micros(), self.interface)?;
+		write_frame(f, &self.frame)
+	}
+}
+
+/// Parse a timestamp in the form `(seconds.fraction)`.
+fn parse_timestamp(input: &str) -> Result<SystemTime, ParseLogEntryError> {
+	input.strip_prefix('(')
+		.and_then(|input| input.strip_suffix(')'))
+		.and_then(parse_seconds)
+		.and_then(|timestamp| SystemTime::UNIX_EPOCH.checked_add(timestamp))
+		.ok_or(error("invalid timestamp"))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::time::Duration;
+	use assert2::{assert, let_assert};
+	use crate::{AnyCanFrame, CanData, CanErrorMask, ExtendedId, StandardId};
+
+	fn parse(line: &str) -> LogEntry {
+		let_assert!(Ok(entry) = line.parse::<LogEntry>());
+		entry
+	}
+
+	#[test]
+	fn parse_classic_frame() {
+		let entry = parse("(1700000000.123456) can0 123#DEADBEEF");
+		assert!(entry.timestamp == SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_000));
+		assert!(entry.interface == "can0");
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.id() == StandardId::new(0x123).unwrap());
+		assert!(frame.data() == Some(CanData::new([0xDE, 0xAD, 0xBE, 0xEF])));
+
+		let entry = parse("(1.5) vcan1 12345678#DE.AD.BE.EF T");
+		assert!(entry.timestamp == SystemTime::UNIX_EPOCH + Duration::from_millis(1500));
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.id() == ExtendedId::new(0x1234_5678).unwrap());
+		assert!(frame.data() == Some(CanData::new([0xDE, 0xAD, 0xBE, 0xEF])));
+	}
+
+	#[test]
+	fn parse_rtr_frame() {
+		let_assert!(AnyCanFrame::Classic(frame) = parse("(0.0) can0 123#R").frame);
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 0);
+
+		let_assert!(AnyCanFrame::Classic(frame) = parse("(0.0) can0 123#R3").frame);
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 3);
+
+		let_assert!(AnyCanFrame::Classic(frame) = parse("(0.0) can0 123#R8_C").frame);
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 12);
+	}
+
+	#[test]
+	fn parse_fd_frame() {
+		let_assert!(AnyCanFrame::Fd(frame) = parse("(0.0) can0 123##50102030405060708090A").frame);
+		assert!(frame.id() == StandardId::new(0x123).unwrap());
+		assert!(frame.bit_rate_switch());
+		assert!(!frame.error_state_indicator());
+		// The data is padded to the next valid CAN FD data length.
+		assert!(frame.data().as_slice() == [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0]);
+	}
+
+	#[test]
+	fn parse_error_frame() {
+		let_assert!(AnyCanFrame::Error(frame) = parse("(0.0) can0 20000004#0004").frame);
+		assert!(frame.error_class() == CanErrorMask::CONTROLLER);
+		assert!(frame.data() == [0, 4, 0, 0, 0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn parse_xl_frame() {
+		let_assert!(AnyCanFrame::Xl(frame) = parse("(0.0) can0 02123#81:03:00000456#DEADBEEF").frame);
+		assert!(frame.virtual_can_id() == 0x02);
+		assert!(frame.priority() == StandardId::new(0x123).unwrap());
+		assert!(frame.simple_extended_content());
+		assert!(frame.sdu_type() == 0x03);
+		assert!(frame.acceptance_field() == 0x456);
+		assert!(frame.data().as_slice() == [0xDE, 0xAD, 0xBE, 0xEF]);
+	}
+
+	#[test]
+	fn format_round_trip() {
+		for line in [
+			"(1700000000.123456) can0 123#DEADBEEF",
+			"(0000000001.000001) can0 123#",
+			"(0000000001.000001) can0 00000123#0011223344556677",
+			"(0000000001.000001) can0 7FF#0011223344556677_F",
+			"(0000000001.000001) can0 123#R",
+			"(0000000001.000001) can0 123#R7",
+			"(0000000001.000001) can0 123#R8_9",
+			"(0000000001.000001) can0 123##4",
+			"(0000000001.000001) can0 1FFFFFFF##7DEADBEEF",
+			"(0000000001.000001) can0 20000044#0000000000000000",
+			"(0000000001.000001) can0 00123#80:00:00000000#DEADBEEF",
+		] {
+			assert!(parse(line).to_string() == line);
+		}
+	}
+
+	#[test]
+	fn parse_invalid_lines() {
+		for line in [
+			"",
+			"(0.0)",
+			"(0.0) can0",
+			"(0.0) can0 123",
+			"0.0 can0 123#",
+			"(0) can0 123#",
+			"(-1.0) can0 123#",
+			"(0.0) can0 800#",
+			"(0.0) can0 1234#",
+			"(0.0) can0 123#ABC",
+			"(0.0) can0 123#AZ",
+			"(0.0) can0 123#112233445566778899",
+			"(0.0) can0 123#1122_9",
+			"(0.0) can0 123#R9",
+			"(0.0) can0 123#R1_9",
+			"(0.0) can0 123##",
+			"(0.0) can0 123##X",
+			"(0.0) can0 60000000#",
+			"(0.0) can0 00123#00:00:00000000#00",
+			"(0.0) can0 00123#80:00:00000000#",
+			"(0.0) can0 123# R extra",
+		] {
+			assert!(let Err(_) = line.parse::<LogEntry>(), "{line:?}");
+		}
+	}
+
+	#[test]
+	fn read_and_write() {
+		let input = "(1700000000.000000) can0 123#01\n\n(1700000000.500000) can1 456#R2\r\n";
+		let reader = CandumpReader::new(input.as_bytes());
+		let_assert!(Ok(entries) = reader.collect::<Result<Vec<_>, _>>());
+		assert!(entries.len() == 2);
+		assert!(entries[1].interface == "can1");
+
+		let mut writer = CandumpWriter::new(Vec::new());
+		for entry in &entries {
+			let_assert!(Ok(()) = writer.write_entry(entry));
+		}
+		let_assert!(Ok(output) = String::from_utf8(writer.into_inner()));
+		assert!(output == "(1700000000.000000) can0 123#01\n(1700000000.500000) can1 456#R2\n");
+	}
+
+	#[test]
+	fn read_invalid_line() {
+		let mut reader = CandumpReader::new("(0.0) can0 123#01\n(0.0) can0 bad\n(0.0) can0 123#02\n".as_bytes());
+		let_assert!(Ok(Some(_)) = reader.read_entry());
+		let_assert!(Err(e) = reader.read_entry());
+		assert!(e.kind() == std::io::ErrorKind::InvalidData);
+		assert!(e.to_string().starts_with("line 2: "));
+		let_assert!(Ok(Some(_)) = reader.read_entry());
+		let_assert!(Ok(None) = reader.read_entry());
+	}
+}
@@ -0,0 +1,80 @@
+//! Reading and writing CAN log files.
+//!
+//! Each entry in a log file is represented by a [`LogEntry`],
+//! which holds the frame together with the time it was captured and the name of the interface it was captured on.
+//!
+//! Supported formats:
+//! * The log format of `candump -l` from `can-utils`, with [`CandumpReader`] and [`CandumpWriter`].
+//! * The Vector ASC format used by CANalyzer and CANoe, with [`AscReader`] and [`AscWriter`].
+//! * The pcap and pcapng capture formats with the `LINKTYPE_CAN_SOCKETCAN` link type as used by Wireshark, with [`PcapReader`] and [`PcapngWriter`].
+
+use std::time::{Duration, SystemTime};
+
+use crate::error::ParseLogEntryError;
+use crate::AnyCanFrame;
+
+mod asc;
+pub use asc::{AscEntry, AscReader, AscWriter};
+
+mod candump;
+pub use candump::{CandumpReader, CandumpWriter};
+
+mod pcap;
+pub use pcap::{PcapReader, PcapngWriter};
+
+/// A single frame in a CAN log file.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+	/// The time at which the frame was captured.
+	pub timestamp: SystemTime,
+
+	/// The name of the interface the frame was captured on.
+	pub interface: String,
+
+	/// The captured frame.
+	pub frame: AnyCanFrame,
+}
+
+impl LogEntry {
+	/// Create a new log entry.
+	pub fn new(timestamp: SystemTime, interface: impl Into<String>, frame: impl Into<AnyCanFrame>) -> Self {
+		Self {
+			timestamp,
+			interface: interface.into(),
+			frame: frame.into(),
+		}
+	}
+}
+
+/// The direction of a logged frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction {
+	/// The frame was received.
+	Rx,
+
+	/// The frame was transmitted.
+	Tx,
+}
+
+fn error(message: &'static str) -> ParseLogEntryError {
+	ParseLogEntryError { message }
+}
+
+/// Parse a number of seconds with a fraction, like `12.345678`.
+///
+/// Digits beyond nanosecond resolution are ignored.
+fn parse_seconds(input: &str) -> Option<Duration> {
+	let (seconds, fraction) = input.split_once('.')?;
+	if !is_decimal(seconds) || !is_decimal(fraction) {
+		return None;
+	}
+
+	let seconds: u64 = seconds.parse().ok()?;
+	let fraction = &fraction[..fraction.len().min(9)];
+	let nanos = fraction.parse::<u32>().ok()? * 10u32.pow(9 - fraction.len() as u32);
+	Some(Duration::new(seconds, nanos))
+}
+
+fn is_decimal(input: &str) -> bool {
+	!input.is_empty() && input.bytes().all(|c| c.is_ascii_digit())
+}
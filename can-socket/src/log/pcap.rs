@@ -0,0 +1,687 @@
+use std::io::{Read, Write};
+use std::time::{Duration, SystemTime};
+
+use crate::error::ParseLogEntryError;
+use crate::{AnyCanFrame, CanData, CanErrorFrame, CanFdData, CanFdFrame, CanFrame, CanId};
+use super::{error, LogEntry};
+
+/// The link type for SocketCAN frames.
+const LINKTYPE_CAN_SOCKETCAN: u16 = 227;
+
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_RTR_FLAG: u32 = 0x4000_0000;
+const CAN_ERR_FLAG: u32 = 0x2000_0000;
+const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+const CAN_SFF_MASK: u32 = 0x0000_07FF;
+
+const CANFD_BRS: u8 = 0x01;
+const CANFD_ESI: u8 = 0x02;
+const CANFD_FDF: u8 = 0x04;
+const CANXL_XLF: u8 = 0x80;
+
+/// The size of a classic CAN frame in a capture.
+const CAN_MTU: usize = 16;
+
+/// The size of a CAN FD frame in a capture.
+const CANFD_MTU: usize = 72;
+
+const PCAP_MAGIC_MICROS: u32 = 0xA1B2_C3D4;
+const PCAP_MAGIC_NANOS: u32 = 0xA1B2_3C4D;
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const PCAPNG_ENHANCED_PACKET: u32 = 0x0000_0006;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const PCAPNG_OPT_END: u16 = 0;
+const PCAPNG_OPT_IF_NAME: u16 = 2;
+const PCAPNG_OPT_IF_TSRESOL: u16 = 9;
+
+/// Blocks larger than this are rejected instead of allocating a buffer for them.
+const MAX_BLOCK_LEN: usize = 16 * 1024 * 1024;
+
+/// Reader for pcap and pcapng capture files with the `LINKTYPE_CAN_SOCKETCAN` link type.
+///
+/// These files are written by Wireshark, `tcpdump` and [`PcapngWriter`].
+/// The reader supports classic CAN frames, CAN FD frames and error frames.
+///
+/// For pcapng files, the interface name of each entry is taken from the interface description block.
+/// Packets from interfaces with a different link type are skipped.
+/// Plain pcap files do not record interface names, so the interface name of each entry is empty.
+///
+/// The reader can also be used as an iterator over the entries in the file.
+#[derive(Debug)]
+pub struct PcapReader<R> {
+	inner: R,
+	format: Format,
+}
+
+#[derive(Debug)]
+enum Format {
+	Pcap {
+		big_endian: bool,
+		nanos: bool,
+	},
+	Pcapng {
+		big_endian: bool,
+		interfaces: Vec<PcapngInterface>,
+	},
+}
+
+#[derive(Debug)]
+struct PcapngInterface {
+	link_type: u16,
+	name: String,
+	timestamp_resolution: u8,
+}
+
+impl<R: Read> PcapReader<R> {
+	/// Create a new reader and parse the file header.
+	///
+	/// This fails with an error of kind [`std::io::ErrorKind::InvalidData`]
+	/// if the file is not a pcap or pcapng file,
+	/// or if it is a pcap file with a different link type.
+	pub fn new(mut inner: R) -> std::io::Result<Self> {
+		let mut magic = [0; 4];
+		inner.read_exact(&mut magic)?;
+
+		let format = if u32::from_le_bytes(magic) == PCAPNG_SECTION_HEADER {
+			let big_endian = read_section_header(&mut inner)?;
+			Format::Pcapng {
+				big_endian,
+				interfaces: Vec::new(),
+			}
+		} else {
+			let (big_endian, nanos) = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+				(PCAP_MAGIC_MICROS, _) => (false, false),
+				(PCAP_MAGIC_NANOS, _) => (false, true),
+				(_, PCAP_MAGIC_MICROS) => (true, false),
+				(_, PCAP_MAGIC_NANOS) => (true, true),
+				_ => return Err(invalid_data(error("not a pcap or pcapng file"))),
+			};
+			let mut header = [0; 20];
+			inner.read_exact(&mut header)?;
+			let link_type = read_u32(&header[16..], big_endian) & 0xFFFF;
+			if link_type != LINKTYPE_CAN_SOCKETCAN.into() {
+				return Err(invalid_data(error("pcap file does not have the LINKTYPE_CAN_SOCKETCAN link type")));
+			}
+			Format::Pcap { big_endian, nanos }
+		};
+
+		Ok(Self { inner, format })
+	}
+
+	/// Read the next entry from the file.
+	///
+	/// Returns `Ok(None)` at the end of the file.
+	pub fn read_entry(&mut self) -> std::io::Result<Option<LogEntry>> {
+		match &mut self.format {
+			Format::Pcap { big_endian, nanos } => {
+				let mut header = [0; 16];
+				if !read_exact_or_eof(&mut self.inner, &mut header)? {
+					return Ok(None);
+				}
+				let seconds = read_u32(&header[0..], *big_endian);
+				let fraction = read_u32(&header[4..], *big_endian);
+				let captured_len = read_u32(&header[8..], *big_endian) as usize;
+				if captured_len > MAX_BLOCK_LEN {
+					return Err(invalid_data(error("packet too large")));
+				}
+				let mut data = vec![0; captured_len];
+				self.inner.read_exact(&mut data)?;
+
+				let fraction = match nanos {
+					true => Duration::from_nanos(fraction.into()),
+					false => Duration::from_micros(fraction.into()),
+				};
+				let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds.into()) + fraction;
+				let frame = decode_frame(&data).map_err(invalid_data)?;
+				Ok(Some(LogEntry::new(timestamp, String::new(), frame)))
+			},
+			Format::Pcapng { big_endian, interfaces } => loop {
+				let mut header = [0; 4];
+				if !read_exact_or_eof(&mut self.inner, &mut header)? {
+					return Ok(None);
+				}
+
+				if u32::from_le_bytes(header) == PCAPNG_SECTION_HEADER {
+					*big_endian = read_section_header(&mut self.inner)?;
+					interfaces.clear();
+					continue;
+				}
+
+				let block_type = read_u32(&header, *big_endian);
+				let body = read_block_body(&mut self.inner, *big_endian)?;
+				match block_type {
+					PCAPNG_INTERFACE_DESCRIPTION => {
+						interfaces.push(parse_interface_description(&body, *big_endian)?);
+					},
+					PCAPNG_ENHANCED_PACKET => {
+						if body.len() < 20 {
+							return Err(invalid_data(error("enhanced packet block too short")));
+						}
+						let interface_id = read_u32(&body[0..], *big_endian) as usize;
+						let timestamp_high = read_u32(&body[4..], *big_endian);
+						let timestamp_low = read_u32(&body[8..], *big_endian);
+						let captured_len = read_u32(&body[12..], *big_endian) as usize;
+						let data = body.get(20..)
+							.and_then(|data| data.get(..captured_len))
+							.ok_or_else(|| invalid_data(error("enhanced packet block too short")))?;
+						let interface = interfaces.get(interface_id)
+							.ok_or_else(|| invalid_data(error("packet refers to an unknown interface")))?;
+						if interface.link_type != LINKTYPE_CAN_SOCKETCAN {
+							continue;
+						}
+
+						let timestamp = u64::from(timestamp_high) << 32 | u64::from(timestamp_low);
+						let timestamp = SystemTime::UNIX_EPOCH.checked_add(timestamp_to_duration(timestamp, interface.timestamp_resolution))
+							.ok_or_else(|| invalid_data(error("timestamp out of range")))?;
+						let frame = decode_frame(data).map_err(invalid_data)?;
+						return Ok(Some(LogEntry::new(timestamp, interface.name.clone(), frame)));
+					},
+					_ => continue,
+				}
+			},
+		}
+	}
+
+	/// Get a reference to the wrapped reader.
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	/// Get a mutable reference to the wrapped reader.
+	pub fn get_mut(&mut self) -> &mut R {
+		&mut self.inner
+	}
+
+	/// Consume the reader and return the wrapped reader.
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+	type Item = std::io::Result<LogEntry>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_entry().transpose()
+	}
+}
+
+/// Writer for pcapng capture files with the `LINKTYPE_CAN_SOCKETCAN` link type.
+///
+/// The files can be opened with Wireshark.
+/// An interface description block is written for each interface the first time a frame from that interface is written.
+/// Timestamps are written with nanosecond resolution.
+///
+/// The writer supports classic CAN frames, CAN FD frames and error frames.
+/// The writer does not buffer its output,
+/// so you may want to wrap the inner writer in a [`std::io::BufWriter`].
+#[derive(Debug)]
+pub struct PcapngWriter<W> {
+	inner: W,
+	interfaces: Vec<String>,
+}
+
+impl<W: Write> PcapngWriter<W> {
+	/// Create a new writer and write the section header.
+	pub fn new(mut inner: W) -> std::io::Result<Self> {
+		let mut body = Vec::with_capacity(16);
+		body.extend_from_slice(&PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes());
+		body.extend_from_slice(&1u16.to_le_bytes());
+		body.extend_from_slice(&0u16.to_le_bytes());
+		// The length of the section is not known up front.
+		body.extend_from_slice(&(-1i64).to_le_bytes());
+		write_block(&mut inner, PCAPNG_SECTION_HEADER, &body)?;
+		Ok(Self {
+			inner,
+			interfaces: Vec::new(),
+		})
+	}
+
+	/// Write a single entry to the file.
+	///
+	/// Writing a CAN XL frame fails with an error of kind [`std::io::ErrorKind::InvalidInput`].
+	pub fn write_entry(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+		let data = encode_frame(&entry.frame)?;
+		let interface_id = self.interface_id(&entry.interface)?;
+
+		// Timestamps before the UNIX epoch can not be represented, so they are written as 0.
+		let timestamp = entry.timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+		let timestamp = u64::try_from(timestamp.as_nanos()).unwrap_or(u64::MAX);
+
+		let mut body = Vec::with_capacity(20 + data.len());
+		body.extend_from_slice(&interface_id.to_le_bytes());
+		body.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+		body.extend_from_slice(&(timestamp as u32).to_le_bytes());
+		body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		body.extend_from_slice(&data);
+		pad(&mut body);
+		write_block(&mut self.inner, PCAPNG_ENHANCED_PACKET, &body)
+	}
+
+	/// Flush the wrapped writer.
+	pub fn flush(&mut self) -> std::io::Result<()> {
+		self.inner.flush()
+	}
+
+	/// Get a reference to the wrapped writer.
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+
+	/// Get a mutable reference to the wrapped writer.
+	pub fn get_mut(&mut self) -> &mut W {
+		&mut self.inner
+	}
+
+	/// Consume the writer and return the wrapped writer.
+	pub fn into_inner(self) -> W {
+		self.inner
+	}
+
+	/// Get the ID of an interface, writing an interface description block if it is a new interface.
+	fn interface_id(&mut self, name: &str) -> std::io::Result<u32> {
+		if let Some(index) = self.interfaces.iter().position(|x| x == name) {
+			return Ok(index as u32);
+		}
+
+		let mut body = Vec::new();
+		body.extend_from_slice(&LINKTYPE_CAN_SOCKETCAN.to_le_bytes());
+		body.extend_from_slice(&0u16.to_le_bytes());
+		body.extend_from_slice(&(CANFD_MTU as u32).to_le_bytes());
+		if !name.is_empty() {
+			push_option(&mut body, PCAPNG_OPT_IF_NAME, name.as_bytes());
+		}
+		push_option(&mut body, PCAPNG_OPT_IF_TSRESOL, &[9]);
+		push_option(&mut body, PCAPNG_OPT_END, &[]);
+		write_block(&mut self.inner, PCAPNG_INTERFACE_DESCRIPTION, &body)?;
+
+		self.interfaces.push(name.into());
+		Ok(self.interfaces.len() as u32 - 1)
+	}
+}
+
+/// Encode a frame in the `LINKTYPE_CAN_SOCKETCAN` format.
+fn encode_frame(frame: &AnyCanFrame) -> std::io::Result<Vec<u8>> {
+	let (can_id, len, flags, len8_dlc, data, mtu) = match frame {
+		AnyCanFrame::Classic(frame) => {
+			let mut can_id = raw_id(frame.id());
+			let dlc = frame.data_length_code();
+			let len8_dlc = if dlc > 8 { dlc } else { 0 };
+			match frame.data() {
+				Some(data) => (can_id, data.len() as u8, 0, len8_dlc, data.to_vec(), CAN_MTU),
+				None => {
+					can_id |= CAN_RTR_FLAG;
+					(can_id, dlc.min(8), 0, len8_dlc, Vec::new(), CAN_MTU)
+				},
+			}
+		},
+		AnyCanFrame::Fd(frame) => {
+			let mut flags = CANFD_FDF;
+			if frame.bit_rate_switch() {
+				flags |= CANFD_BRS;
+			}
+			if frame.error_state_indicator() {
+				flags |= CANFD_ESI;
+			}
+			let data = frame.data();
+			(raw_id(frame.id()), data.len() as u8, flags, 0, data.to_vec(), CANFD_MTU)
+		},
+		AnyCanFrame::Error(frame) => {
+			(CAN_ERR_FLAG | frame.error_class().bits(), 8, 0, 0, frame.data().to_vec(), CAN_MTU)
+		},
+		AnyCanFrame::Xl(_) => {
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "CAN XL frames are not supported in capture files"));
+		},
+	};
+
+	let mut output = Vec::with_capacity(mtu);
+	output.extend_from_slice(&can_id.to_be_bytes());
+	output.extend_from_slice(&[len, flags, 0, len8_dlc]);
+	output.extend_from_slice(&data);
+	output.resize(mtu, 0);
+	Ok(output)
+}
+
+/// Decode a frame in the `LINKTYPE_CAN_SOCKETCAN` format.
+fn decode_frame(data: &[u8]) -> Result<AnyCanFrame, ParseLogEntryError> {
+	let Some((header, payload)) = data.split_first_chunk::<8>() else {
+		return Err(error("packet too short for a CAN frame"));
+	};
+	let can_id = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+	let len = usize::from(header[4]);
+	let flags = header[5];
+	let len8_dlc = header[7];
+	if flags & CANXL_XLF != 0 {
+		return Err(error("CAN XL frames are not supported"));
+	}
+	let fd = flags & CANFD_FDF != 0 || data.len() == CANFD_MTU;
+	let rtr = !fd && can_id & CAN_RTR_FLAG != 0;
+	let payload = match rtr {
+		true => &[],
+		false => payload.get(..len).ok_or(error("packet too short for the frame data"))?,
+	};
+
+	if can_id & CAN_ERR_FLAG != 0 {
+		let mut data = [0; 8];
+		let len = payload.len().min(8);
+		data[..len].copy_from_slice(&payload[..len]);
+		let frame = CanErrorFrame {
+			inner: crate::sys::CanErrorFrame::from_raw(can_id & CAN_EFF_MASK, data),
+		};
+		return Ok(frame.into());
+	}
+
+	let id = if can_id & CAN_EFF_FLAG != 0 {
+		CanId::new_extended(can_id & CAN_EFF_MASK)
+	} else {
+		CanId::new(can_id & CAN_SFF_MASK)
+	};
+	let id = id.map_err(|_| error("invalid CAN ID"))?;
+
+	if fd {
+		let data = CanFdData::try_from(payload).map_err(|_| error("too much data for a CAN FD frame"))?;
+		let frame = CanFdFrame::new(id, data)
+			.with_bit_rate_switch(flags & CANFD_BRS != 0)
+			.with_error_state_indicator(flags & CANFD_ESI != 0);
+		return Ok(frame.into());
+	}
+
+	let dlc = match len8_dlc {
+		9..=15 if len == 8 => len8_dlc,
+		_ => len as u8,
+	};
+	let frame = if rtr {
+		CanFrame::new_rtr(id)
+	} else {
+		let data = CanData::try_from(payload).map_err(|_| error("too much data for a classic CAN frame"))?;
+		CanFrame::new(id, data)
+	};
+	let frame = frame.with_data_length_code(dlc).map_err(|_| error("invalid data length code"))?;
+	Ok(frame.into())
+}
+
+fn raw_id(id: CanId) -> u32 {
+	match id {
+		CanId::Standard(id) => id.as_u16().into(),
+		CanId::Extended(id) => id.as_u32() | CAN_EFF_FLAG,
+	}
+}
+
+/// Read the rest of a pcapng section header block after the block type, and return the byte order of the section.
+fn read_section_header(stream: &mut impl Read) -> std::io::Result<bool> {
+	let mut header = [0; 8];
+	stream.read_exact(&mut header)?;
+	let big_endian = match read_u32(&header[4..], false) {
+		PCAPNG_BYTE_ORDER_MAGIC => false,
+		x if x.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => true,
+		_ => return Err(invalid_data(error("invalid byte order magic in pcapng section header"))),
+	};
+	let len = read_u32(&header, big_endian) as usize;
+	if !(28..=MAX_BLOCK_LEN).contains(&len) {
+		return Err(invalid_data(error("invalid pcapng block length")));
+	}
+	// Skip the version, section length, options and trailing block length.
+	std::io::copy(&mut stream.take(len as u64 - 12), &mut std::io::sink())?;
+	Ok(big_endian)
+}
+
+/// Read the length of a pcapng block and return the body of the block.
+fn read_block_body(stream: &mut impl Read, big_endian: bool) -> std::io::Result<Vec<u8>> {
+	let mut len = [0; 4];
+	stream.read_exact(&mut len)?;
+	let len = read_u32(&len, big_endian) as usize;
+	if !(12..=MAX_BLOCK_LEN).contains(&len) || len & 3 != 0 {
+		return Err(invalid_data(error("invalid pcapng block length")));
+	}
+	let mut body = vec![0; len - 8];
+	stream.read_exact(&mut body)?;
+	// Drop the trailing block length.
+	body.truncate(len - 12);
+	Ok(body)
+}
+
+fn parse_interface_description(body: &[u8], big_endian: bool) -> std::io::Result<PcapngInterface> {
+	if body.len() < 8 {
+		return Err(invalid_data(error("interface description block too short")));
+	}
+	let mut interface = PcapngInterface {
+		link_type: read_u16(&body[0..], big_endian),
+		name: String::new(),
+		timestamp_resolution: 6,
+	};
+
+	let mut options = &body[8..];
+	while options.len() >= 4 {
+		let code = read_u16(&options[0..], big_endian);
+		let len = usize::from(read_u16(&options[2..], big_endian));
+		let value = options.get(4..4 + len)
+			.ok_or_else(|| invalid_data(error("invalid option in interface description block")))?;
+		match code {
+			PCAPNG_OPT_END => break,
+			PCAPNG_OPT_IF_NAME => interface.name = String::from_utf8_lossy(value).into_owned(),
+			PCAPNG_OPT_IF_TSRESOL => interface.timestamp_resolution = value.first().copied().unwrap_or(6),
+			_ => (),
+		}
+		options = options.get(4 + len.next_multiple_of(4)..).unwrap_or_default();
+	}
+	Ok(interface)
+}
+
+/// Convert a pcapng timestamp to a duration using the `if_tsresol` option of the interface.
+fn timestamp_to_duration(timestamp: u64, resolution: u8) -> Duration {
+	let exponent = u32::from(resolution & 0x7F);
+	let nanos = if resolution & 0x80 != 0 {
+		(u128::from(timestamp) * 1_000_000_000) >> exponent
+	} else if exponent <= 9 {
+		u128::from(timestamp) * 10u128.pow(9 - exponent)
+	} else {
+		u128::from(timestamp) / 10u128.pow(exponent.min(38) - 9)
+	};
+	Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+}
+
+fn write_block(stream: &mut impl Write, block_type: u32, body: &[u8]) -> std::io::Result<()> {
+	let len = body.len() as u32 + 12;
+	let mut block = Vec::with_capacity(len as usize);
+	block.extend_from_slice(&block_type.to_le_bytes());
+	block.extend_from_slice(&len.to_le_bytes());
+	block.extend_from_slice(body);
+	block.extend_from_slice(&len.to_le_bytes());
+	stream.write_all(&block)
+}
+
+fn push_option(body: &mut Vec<u8>, code: u16, value: &[u8]) {
+	body.extend_from_slice(&code.to_le_bytes());
+	body.extend_from_slice(&(value.len() as u16).to_le_bytes());
+	body.extend_from_slice(value);
+	pad(body);
+}
+
+/// Pad a buffer with zeroes to a multiple of 4 bytes.
+fn pad(buffer: &mut Vec<u8>) {
+	buffer.resize(buffer.len().next_multiple_of(4), 0);
+}
+
+fn read_u16(bytes: &[u8], big_endian: bool) -> u16 {
+	let bytes = [bytes[0], bytes[1]];
+	match big_endian {
+		true => u16::from_be_bytes(bytes),
+		false => u16::from_le_bytes(bytes),
+	}
+}
+
+fn read_u32(bytes: &[u8], big_endian: bool) -> u32 {
+	let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+	match big_endian {
+		true => u32::from_be_bytes(bytes),
+		false => u32::from_le_bytes(bytes),
+	}
+}
+
+/// Fill the buffer, or return `false` if the stream is at the end of the file.
+fn read_exact_or_eof(stream: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<bool> {
+	let mut read = 0;
+	while read < buffer.len() {
+		match stream.read(&mut buffer[read..]) {
+			Ok(0) if read == 0 => return Ok(false),
+			Ok(0) => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+			Ok(n) => read += n,
+			Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(true)
+}
+
+fn invalid_data(error: ParseLogEntryError) -> std::io::Error {
+	error.into()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::{CanErrorMask, ExtendedId, StandardId};
+
+	fn entries() -> Vec<LogEntry> {
+		let start = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+		vec![
+			LogEntry::new(start, "can0", CanFrame::new(0x123u16, [1, 2, 3])),
+			LogEntry::new(start + Duration::from_millis(1), "can1", CanFrame::new_rtr(ExtendedId::new(0x1234_5678).unwrap()).with_data_length_code(4).unwrap()),
+			LogEntry::new(start + Duration::from_millis(2), "can0", CanFdFrame::new(0x456u16, [7; 12]).with_bit_rate_switch(true)),
+			LogEntry::new(start + Duration::from_millis(3), "can1", CanFrame::new(0x7FFu16, [0xFF; 8]).with_data_length_code(15).unwrap()),
+			LogEntry::new(start + Duration::from_millis(4), "can0", CanErrorFrame {
+				inner: crate::sys::CanErrorFrame::from_raw(CanErrorMask::NO_ACK.bits(), [0, 1, 2, 3, 4, 5, 6, 7]),
+			}),
+		]
+	}
+
+	#[test]
+	fn encode_classic_frame() {
+		let_assert!(Ok(data) = encode_frame(&CanFrame::new(ExtendedId::new(0x1234_5678).unwrap(), [0xAA, 0xBB]).into()));
+		assert!(data == [0x92, 0x34, 0x56, 0x78, 2, 0, 0, 0, 0xAA, 0xBB, 0, 0, 0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn write_and_read_back() {
+		let entries = entries();
+		let_assert!(Ok(mut writer) = PcapngWriter::new(Vec::new()));
+		for entry in &entries {
+			let_assert!(Ok(()) = writer.write_entry(entry));
+		}
+		let output = writer.into_inner();
+
+		let_assert!(Ok(reader) = PcapReader::new(output.as_slice()));
+		let_assert!(Ok(read) = reader.collect::<Result<Vec<_>, _>>());
+		assert!(read.len() == entries.len());
+		for (read, written) in read.iter().zip(&entries) {
+			assert!(read.timestamp == written.timestamp);
+			assert!(read.interface == written.interface);
+		}
+
+		let_assert!(AnyCanFrame::Classic(frame) = &read[0].frame);
+		assert!(frame.id() == StandardId::new(0x123).unwrap());
+		assert!(frame.data() == Some(CanData::new([1, 2, 3])));
+
+		let_assert!(AnyCanFrame::Classic(frame) = &read[1].frame);
+		assert!(frame.id() == ExtendedId::new(0x1234_5678).unwrap());
+		assert!(frame.is_rtr());
+		assert!(frame.data_length_code() == 4);
+
+		let_assert!(AnyCanFrame::Fd(frame) = &read[2].frame);
+		assert!(frame.bit_rate_switch());
+		assert!(frame.data().as_slice() == [7; 12]);
+
+		let_assert!(AnyCanFrame::Classic(frame) = &read[3].frame);
+		assert!(frame.data_length_code() == 15);
+
+		let_assert!(AnyCanFrame::Error(frame) = &read[4].frame);
+		assert!(frame.is_no_ack());
+		assert!(frame.data() == [0, 1, 2, 3, 4, 5, 6, 7]);
+	}
+
+	#[test]
+	fn write_interface_description_once() {
+		let_assert!(Ok(mut writer) = PcapngWriter::new(Vec::new()));
+		for entry in &entries() {
+			let_assert!(Ok(()) = writer.write_entry(entry));
+		}
+		let output = writer.into_inner();
+
+		let mut interfaces = 0;
+		let mut offset = 0;
+		while offset < output.len() {
+			let block_type = read_u32(&output[offset..], false);
+			if block_type == PCAPNG_INTERFACE_DESCRIPTION {
+				interfaces += 1;
+			}
+			offset += read_u32(&output[offset + 4..], false) as usize;
+		}
+		assert!(interfaces == 2);
+	}
+
+	#[test]
+	fn read_big_endian_pcap() {
+		let mut file = Vec::new();
+		file.extend_from_slice(&PCAP_MAGIC_MICROS.to_be_bytes());
+		file.extend_from_slice(&[0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 227]);
+		file.extend_from_slice(&[0x65, 0x53, 0xF1, 0x00, 0x00, 0x00, 0x01, 0xF4, 0, 0, 0, 16, 0, 0, 0, 16]);
+		file.extend_from_slice(&[0, 0, 0x01, 0x23, 1, 0, 0, 0, 0x42, 0, 0, 0, 0, 0, 0, 0]);
+
+		let_assert!(Ok(mut reader) = PcapReader::new(file.as_slice()));
+		let_assert!(Ok(Some(entry)) = reader.read_entry());
+		assert!(entry.timestamp == SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000) + Duration::from_micros(500));
+		assert!(entry.interface.is_empty());
+		let_assert!(AnyCanFrame::Classic(frame) = entry.frame);
+		assert!(frame.id() == StandardId::new(0x123).unwrap());
+		assert!(frame.data() == Some(CanData::new([0x42])));
+		let_assert!(Ok(None) = reader.read_entry());
+	}
+
+	#[test]
+	fn reject_other_files() {
+		let_assert!(Err(e) = PcapReader::new(&b"(1700000000.000000) can0 123#"[..]));
+		assert!(e.kind() == std::io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn reject_timestamp_out_of_range() {
+		let_assert!(Ok(writer) = PcapngWriter::new(Vec::new()));
+		let mut file = writer.into_inner();
+
+		// An interface with timestamps in seconds.
+		let mut body = Vec::new();
+		body.extend_from_slice(&LINKTYPE_CAN_SOCKETCAN.to_le_bytes());
+		body.extend_from_slice(&0u16.to_le_bytes());
+		body.extend_from_slice(&(CANFD_MTU as u32).to_le_bytes());
+		push_option(&mut body, PCAPNG_OPT_IF_TSRESOL, &[0]);
+		push_option(&mut body, PCAPNG_OPT_END, &[]);
+		let_assert!(Ok(()) = write_block(&mut file, PCAPNG_INTERFACE_DESCRIPTION, &body));
+
+		let_assert!(Ok(data) = encode_frame(&CanFrame::new(0x123u16, [1]).into()));
+		let mut body = Vec::new();
+		body.extend_from_slice(&0u32.to_le_bytes());
+		body.extend_from_slice(&u32::MAX.to_le_bytes());
+		body.extend_from_slice(&u32::MAX.to_le_bytes());
+		body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		body.extend_from_slice(&data);
+		let_assert!(Ok(()) = write_block(&mut file, PCAPNG_ENHANCED_PACKET, &body));
+
+		let_assert!(Ok(mut reader) = PcapReader::new(file.as_slice()));
+		let_assert!(Err(e) = reader.read_entry());
+		assert!(e.kind() == std::io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn timestamp_resolution() {
+		assert!(timestamp_to_duration(1_500_000, 6) == Duration::from_millis(1500));
+		assert!(timestamp_to_duration(1_500, 3) == Duration::from_millis(1500));
+		assert!(timestamp_to_duration(15_000_000_000, 10) == Duration::from_millis(1500));
+		assert!(timestamp_to_duration(3 << 19, 0x80 | 20) == Duration::from_millis(1500));
+	}
+}
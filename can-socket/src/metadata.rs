@@ -0,0 +1,146 @@
+use crate::CanInterface;
+
+/// The type of timestamps to generate for received frames.
+///
+/// Used with [`CanSocket::set_timestamp_mode()`][crate::CanSocket::set_timestamp_mode].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum TimestampMode {
+	/// Do not generate timestamps (the default for new sockets).
+	#[default]
+	Disabled,
+
+	/// Generate software timestamps with nanosecond resolution, using `SO_TIMESTAMPNS`.
+	///
+	/// The timestamp is taken by the kernel when the frame is received by the network stack.
+	Software,
+
+	/// Generate software timestamps and raw hardware timestamps, using `SO_TIMESTAMPING`.
+	///
+	/// Hardware timestamps are only available if the CAN driver supports them.
+	SoftwareAndHardware,
+}
+
+/// The timestamps of a received frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Timestamps {
+	/// The software timestamp of the frame, generated by the kernel.
+	///
+	/// This is `None` if software timestamps are not enabled on the socket.
+	pub software: Option<std::time::SystemTime>,
+
+	/// The raw hardware timestamp of the frame, generated by the CAN controller.
+	///
+	/// The epoch of the timestamp depends on the driver.
+	/// Many drivers synchronize the hardware clock with the system clock,
+	/// in which case the timestamp is relative to the UNIX epoch.
+	///
+	/// This is `None` if hardware timestamps are not enabled on the socket,
+	/// or if they are not supported by the driver.
+	pub hardware: Option<std::time::Duration>,
+}
+
+/// Metadata of a received frame.
+#[derive(Debug, Clone)]
+pub struct RecvMetadata {
+	pub(crate) interface: CanInterface,
+	pub(crate) timestamps: Timestamps,
+	pub(crate) drop_count: u32,
+}
+
+impl RecvMetadata {
+	pub(crate) fn from_sys(inner: crate::sys::RecvMetadata) -> Self {
+		Self {
+			interface: CanInterface { inner: inner.interface },
+			timestamps: inner.timestamps,
+			drop_count: inner.drop_count,
+		}
+	}
+
+	/// Get the interface the frame was received on.
+	#[inline]
+	pub fn interface(&self) -> &CanInterface {
+		&self.interface
+	}
+
+	/// Get the timestamps of the frame.
+	///
+	/// Timestamps must be enabled on the socket with [`CanSocket::set_timestamp_mode()`][crate::CanSocket::set_timestamp_mode].
+	#[inline]
+	pub fn timestamps(&self) -> Timestamps {
+		self.timestamps
+	}
+
+	/// Get the total number of frames dropped by the socket before this frame was received.
+	///
+	/// This is a counter maintained by the kernel for the lifetime of the socket.
+	/// It counts frames that were dropped because the receive queue of the socket was full.
+	/// The counter wraps around on overflow.
+	///
+	/// The drop count must be enabled on the socket with [`CanSocket::set_drop_count_enabled()`][crate::CanSocket::set_drop_count_enabled],
+	/// otherwise this always returns 0.
+	///
+	/// Use a [`DropTracker`] to get the number of frames dropped since the previously received frame.
+	#[inline]
+	pub fn drop_count(&self) -> u32 {
+		self.drop_count
+	}
+}
+
+/// Helper to track the number of frames dropped between received frames.
+///
+/// Pass the metadata of each received frame to [`Self::update()`]
+/// to get the number of frames that were dropped since the previous frame.
+#[derive(Debug, Clone, Default)]
+pub struct DropTracker {
+	last_drop_count: u32,
+}
+
+impl DropTracker {
+	/// Create a new drop tracker.
+	///
+	/// The tracker assumes that no frames were dropped before it was created.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Update the tracker with the metadata of a received frame.
+	///
+	/// Returns the number of frames that were dropped since the previous call.
+	pub fn update(&mut self, metadata: &RecvMetadata) -> u32 {
+		self.update_drop_count(metadata.drop_count())
+	}
+
+	/// Update the tracker with a cumulative drop count.
+	///
+	/// Returns the number of frames that were dropped since the previous call.
+	pub fn update_drop_count(&mut self, drop_count: u32) -> u32 {
+		let lost = drop_count.wrapping_sub(self.last_drop_count);
+		self.last_drop_count = drop_count;
+		lost
+	}
+
+	/// Get the total number of dropped frames as seen by the last update.
+	pub fn drop_count(&self) -> u32 {
+		self.last_drop_count
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn drop_tracker() {
+		let mut tracker = DropTracker::new();
+		assert!(tracker.update_drop_count(0) == 0);
+		assert!(tracker.update_drop_count(3) == 3);
+		assert!(tracker.update_drop_count(3) == 0);
+		assert!(tracker.update_drop_count(10) == 7);
+		assert!(tracker.drop_count() == 10);
+
+		let mut tracker = DropTracker::new();
+		tracker.update_drop_count(u32::MAX - 1);
+		assert!(tracker.update_drop_count(2) == 4);
+	}
+}
@@ -0,0 +1,512 @@
+use crate::sys::netlink::{find_attribute, parse_pod, Attributes, MessageBuilder};
+
+const IFLA_CAN_BITTIMING: u16 = 1;
+const IFLA_CAN_CLOCK: u16 = 3;
+const IFLA_CAN_CTRLMODE: u16 = 5;
+const IFLA_CAN_RESTART_MS: u16 = 6;
+pub(crate) const IFLA_CAN_RESTART: u16 = 7;
+const IFLA_CAN_DATA_BITTIMING: u16 = 9;
+const IFLA_CAN_TERMINATION: u16 = 11;
+const IFLA_CAN_CTRLMODE_EXT: u16 = 17;
+
+const IFLA_CAN_CTRLMODE_SUPPORTED: u16 = 1;
+
+/// Bit timing parameters of a CAN interface.
+///
+/// These match the fields of `struct can_bittiming` used by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(C)]
+pub struct CanBitTiming {
+	/// The bitrate in bits per second.
+	pub bitrate: u32,
+
+	/// The sample point in tenths of a percent (so 875 means 87.5%).
+	pub sample_point: u32,
+
+	/// The time quantum in nanoseconds.
+	pub tq: u32,
+
+	/// The propagation segment in time quanta.
+	pub prop_seg: u32,
+
+	/// The phase buffer segment 1 in time quanta.
+	pub phase_seg1: u32,
+
+	/// The phase buffer segment 2 in time quanta.
+	pub phase_seg2: u32,
+
+	/// The synchronisation jump width in time quanta.
+	pub sjw: u32,
+
+	/// The bitrate prescaler.
+	pub brp: u32,
+}
+
+impl CanBitTiming {
+	/// Create bit timing parameters with only the bitrate and sample point set.
+	///
+	/// The kernel calculates the other parameters when the bit timing is applied.
+	/// A sample point of 0 lets the kernel pick the sample point too.
+	pub const fn from_bitrate(bitrate: u32, sample_point: u32) -> Self {
+		Self {
+			bitrate,
+			sample_point,
+			tq: 0,
+			prop_seg: 0,
+			phase_seg1: 0,
+			phase_seg2: 0,
+			sjw: 0,
+			brp: 0,
+		}
+	}
+
+	fn as_bytes(&self) -> &[u8] {
+		// SAFETY: The struct is `repr(C)` and consists only of `u32` fields, so it has no padding.
+		unsafe {
+			std::slice::from_raw_parts((self as *const Self).cast(), std::mem::size_of::<Self>())
+		}
+	}
+}
+
+/// Control mode flags of a CAN interface.
+///
+/// Flags can be combined with the `|` operator.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct CanControlMode {
+	bits: u32,
+}
+
+impl CanControlMode {
+	/// No flags.
+	pub const NONE: Self = Self::from_bits(0);
+
+	/// Loopback mode: transmitted frames are received by the controller without touching the bus.
+	pub const LOOPBACK: Self = Self::from_bits(0x0001);
+
+	/// Listen-only mode: the controller never transmits, not even acknowledgements.
+	pub const LISTEN_ONLY: Self = Self::from_bits(0x0002);
+
+	/// Sample each bit three times.
+	pub const TRIPLE_SAMPLING: Self = Self::from_bits(0x0004);
+
+	/// Do not retransmit frames that were not acknowledged or lost arbitration.
+	pub const ONE_SHOT: Self = Self::from_bits(0x0008);
+
+	/// Report bus errors as error frames.
+	pub const BERR_REPORTING: Self = Self::from_bits(0x0010);
+
+	/// CAN FD mode.
+	pub const FD: Self = Self::from_bits(0x0020);
+
+	/// Ignore missing acknowledgements.
+	pub const PRESUME_ACK: Self = Self::from_bits(0x0040);
+
+	/// Use the non-ISO CAN FD protocol.
+	pub const FD_NON_ISO: Self = Self::from_bits(0x0080);
+
+	/// Classic CAN data length codes above 8.
+	pub const CC_LEN8_DLC: Self = Self::from_bits(0x0100);
+
+	/// Automatic transmitter delay compensation.
+	pub const TDC_AUTO: Self = Self::from_bits(0x0200);
+
+	/// Manual transmitter delay compensation.
+	pub const TDC_MANUAL: Self = Self::from_bits(0x0400);
+
+	/// Create control mode flags from the raw bits used by the kernel.
+	#[inline]
+	pub const fn from_bits(bits: u32) -> Self {
+		Self { bits }
+	}
+
+	/// Get the raw bits of the control mode flags.
+	#[inline]
+	pub const fn bits(self) -> u32 {
+		self.bits
+	}
+
+	/// Check if all flags in `other` are also in `self`.
+	#[inline]
+	pub const fn contains(self, other: Self) -> bool {
+		self.bits & other.bits == other.bits
+	}
+
+	/// Check if no flags are set.
+	#[inline]
+	pub const fn is_empty(self) -> bool {
+		self.bits == 0
+	}
+}
+
+impl std::ops::BitOr for CanControlMode {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self::Output {
+		Self::from_bits(self.bits | rhs.bits)
+	}
+}
+
+impl std::ops::BitOrAssign for CanControlMode {
+	fn bitor_assign(&mut self, rhs: Self) {
+		*self = *self | rhs
+	}
+}
+
+impl std::ops::BitAnd for CanControlMode {
+	type Output = Self;
+
+	fn bitand(self, rhs: Self) -> Self::Output {
+		Self::from_bits(self.bits & rhs.bits)
+	}
+}
+
+impl std::ops::BitAndAssign for CanControlMode {
+	fn bitand_assign(&mut self, rhs: Self) {
+		*self = *self & rhs
+	}
+}
+
+impl std::ops::Not for CanControlMode {
+	type Output = Self;
+
+	fn not(self) -> Self::Output {
+		Self::from_bits(!self.bits)
+	}
+}
+
+impl std::fmt::Debug for CanControlMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		const NAMES: [(CanControlMode, &str); 11] = [
+			(CanControlMode::LOOPBACK, "LOOPBACK"),
+			(CanControlMode::LISTEN_ONLY, "LISTEN_ONLY"),
+			(CanControlMode::TRIPLE_SAMPLING, "TRIPLE_SAMPLING"),
+			(CanControlMode::ONE_SHOT, "ONE_SHOT"),
+			(CanControlMode::BERR_REPORTING, "BERR_REPORTING"),
+			(CanControlMode::FD, "FD"),
+			(CanControlMode::PRESUME_ACK, "PRESUME_ACK"),
+			(CanControlMode::FD_NON_ISO, "FD_NON_ISO"),
+			(CanControlMode::CC_LEN8_DLC, "CC_LEN8_DLC"),
+			(CanControlMode::TDC_AUTO, "TDC_AUTO"),
+			(CanControlMode::TDC_MANUAL, "TDC_MANUAL"),
+		];
+
+		let mut remaining = self.bits;
+		let mut first = true;
+		for (flag, name) in NAMES {
+			if self.contains(flag) {
+				if !first {
+					f.write_str(" | ")?;
+				}
+				f.write_str(name)?;
+				remaining &= !flag.bits;
+				first = false;
+			}
+		}
+		if remaining != 0 || first {
+			if !first {
+				f.write_str(" | ")?;
+			}
+			write!(f, "0x{remaining:X}")?;
+		}
+		Ok(())
+	}
+}
+
+/// The configuration of a CAN interface, as reported by the kernel.
+///
+/// Fields are `None` if the kernel did not report them.
+/// Virtual CAN interfaces have no CAN specific configuration at all, so all fields will be `None`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct CanConfig {
+	/// The bit timing of the nominal bitrate.
+	pub bit_timing: Option<CanBitTiming>,
+
+	/// The bit timing of the data phase of CAN FD frames.
+	pub data_bit_timing: Option<CanBitTiming>,
+
+	/// The frequency of the CAN controller clock in Hz.
+	pub clock_frequency: Option<u32>,
+
+	/// The currently enabled control mode flags.
+	pub control_mode: Option<CanControlMode>,
+
+	/// The control mode flags supported by the interface.
+	///
+	/// This is `None` if the kernel does not report the supported flags (before Linux 5.16).
+	pub supported_control_mode: Option<CanControlMode>,
+
+	/// The delay in milliseconds before automatically restarting after a bus-off condition.
+	///
+	/// A value of 0 means automatic restarts are disabled.
+	pub restart_ms: Option<u32>,
+
+	/// The bus termination resistance in Ohm.
+	pub termination: Option<u16>,
+}
+
+impl CanConfig {
+	/// Parse the configuration from the nested `IFLA_INFO_DATA` attributes of a CAN interface.
+	pub(crate) fn parse(info_data: &[u8]) -> Self {
+		let mut config = Self::default();
+		for (kind, value) in Attributes::new(info_data) {
+			match kind {
+				IFLA_CAN_BITTIMING => config.bit_timing = parse_pod(value),
+				IFLA_CAN_DATA_BITTIMING => config.data_bit_timing = parse_pod(value),
+				IFLA_CAN_CLOCK => config.clock_frequency = parse_pod(value),
+				// The kernel reports the current flags in `struct can_ctrlmode`, and always sets the mask to 0.
+				IFLA_CAN_CTRLMODE => {
+					if let Some([_mask, flags]) = parse_pod::<[u32; 2]>(value) {
+						config.control_mode = Some(CanControlMode::from_bits(flags));
+					}
+				},
+				IFLA_CAN_CTRLMODE_EXT => {
+					config.supported_control_mode = find_attribute(value, IFLA_CAN_CTRLMODE_SUPPORTED)
+						.and_then(parse_pod)
+						.map(CanControlMode::from_bits);
+				},
+				IFLA_CAN_RESTART_MS => config.restart_ms = parse_pod(value),
+				IFLA_CAN_TERMINATION => config.termination = parse_pod(value),
+				_ => (),
+			}
+		}
+		config
+	}
+}
+
+/// Changes to apply to the configuration of a CAN interface.
+///
+/// Only the settings that are explicitly set are changed.
+/// Most settings can only be changed while the interface is down.
+///
+/// Use [`NetlinkSocket::set_can_config()`][super::NetlinkSocket::set_can_config] to apply the changes.
+#[derive(Debug, Clone, Default)]
+pub struct CanConfigUpdate {
+	bit_timing: Option<CanBitTiming>,
+	data_bit_timing: Option<CanBitTiming>,
+	control_mode_mask: CanControlMode,
+	control_mode_flags: CanControlMode,
+	restart_ms: Option<u32>,
+	termination: Option<u16>,
+}
+
+impl CanConfigUpdate {
+	/// Create a new configuration update that does not change anything.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the nominal bitrate in bits per second.
+	///
+	/// The kernel calculates the bit timing parameters from the bitrate and sample point.
+	pub fn with_bitrate(mut self, bitrate: u32) -> Self {
+		self.bit_timing.get_or_insert_with(Default::default).bitrate = bitrate;
+		self
+	}
+
+	/// Set the nominal sample point in tenths of a percent (so 875 means 87.5%).
+	///
+	/// This must be combined with [`Self::with_bitrate()`].
+	pub fn with_sample_point(mut self, sample_point: u32) -> Self {
+		self.bit_timing.get_or_insert_with(Default::default).sample_point = sample_point;
+		self
+	}
+
+	/// Set the full nominal bit timing parameters.
+	pub fn with_bit_timing(mut self, bit_timing: CanBitTiming) -> Self {
+		self.bit_timing = Some(bit_timing);
+		self
+	}
+
+	/// Set the bitrate of the data phase of CAN FD frames in bits per second.
+	pub fn with_data_bitrate(mut self, bitrate: u32) -> Self {
+		self.data_bit_timing.get_or_insert_with(Default::default).bitrate = bitrate;
+		self
+	}
+
+	/// Set the sample point of the data phase of CAN FD frames in tenths of a percent.
+	///
+	/// This must be combined with [`Self::with_data_bitrate()`].
+	pub fn with_data_sample_point(mut self, sample_point: u32) -> Self {
+		self.data_bit_timing.get_or_insert_with(Default::default).sample_point = sample_point;
+		self
+	}
+
+	/// Set the full bit timing parameters of the data phase of CAN FD frames.
+	pub fn with_data_bit_timing(mut self, bit_timing: CanBitTiming) -> Self {
+		self.data_bit_timing = Some(bit_timing);
+		self
+	}
+
+	/// Set the delay in milliseconds before automatically restarting after a bus-off condition.
+	///
+	/// A value of 0 disables automatic restarts.
+	pub fn with_restart_ms(mut self, restart_ms: u32) -> Self {
+		self.restart_ms = Some(restart_ms);
+		self
+	}
+
+	/// Set the bus termination resistance in Ohm.
+	pub fn with_termination(mut self, termination: u16) -> Self {
+		self.termination = Some(termination);
+		self
+	}
+
+	/// Enable or disable control mode flags.
+	///
+	/// Flags that are not mentioned in `flags` are left unchanged.
+	pub fn with_control_mode(mut self, flags: CanControlMode, enable: bool) -> Self {
+		self.control_mode_mask |= flags;
+		if enable {
+			self.control_mode_flags |= flags;
+		} else {
+			self.control_mode_flags &= !flags;
+		}
+		self
+	}
+
+	/// Enable or disable listen-only mode.
+	pub fn with_listen_only(self, enable: bool) -> Self {
+		self.with_control_mode(CanControlMode::LISTEN_ONLY, enable)
+	}
+
+	/// Enable or disable loopback mode.
+	pub fn with_loopback(self, enable: bool) -> Self {
+		self.with_control_mode(CanControlMode::LOOPBACK, enable)
+	}
+
+	/// Enable or disable CAN FD mode.
+	pub fn with_fd(self, enable: bool) -> Self {
+		self.with_control_mode(CanControlMode::FD, enable)
+	}
+
+	/// Check if the update changes anything.
+	pub fn is_empty(&self) -> bool {
+		self.bit_timing.is_none()
+			&& self.data_bit_timing.is_none()
+			&& self.control_mode_mask.is_empty()
+			&& self.restart_ms.is_none()
+			&& self.termination.is_none()
+	}
+
+	/// Encode the changes as `IFLA_CAN_*` attributes.
+	pub(crate) fn encode(&self, message: &mut MessageBuilder) {
+		if let Some(bit_timing) = &self.bit_timing {
+			message.push_attribute(IFLA_CAN_BITTIMING, bit_timing.as_bytes());
+		}
+		if let Some(bit_timing) = &self.data_bit_timing {
+			message.push_attribute(IFLA_CAN_DATA_BITTIMING, bit_timing.as_bytes());
+		}
+		if !self.control_mode_mask.is_empty() {
+			let mut ctrlmode = [0; 8];
+			ctrlmode[..4].copy_from_slice(&self.control_mode_mask.bits().to_ne_bytes());
+			ctrlmode[4..].copy_from_slice(&self.control_mode_flags.bits().to_ne_bytes());
+			message.push_attribute(IFLA_CAN_CTRLMODE, &ctrlmode);
+		}
+		if let Some(restart_ms) = self.restart_ms {
+			message.push_u32(IFLA_CAN_RESTART_MS, restart_ms);
+		}
+		if let Some(termination) = self.termination {
+			message.push_attribute(IFLA_CAN_TERMINATION, &termination.to_ne_bytes());
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	fn attribute(kind: u16, value: &[u8]) -> Vec<u8> {
+		let mut data = Vec::new();
+		data.extend_from_slice(&(4 + value.len() as u16).to_ne_bytes());
+		data.extend_from_slice(&kind.to_ne_bytes());
+		data.extend_from_slice(value);
+		data.resize((data.len() + 3) & !3, 0);
+		data
+	}
+
+	fn u32s(values: &[u32]) -> Vec<u8> {
+		values.iter().flat_map(|x| x.to_ne_bytes()).collect()
+	}
+
+	#[test]
+	fn parse_config() {
+		let mut data = Vec::new();
+		data.extend(attribute(IFLA_CAN_BITTIMING, &u32s(&[500_000, 875, 125, 6, 7, 2, 1, 10])));
+		data.extend(attribute(IFLA_CAN_DATA_BITTIMING, &u32s(&[2_000_000, 750, 25, 7, 7, 5, 1, 2])));
+		data.extend(attribute(IFLA_CAN_CLOCK, &u32s(&[80_000_000])));
+		data.extend(attribute(IFLA_CAN_CTRLMODE, &u32s(&[0, 0x22])));
+		data.extend(attribute(IFLA_CAN_CTRLMODE_EXT | libc::NLA_F_NESTED as u16, &attribute(IFLA_CAN_CTRLMODE_SUPPORTED, &u32s(&[0x3F]))));
+		data.extend(attribute(IFLA_CAN_RESTART_MS, &u32s(&[100])));
+		data.extend(attribute(IFLA_CAN_TERMINATION, &120u16.to_ne_bytes()));
+		data.extend(attribute(4, &u32s(&[0])));
+
+		let config = CanConfig::parse(&data);
+		assert!(config.bit_timing == Some(CanBitTiming {
+			bitrate: 500_000,
+			sample_point: 875,
+			tq: 125,
+			prop_seg: 6,
+			phase_seg1: 7,
+			phase_seg2: 2,
+			sjw: 1,
+			brp: 10,
+		}));
+		let data_bit_timing = config.data_bit_timing.unwrap();
+		assert!(data_bit_timing.bitrate == 2_000_000);
+		assert!(data_bit_timing.sample_point == 750);
+		assert!(config.clock_frequency == Some(80_000_000));
+		assert!(config.supported_control_mode == Some(CanControlMode::from_bits(0x3F)));
+		assert!(config.control_mode == Some(CanControlMode::LISTEN_ONLY | CanControlMode::FD));
+		assert!(config.restart_ms == Some(100));
+		assert!(config.termination == Some(120));
+	}
+
+	#[test]
+	fn parse_config_without_supported_control_mode() {
+		let data = attribute(IFLA_CAN_CTRLMODE, &u32s(&[0, 0x02]));
+		let config = CanConfig::parse(&data);
+		assert!(config.control_mode == Some(CanControlMode::LISTEN_ONLY));
+		assert!(let None = config.supported_control_mode);
+	}
+
+	#[test]
+	fn parse_empty_config() {
+		assert!(CanConfig::parse(&[]) == CanConfig::default());
+		// Truncated attributes are ignored.
+		let data = attribute(IFLA_CAN_BITTIMING, &u32s(&[500_000]));
+		assert!(let None = CanConfig::parse(&data).bit_timing);
+	}
+
+	#[test]
+	fn encode_config_update() {
+		let update = CanConfigUpdate::new()
+			.with_bitrate(250_000)
+			.with_sample_point(800)
+			.with_listen_only(true)
+			.with_loopback(false)
+			.with_restart_ms(50);
+		assert!(!update.is_empty());
+
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		update.encode(&mut message);
+
+		let config = CanConfig::parse(message.payload());
+		assert!(config.bit_timing == Some(CanBitTiming::from_bitrate(250_000, 800)));
+		assert!(let None = config.data_bit_timing);
+		assert!(config.control_mode == Some(CanControlMode::LISTEN_ONLY));
+		assert!(find_attribute(message.payload(), IFLA_CAN_CTRLMODE).and_then(parse_pod) == Some([
+			(CanControlMode::LISTEN_ONLY | CanControlMode::LOOPBACK).bits(),
+			CanControlMode::LISTEN_ONLY.bits(),
+		]));
+		assert!(config.restart_ms == Some(50));
+		assert!(let None = config.termination);
+	}
+
+	#[test]
+	fn control_mode_debug() {
+		assert!(format!("{:?}", CanControlMode::NONE) == "0x0");
+		assert!(format!("{:?}", CanControlMode::LOOPBACK | CanControlMode::FD) == "LOOPBACK | FD");
+		assert!(format!("{:?}", CanControlMode::from_bits(0x1002)) == "LISTEN_ONLY | 0x1000");
+	}
+}
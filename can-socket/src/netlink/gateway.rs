@@ -0,0 +1,689 @@
+use crate::sys::netlink::{parse_pod, Attributes, MessageBuilder};
+use crate::{CanFilter, CanId, CanInterface};
+
+/// Gateway type for routing between two CAN interfaces.
+const CGW_TYPE_CAN_CAN: u8 = 1;
+
+const CGW_FLAGS_CAN_ECHO: u16 = 0x01;
+const CGW_FLAGS_CAN_SRC_TSTAMP: u16 = 0x02;
+const CGW_FLAGS_CAN_IIF_TX_OK: u16 = 0x04;
+const CGW_FLAGS_CAN_FD: u16 = 0x08;
+
+const CGW_MOD_AND: u16 = 1;
+const CGW_MOD_OR: u16 = 2;
+const CGW_MOD_XOR: u16 = 3;
+const CGW_MOD_SET: u16 = 4;
+const CGW_CS_XOR: u16 = 5;
+const CGW_CS_CRC8: u16 = 6;
+const CGW_HANDLED: u16 = 7;
+const CGW_DROPPED: u16 = 8;
+const CGW_SRC_IF: u16 = 9;
+const CGW_DST_IF: u16 = 10;
+const CGW_FILTER: u16 = 11;
+const CGW_DELETED: u16 = 12;
+const CGW_LIM_HOPS: u16 = 13;
+const CGW_MOD_UID: u16 = 14;
+
+/// Modification type flags.
+const CGW_MOD_ID: u8 = 0x01;
+const CGW_MOD_DLC: u8 = 0x02;
+const CGW_MOD_DATA: u8 = 0x04;
+
+const CGW_CRC8PRF_UNSPEC: u8 = 0;
+const CGW_CRC8PRF_1U8: u8 = 1;
+const CGW_CRC8PRF_16U8: u8 = 2;
+const CGW_CRC8PRF_SFFID_XOR: u8 = 3;
+
+/// Size of a frame modification attribute: a `struct can_frame` followed by the modification type.
+const CGW_MODATTR_LEN: usize = 17;
+
+/// Size of the XOR checksum attribute.
+const CGW_CS_XOR_LEN: usize = 4;
+
+/// Size of the CRC8 checksum attribute.
+const CGW_CS_CRC8_LEN: usize = 282;
+
+/// A routing rule for the kernel CAN gateway.
+///
+/// The gateway receives frames from the source interface,
+/// optionally filters and modifies them,
+/// and sends them on the destination interface.
+///
+/// Rules are managed with [`NetlinkSocket::add_gateway_rule()`][super::NetlinkSocket::add_gateway_rule] and related functions.
+/// Modifications are applied in the order AND, OR, XOR and SET, followed by the checksum calculations.
+#[derive(Debug, Clone)]
+pub struct CanGatewayRule {
+	/// The interface to receive frames from.
+	pub source: CanInterface,
+
+	/// The interface to send the frames on.
+	pub destination: CanInterface,
+
+	/// Only route frames that match this filter.
+	pub filter: Option<CanFilter>,
+
+	/// Modifications to apply to the routed frames.
+	pub modifications: FrameModifications,
+
+	/// Calculate an XOR checksum over the data of routed frames.
+	pub xor_checksum: Option<XorChecksum>,
+
+	/// Calculate a CRC8 checksum over the data of routed frames.
+	pub crc8_checksum: Option<Crc8Checksum>,
+
+	/// Limit the number of times a frame can be routed by the gateway.
+	///
+	/// This can be used to prevent routing loops.
+	pub hop_limit: Option<u8>,
+
+	/// A user defined identifier for the rule.
+	///
+	/// Adding a rule with the UID of an existing rule updates the modifications of the existing rule.
+	pub modification_uid: Option<u32>,
+
+	/// Deliver routed frames to sockets on the destination interface as if they were sent locally.
+	pub echo: bool,
+
+	/// Keep the timestamp of the received frame for the routed frame.
+	pub source_timestamp: bool,
+
+	/// Allow the source and destination interface to be the same.
+	pub allow_same_interface: bool,
+}
+
+impl CanGatewayRule {
+	/// Create a new rule that routes all frames from `source` to `destination` without modifications.
+	pub fn new(source: CanInterface, destination: CanInterface) -> Self {
+		Self {
+			source,
+			destination,
+			filter: None,
+			modifications: FrameModifications::default(),
+			xor_checksum: None,
+			crc8_checksum: None,
+			hop_limit: None,
+			modification_uid: None,
+			echo: false,
+			source_timestamp: false,
+			allow_same_interface: false,
+		}
+	}
+
+	/// Only route frames that match the filter.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_filter(mut self, filter: CanFilter) -> Self {
+		self.filter = Some(filter);
+		self
+	}
+
+	/// Set the modifications to apply to the routed frames.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_modifications(mut self, modifications: FrameModifications) -> Self {
+		self.modifications = modifications;
+		self
+	}
+
+	/// Calculate an XOR checksum over the data of routed frames.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_xor_checksum(mut self, checksum: XorChecksum) -> Self {
+		self.xor_checksum = Some(checksum);
+		self
+	}
+
+	/// Calculate a CRC8 checksum over the data of routed frames.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_crc8_checksum(mut self, checksum: Crc8Checksum) -> Self {
+		self.crc8_checksum = Some(checksum);
+		self
+	}
+
+	/// Limit the number of times a frame can be routed by the gateway.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_hop_limit(mut self, hop_limit: u8) -> Self {
+		self.hop_limit = Some(hop_limit);
+		self
+	}
+
+	/// Set a user defined identifier for the rule.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_modification_uid(mut self, uid: u32) -> Self {
+		self.modification_uid = Some(uid);
+		self
+	}
+
+	/// Deliver routed frames to sockets on the destination interface as if they were sent locally.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_echo(mut self, echo: bool) -> Self {
+		self.echo = echo;
+		self
+	}
+
+	/// Keep the timestamp of the received frame for the routed frame.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_source_timestamp(mut self, source_timestamp: bool) -> Self {
+		self.source_timestamp = source_timestamp;
+		self
+	}
+
+	/// Allow the source and destination interface to be the same.
+	#[must_use = "this function returns a new rule, it does not modify self"]
+	pub fn with_allow_same_interface(mut self, allow: bool) -> Self {
+		self.allow_same_interface = allow;
+		self
+	}
+
+	/// Encode the rule as a `rtcanmsg` header followed by the netlink attributes.
+	pub(crate) fn encode(&self, message: &mut MessageBuilder) {
+		let mut flags = 0;
+		if self.echo {
+			flags |= CGW_FLAGS_CAN_ECHO;
+		}
+		if self.source_timestamp {
+			flags |= CGW_FLAGS_CAN_SRC_TSTAMP;
+		}
+		if self.allow_same_interface {
+			flags |= CGW_FLAGS_CAN_IIF_TX_OK;
+		}
+		message.push_header(&rtcanmsg(flags));
+
+		self.modifications.encode(message);
+		if let Some(checksum) = &self.xor_checksum {
+			message.push_attribute(CGW_CS_XOR, &checksum.encode());
+		}
+		if let Some(checksum) = &self.crc8_checksum {
+			message.push_attribute(CGW_CS_CRC8, &checksum.encode());
+		}
+		if let Some(uid) = self.modification_uid {
+			message.push_u32(CGW_MOD_UID, uid);
+		}
+		message.push_u32(CGW_SRC_IF, self.source.index());
+		message.push_u32(CGW_DST_IF, self.destination.index());
+		if let Some(filter) = &self.filter {
+			let (can_id, can_mask) = filter.filter.raw_parts();
+			let mut data = [0; 8];
+			data[..4].copy_from_slice(&can_id.to_ne_bytes());
+			data[4..].copy_from_slice(&can_mask.to_ne_bytes());
+			message.push_attribute(CGW_FILTER, &data);
+		}
+		if let Some(hop_limit) = self.hop_limit {
+			message.push_attribute(CGW_LIM_HOPS, &[hop_limit]);
+		}
+	}
+}
+
+/// A rule of the kernel CAN gateway, with the statistics of the rule.
+#[derive(Debug, Clone)]
+pub struct CanGatewayRuleInfo {
+	/// The routing rule.
+	pub rule: CanGatewayRule,
+
+	/// The number of frames routed by the rule.
+	pub handled: u32,
+
+	/// The number of frames that could not be sent on the destination interface.
+	pub dropped: u32,
+
+	/// The number of frames deleted because of the hop limit.
+	pub deleted: u32,
+}
+
+impl CanGatewayRuleInfo {
+	/// Parse a rule from a `RTM_NEWROUTE` message payload.
+	///
+	/// Returns `Ok(None)` for rules that are not supported by this crate.
+	pub(crate) fn parse(payload: &[u8]) -> std::io::Result<Option<Self>> {
+		if payload.len() < 4 {
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "netlink message too short for CAN gateway rule"));
+		}
+		let gateway_type = payload[1];
+		let flags = u16::from_ne_bytes([payload[2], payload[3]]);
+		if gateway_type != CGW_TYPE_CAN_CAN || flags & CGW_FLAGS_CAN_FD != 0 {
+			return Ok(None);
+		}
+
+		let mut rule = CanGatewayRule::new(CanInterface::from_index(0), CanInterface::from_index(0));
+		rule.echo = flags & CGW_FLAGS_CAN_ECHO != 0;
+		rule.source_timestamp = flags & CGW_FLAGS_CAN_SRC_TSTAMP != 0;
+		rule.allow_same_interface = flags & CGW_FLAGS_CAN_IIF_TX_OK != 0;
+		let mut info = Self {
+			rule,
+			handled: 0,
+			dropped: 0,
+			deleted: 0,
+		};
+
+		for (kind, value) in Attributes::new(&payload[4..]) {
+			let rule = &mut info.rule;
+			match kind {
+				CGW_MOD_AND => rule.modifications.and = FrameModification::parse(value),
+				CGW_MOD_OR => rule.modifications.or = FrameModification::parse(value),
+				CGW_MOD_XOR => rule.modifications.xor = FrameModification::parse(value),
+				CGW_MOD_SET => rule.modifications.set = FrameModification::parse(value),
+				CGW_CS_XOR => rule.xor_checksum = XorChecksum::parse(value),
+				CGW_CS_CRC8 => rule.crc8_checksum = Crc8Checksum::parse(value),
+				CGW_HANDLED => info.handled = parse_pod(value).unwrap_or_default(),
+				CGW_DROPPED => info.dropped = parse_pod(value).unwrap_or_default(),
+				CGW_DELETED => info.deleted = parse_pod(value).unwrap_or_default(),
+				CGW_SRC_IF => rule.source = CanInterface::from_index(parse_pod(value).unwrap_or_default()),
+				CGW_DST_IF => rule.destination = CanInterface::from_index(parse_pod(value).unwrap_or_default()),
+				CGW_FILTER => {
+					if let Some([can_id, can_mask]) = parse_pod::<[u32; 2]>(value) {
+						rule.filter = Some(CanFilter {
+							filter: crate::sys::CanFilter::from_raw_parts(can_id, can_mask),
+						});
+					}
+				},
+				CGW_LIM_HOPS => rule.hop_limit = parse_pod(value),
+				CGW_MOD_UID => rule.modification_uid = parse_pod(value),
+				_ => (),
+			}
+		}
+		Ok(Some(info))
+	}
+}
+
+/// The modifications applied to frames routed by the gateway.
+///
+/// The modifications are applied in the order AND, OR, XOR and SET.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct FrameModifications {
+	/// Bitwise AND the selected fields with the given values.
+	pub and: Option<FrameModification>,
+
+	/// Bitwise OR the selected fields with the given values.
+	pub or: Option<FrameModification>,
+
+	/// Bitwise XOR the selected fields with the given values.
+	pub xor: Option<FrameModification>,
+
+	/// Set the selected fields to the given values.
+	pub set: Option<FrameModification>,
+}
+
+impl FrameModifications {
+	/// Check if there are no modifications.
+	pub fn is_empty(&self) -> bool {
+		[self.and, self.or, self.xor, self.set]
+			.iter()
+			.flatten()
+			.all(|modification| modification.is_empty())
+	}
+
+	fn encode(&self, message: &mut MessageBuilder) {
+		let modifications = [
+			(CGW_MOD_AND, &self.and),
+			(CGW_MOD_OR, &self.or),
+			(CGW_MOD_XOR, &self.xor),
+			(CGW_MOD_SET, &self.set),
+		];
+		for (kind, modification) in modifications {
+			if let Some(modification) = modification {
+				if !modification.is_empty() {
+					message.push_attribute(kind, &modification.encode());
+				}
+			}
+		}
+	}
+}
+
+/// The values for a single modification operation.
+///
+/// Only the fields that are set are modified.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct FrameModification {
+	/// The value for the raw CAN ID.
+	///
+	/// This is the CAN ID as used by the kernel, including the flags in the upper three bits:
+	/// bit 31 is set for extended IDs and bit 30 is set for RTR frames.
+	pub id: Option<u32>,
+
+	/// The value for the data length code.
+	pub dlc: Option<u8>,
+
+	/// The value for the data.
+	pub data: Option<[u8; 8]>,
+}
+
+impl FrameModification {
+	/// Create a new modification that does not touch any field.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the value for the raw CAN ID, including the flags in the upper three bits.
+	#[must_use = "this function returns a new modification, it does not modify self"]
+	pub fn with_id(mut self, id: u32) -> Self {
+		self.id = Some(id);
+		self
+	}
+
+	/// Set the value for the CAN ID from a [`CanId`].
+	///
+	/// The extended frame flag is set for extended IDs.
+	#[must_use = "this function returns a new modification, it does not modify self"]
+	pub fn with_can_id(self, id: impl Into<CanId>) -> Self {
+		let id = match id.into() {
+			CanId::Standard(id) => u32::from(id.as_u16()),
+			CanId::Extended(id) => id.as_u32() | libc::CAN_EFF_FLAG,
+		};
+		self.with_id(id)
+	}
+
+	/// Set the value for the data length code.
+	#[must_use = "this function returns a new modification, it does not modify self"]
+	pub fn with_dlc(mut self, dlc: u8) -> Self {
+		self.dlc = Some(dlc);
+		self
+	}
+
+	/// Set the value for the data.
+	#[must_use = "this function returns a new modification, it does not modify self"]
+	pub fn with_data(mut self, data: [u8; 8]) -> Self {
+		self.data = Some(data);
+		self
+	}
+
+	/// Check if the modification does not touch any field.
+	pub fn is_empty(&self) -> bool {
+		self.id.is_none() && self.dlc.is_none() && self.data.is_none()
+	}
+
+	/// Encode the modification as a `struct cgw_frame_mod`.
+	fn encode(&self) -> [u8; CGW_MODATTR_LEN] {
+		let mut data = [0; CGW_MODATTR_LEN];
+		let mut modification_type = 0;
+		if let Some(id) = self.id {
+			data[0..4].copy_from_slice(&id.to_ne_bytes());
+			modification_type |= CGW_MOD_ID;
+		}
+		if let Some(dlc) = self.dlc {
+			data[4] = dlc;
+			modification_type |= CGW_MOD_DLC;
+		}
+		if let Some(frame_data) = self.data {
+			data[8..16].copy_from_slice(&frame_data);
+			modification_type |= CGW_MOD_DATA;
+		}
+		data[16] = modification_type;
+		data
+	}
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		let data: &[u8; CGW_MODATTR_LEN] = data.get(..CGW_MODATTR_LEN)?.try_into().ok()?;
+		let modification_type = data[16];
+		let modification = Self {
+			id: (modification_type & CGW_MOD_ID != 0).then(|| u32::from_ne_bytes([data[0], data[1], data[2], data[3]])),
+			dlc: (modification_type & CGW_MOD_DLC != 0).then_some(data[4]),
+			data: (modification_type & CGW_MOD_DATA != 0).then(|| data[8..16].try_into().unwrap()),
+		};
+		Some(modification)
+	}
+}
+
+/// An XOR checksum calculated by the gateway.
+///
+/// The checksum is calculated over the data bytes from `from_index` to `to_index` (inclusive),
+/// and stored in the data byte at `result_index`.
+/// Negative indices count from the end of the data: -1 is the last data byte.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct XorChecksum {
+	/// The index of the first data byte to include in the checksum.
+	pub from_index: i8,
+
+	/// The index of the last data byte to include in the checksum.
+	pub to_index: i8,
+
+	/// The index of the data byte to store the checksum in.
+	pub result_index: i8,
+
+	/// The initial value of the checksum.
+	pub initial_value: u8,
+}
+
+impl XorChecksum {
+	/// Create a new XOR checksum with an initial value of 0.
+	pub fn new(from_index: i8, to_index: i8, result_index: i8) -> Self {
+		Self {
+			from_index,
+			to_index,
+			result_index,
+			initial_value: 0,
+		}
+	}
+
+	fn encode(&self) -> [u8; CGW_CS_XOR_LEN] {
+		[self.from_index as u8, self.to_index as u8, self.result_index as u8, self.initial_value]
+	}
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		let data = data.get(..CGW_CS_XOR_LEN)?;
+		Some(Self {
+			from_index: data[0] as i8,
+			to_index: data[1] as i8,
+			result_index: data[2] as i8,
+			initial_value: data[3],
+		})
+	}
+}
+
+/// A CRC8 checksum calculated by the gateway.
+///
+/// The checksum is calculated over the data bytes from `from_index` to `to_index` (inclusive),
+/// and stored in the data byte at `result_index`.
+/// Negative indices count from the end of the data: -1 is the last data byte.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Crc8Checksum {
+	/// The index of the first data byte to include in the checksum.
+	pub from_index: i8,
+
+	/// The index of the last data byte to include in the checksum.
+	pub to_index: i8,
+
+	/// The index of the data byte to store the checksum in.
+	pub result_index: i8,
+
+	/// The initial value of the CRC.
+	pub initial_value: u8,
+
+	/// The value to XOR the final CRC with.
+	pub final_xor: u8,
+
+	/// The lookup table of the CRC algorithm.
+	///
+	/// Use [`Self::table_for_polynomial()`] to compute the table for a polynomial.
+	pub table: [u8; 256],
+
+	/// Additional input for the CRC calculation.
+	pub profile: Crc8Profile,
+}
+
+impl Crc8Checksum {
+	/// Create a new CRC8 checksum for a polynomial, with an initial value and final XOR value of 0.
+	pub fn new(from_index: i8, to_index: i8, result_index: i8, polynomial: u8) -> Self {
+		Self {
+			from_index,
+			to_index,
+			result_index,
+			initial_value: 0,
+			final_xor: 0,
+			table: Self::table_for_polynomial(polynomial),
+			profile: Crc8Profile::Unspecified,
+		}
+	}
+
+	/// Compute the lookup table for a (non-reflected) CRC8 polynomial.
+	///
+	/// For example, the SAE J1850 polynomial used by AUTOSAR is `0x1D`.
+	pub fn table_for_polynomial(polynomial: u8) -> [u8; 256] {
+		let mut table = [0; 256];
+		for (i, entry) in table.iter_mut().enumerate() {
+			let mut crc = i as u8;
+			for _ in 0..8 {
+				if crc & 0x80 != 0 {
+					crc = (crc << 1) ^ polynomial;
+				} else {
+					crc <<= 1;
+				}
+			}
+			*entry = crc;
+		}
+		table
+	}
+
+	fn encode(&self) -> [u8; CGW_CS_CRC8_LEN] {
+		let mut data = [0; CGW_CS_CRC8_LEN];
+		data[0] = self.from_index as u8;
+		data[1] = self.to_index as u8;
+		data[2] = self.result_index as u8;
+		data[3] = self.initial_value;
+		data[4] = self.final_xor;
+		data[5..261].copy_from_slice(&self.table);
+		match self.profile {
+			Crc8Profile::Unspecified => data[261] = CGW_CRC8PRF_UNSPEC,
+			Crc8Profile::Constant(value) => {
+				data[261] = CGW_CRC8PRF_1U8;
+				data[262] = value;
+			},
+			Crc8Profile::Counter(values) => {
+				data[261] = CGW_CRC8PRF_16U8;
+				data[262..278].copy_from_slice(&values);
+			},
+			Crc8Profile::StandardIdXor => data[261] = CGW_CRC8PRF_SFFID_XOR,
+		}
+		data
+	}
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		let data = data.get(..CGW_CS_CRC8_LEN)?;
+		let profile = match data[261] {
+			CGW_CRC8PRF_1U8 => Crc8Profile::Constant(data[262]),
+			CGW_CRC8PRF_16U8 => Crc8Profile::Counter(data[262..278].try_into().unwrap()),
+			CGW_CRC8PRF_SFFID_XOR => Crc8Profile::StandardIdXor,
+			_ => Crc8Profile::Unspecified,
+		};
+		Some(Self {
+			from_index: data[0] as i8,
+			to_index: data[1] as i8,
+			result_index: data[2] as i8,
+			initial_value: data[3],
+			final_xor: data[4],
+			table: data[5..261].try_into().unwrap(),
+			profile,
+		})
+	}
+}
+
+/// Additional input for the CRC8 calculation of the gateway.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Crc8Profile {
+	/// Only the selected data bytes are used.
+	Unspecified,
+
+	/// A constant byte is added to the CRC calculation.
+	Constant(u8),
+
+	/// A byte from the table is added to the CRC calculation, selected by the lower 4 bits of data byte 1.
+	///
+	/// This can be used for protocols with a 4 bit message counter, like AUTOSAR E2E profile 1.
+	Counter([u8; 16]),
+
+	/// The XOR of the two bytes of the standard CAN ID is added to the CRC calculation.
+	StandardIdXor,
+}
+
+/// Create a `struct rtcanmsg` for the CAN-to-CAN gateway with the given flags.
+pub(crate) fn rtcanmsg(flags: u16) -> [u8; 4] {
+	let flags = flags.to_ne_bytes();
+	[libc::AF_CAN as u8, CGW_TYPE_CAN_CAN, flags[0], flags[1]]
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::StandardId;
+
+	#[test]
+	fn crc8_table() {
+		// SAE J1850 polynomial.
+		let table = Crc8Checksum::table_for_polynomial(0x1D);
+		assert!(table[0] == 0x00);
+		assert!(table[1] == 0x1D);
+		assert!(table[2] == 0x3A);
+		assert!(table[0x80] == 0x26);
+		assert!(table[0xFF] == 0xC4);
+	}
+
+	#[test]
+	fn encode_parse_rule() {
+		let filter = CanFilter::new(StandardId::new(0x100).unwrap().into()).match_exact_id();
+		let rule = CanGatewayRule::new(CanInterface::from_index(3), CanInterface::from_index(4))
+			.with_filter(filter)
+			.with_modifications(FrameModifications {
+				and: Some(FrameModification::new().with_data([0xFF, 0xFF, 0, 0, 0, 0, 0, 0])),
+				set: Some(FrameModification::new().with_can_id(StandardId::new(0x200).unwrap()).with_dlc(4)),
+				..Default::default()
+			})
+			.with_xor_checksum(XorChecksum::new(0, 2, 3))
+			.with_crc8_checksum(Crc8Checksum {
+				profile: Crc8Profile::Counter([7; 16]),
+				..Crc8Checksum::new(0, -2, -1, 0x1D)
+			})
+			.with_hop_limit(2)
+			.with_modification_uid(42)
+			.with_echo(true);
+
+		let mut message = MessageBuilder::new(libc::RTM_NEWROUTE, 0);
+		rule.encode(&mut message);
+		let mut payload = message.payload().to_vec();
+		// Add the counters that the kernel includes in the dump.
+		let mut counters = MessageBuilder::new(libc::RTM_NEWROUTE, 0);
+		counters.push_u32(CGW_HANDLED, 10);
+		counters.push_u32(CGW_DROPPED, 1);
+		payload.extend_from_slice(counters.payload());
+
+		let_assert!(Ok(Some(info)) = CanGatewayRuleInfo::parse(&payload));
+		assert!(info.handled == 10);
+		assert!(info.dropped == 1);
+		assert!(info.deleted == 0);
+		let parsed = info.rule;
+		assert!(parsed.source.index() == 3);
+		assert!(parsed.destination.index() == 4);
+		let_assert!(Some(parsed_filter) = parsed.filter);
+		assert!(parsed_filter.filter.raw_parts() == filter.filter.raw_parts());
+		assert!(parsed.modifications == rule.modifications);
+		assert!(parsed.xor_checksum == rule.xor_checksum);
+		assert!(parsed.crc8_checksum == rule.crc8_checksum);
+		assert!(parsed.hop_limit == Some(2));
+		assert!(parsed.modification_uid == Some(42));
+		assert!(parsed.echo);
+		assert!(!parsed.source_timestamp);
+		assert!(!parsed.allow_same_interface);
+	}
+
+	#[test]
+	fn encode_modification() {
+		let modification = FrameModification::new().with_id(0x8000_1234).with_data([1, 2, 3, 4, 5, 6, 7, 8]);
+		let data = modification.encode();
+		assert!(data[0..4] == 0x8000_1234u32.to_ne_bytes());
+		assert!(data[8..16] == [1, 2, 3, 4, 5, 6, 7, 8]);
+		assert!(data[16] == CGW_MOD_ID | CGW_MOD_DATA);
+
+		let mut message = MessageBuilder::new(libc::RTM_NEWROUTE, 0);
+		FrameModifications {
+			or: Some(FrameModification::new()),
+			..Default::default()
+		}.encode(&mut message);
+		assert!(message.payload().is_empty());
+	}
+
+	#[test]
+	fn skip_unsupported_rules() {
+		let mut header = rtcanmsg(CGW_FLAGS_CAN_FD);
+		assert!(let Ok(None) = CanGatewayRuleInfo::parse(&header));
+		header = rtcanmsg(0);
+		header[1] = 2;
+		assert!(let Ok(None) = CanGatewayRuleInfo::parse(&header));
+		assert!(let Err(_) = CanGatewayRuleInfo::parse(&header[..2]));
+	}
+}
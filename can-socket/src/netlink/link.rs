@@ -0,0 +1,231 @@
+use crate::sys::netlink::{find_attribute, ifinfomsg, parse_pod, parse_str};
+use crate::CanInterface;
+
+/// The MTU of interfaces that support CAN FD frames.
+const CANFD_MTU: u32 = 72;
+
+/// The minimum MTU of interfaces that support CAN XL frames.
+const CANXL_MIN_MTU: u32 = 76;
+
+/// The maximum MTU of interfaces that support CAN XL frames.
+const CANXL_MAX_MTU: u32 = 2060;
+
+/// Information about a CAN interface.
+///
+/// Use [`CanInterface::list()`] to get the information of all CAN interfaces on the system.
+#[derive(Debug, Clone)]
+pub struct CanInterfaceInfo {
+	interface: CanInterface,
+	name: String,
+	link_kind: CanLinkKind,
+	operational_state: OperationalState,
+	mtu: u32,
+}
+
+impl CanInterfaceInfo {
+	/// Parse the interface information from a `RTM_NEWLINK` message payload.
+	///
+	/// Returns `Ok(None)` if the interface is not a CAN interface.
+	pub(crate) fn parse(payload: &[u8]) -> std::io::Result<Option<Self>> {
+		let (header, attributes) = ifinfomsg::parse(payload)?;
+		if header.ifi_type != libc::ARPHRD_CAN {
+			return Ok(None);
+		}
+
+		let name = find_attribute(attributes, libc::IFLA_IFNAME)
+			.and_then(parse_str)
+			.unwrap_or_default();
+		let link_kind = find_attribute(attributes, libc::IFLA_LINKINFO)
+			.and_then(|link_info| find_attribute(link_info, libc::IFLA_INFO_KIND))
+			.and_then(parse_str)
+			.map(CanLinkKind::from_kind)
+			.unwrap_or(CanLinkKind::Unknown);
+		let operational_state = find_attribute(attributes, libc::IFLA_OPERSTATE)
+			.and_then(parse_pod::<u8>)
+			.map(OperationalState::from_raw)
+			.unwrap_or(OperationalState::Unknown);
+		let mtu = find_attribute(attributes, libc::IFLA_MTU)
+			.and_then(parse_pod)
+			.unwrap_or(0);
+
+		Ok(Some(Self {
+			interface: CanInterface::from_index(header.ifi_index as u32),
+			name,
+			link_kind,
+			operational_state,
+			mtu,
+		}))
+	}
+
+	/// Get the interface.
+	#[inline]
+	pub fn interface(&self) -> &CanInterface {
+		&self.interface
+	}
+
+	/// Get the index of the interface.
+	#[inline]
+	pub fn index(&self) -> u32 {
+		self.interface.index()
+	}
+
+	/// Get the name of the interface.
+	#[inline]
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the kind of the interface.
+	#[inline]
+	pub fn link_kind(&self) -> &CanLinkKind {
+		&self.link_kind
+	}
+
+	/// Get the operational state of the interface.
+	#[inline]
+	pub fn operational_state(&self) -> OperationalState {
+		self.operational_state
+	}
+
+	/// Get the MTU of the interface.
+	///
+	/// The MTU determines which frame types the interface supports:
+	/// 16 for classic CAN frames, 72 for CAN FD frames and 76 up to 2060 for CAN XL frames.
+	#[inline]
+	pub fn mtu(&self) -> u32 {
+		self.mtu
+	}
+
+	/// Check if the interface supports CAN FD frames, based on the MTU.
+	///
+	/// Interfaces that support CAN XL frames also support CAN FD frames.
+	pub fn supports_fd(&self) -> bool {
+		self.mtu == CANFD_MTU || self.supports_xl()
+	}
+
+	/// Check if the interface supports CAN XL frames, based on the MTU.
+	pub fn supports_xl(&self) -> bool {
+		(CANXL_MIN_MTU..=CANXL_MAX_MTU).contains(&self.mtu)
+	}
+}
+
+/// The kind of a CAN interface.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum CanLinkKind {
+	/// A CAN interface backed by a real CAN controller (`can`), including serial line (`slcan`) interfaces.
+	Can,
+
+	/// A virtual CAN interface (`vcan`).
+	Vcan,
+
+	/// A virtual CAN tunnel interface (`vxcan`).
+	Vxcan,
+
+	/// Another kind of interface.
+	Other(String),
+
+	/// The interface did not report its kind.
+	Unknown,
+}
+
+impl CanLinkKind {
+	fn from_kind(kind: String) -> Self {
+		match kind.as_str() {
+			"can" => Self::Can,
+			"vcan" => Self::Vcan,
+			"vxcan" => Self::Vxcan,
+			_ => Self::Other(kind),
+		}
+	}
+}
+
+/// The operational state of a network interface, as defined in RFC 2863.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OperationalState {
+	/// The state is unknown.
+	Unknown,
+
+	/// The interface is not present.
+	NotPresent,
+
+	/// The interface is down.
+	Down,
+
+	/// The interface is down because a lower layer interface is down.
+	LowerLayerDown,
+
+	/// The interface is in test mode.
+	Testing,
+
+	/// The interface is waiting for an external event.
+	Dormant,
+
+	/// The interface is up and can be used.
+	Up,
+
+	/// An operational state not known to this library.
+	Other(u8),
+}
+
+impl OperationalState {
+	fn from_raw(raw: u8) -> Self {
+		match raw {
+			0 => Self::Unknown,
+			1 => Self::NotPresent,
+			2 => Self::Down,
+			3 => Self::LowerLayerDown,
+			4 => Self::Testing,
+			5 => Self::Dormant,
+			6 => Self::Up,
+			x => Self::Other(x),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::sys::netlink::MessageBuilder;
+	use assert2::{assert, let_assert};
+
+	fn link_message(ifi_type: u16, kind: &str, mtu: u32) -> Vec<u8> {
+		let mut header = ifinfomsg::new(12);
+		header.ifi_type = ifi_type;
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		message.push_header(header.as_bytes());
+		message.push_str(libc::IFLA_IFNAME, "vcan-test");
+		message.push_u32(libc::IFLA_MTU, mtu);
+		message.push_attribute(libc::IFLA_OPERSTATE, &[6]);
+		let link_info = message.begin_nested(libc::IFLA_LINKINFO);
+		message.push_str(libc::IFLA_INFO_KIND, kind);
+		message.end_nested(link_info);
+		message.payload().to_vec()
+	}
+
+	#[test]
+	fn parse_interface_info() {
+		let_assert!(Ok(Some(info)) = CanInterfaceInfo::parse(&link_message(libc::ARPHRD_CAN, "vcan", 72)));
+		assert!(info.index() == 12);
+		assert!(info.name() == "vcan-test");
+		assert!(info.link_kind() == &CanLinkKind::Vcan);
+		assert!(info.operational_state() == OperationalState::Up);
+		assert!(info.mtu() == 72);
+		assert!(info.supports_fd());
+		assert!(!info.supports_xl());
+
+		let_assert!(Ok(Some(info)) = CanInterfaceInfo::parse(&link_message(libc::ARPHRD_CAN, "vxcan", 2060)));
+		assert!(info.link_kind() == &CanLinkKind::Vxcan);
+		assert!(info.supports_fd());
+		assert!(info.supports_xl());
+
+		let_assert!(Ok(Some(info)) = CanInterfaceInfo::parse(&link_message(libc::ARPHRD_CAN, "can", 16)));
+		assert!(info.link_kind() == &CanLinkKind::Can);
+		assert!(!info.supports_fd());
+		assert!(!info.supports_xl());
+	}
+
+	#[test]
+	fn skip_non_can_interfaces() {
+		assert!(let Ok(None) = CanInterfaceInfo::parse(&link_message(libc::ARPHRD_ETHER, "veth", 1500)));
+	}
+}
@@ -0,0 +1,197 @@
+//! Configuration of CAN interfaces using the routing netlink interface.
+//!
+//! The [`NetlinkSocket`] can be used to list CAN interfaces,
+//! to read and change the bitrate and other settings of CAN interfaces,
+//! to monitor the controller state and statistics of CAN interfaces,
+//! to bring interfaces up or down, and to restart an interface after a bus-off condition.
+//!
+//! It can also manage the routing rules of the kernel CAN gateway (see [`CanGatewayRule`]),
+//! which requires the `can-gw` kernel module.
+//!
+//! With the `"vcan"` feature, it can also create and delete virtual CAN interfaces.
+//! See [`VirtualInterface`] for a guard that deletes the interface when dropped.
+//!
+//! Changing the configuration of an interface requires the `CAP_NET_ADMIN` capability.
+
+use crate::sys::netlink::{find_attribute, ifinfomsg, parse_str, MessageBuilder};
+use crate::CanInterface;
+
+mod config;
+pub use config::{CanBitTiming, CanConfig, CanConfigUpdate, CanControlMode};
+
+mod gateway;
+pub use gateway::{CanGatewayRule, CanGatewayRuleInfo, Crc8Checksum, Crc8Profile, FrameModification, FrameModifications, XorChecksum};
+
+mod link;
+pub use link::{CanInterfaceInfo, CanLinkKind, OperationalState};
+
+mod stats;
+pub use stats::{BusErrorCounters, CanDeviceStats, CanInterfaceStats, CanState, LinkStats};
+
+#[cfg(feature = "vcan")]
+mod virtual_interface;
+#[cfg(feature = "vcan")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "vcan")))]
+pub use virtual_interface::VirtualInterface;
+
+/// A routing netlink socket to configure CAN interfaces.
+pub struct NetlinkSocket {
+	inner: crate::sys::netlink::NetlinkSocket,
+}
+
+impl std::fmt::Debug for NetlinkSocket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("NetlinkSocket")
+			.finish_non_exhaustive()
+	}
+}
+
+impl NetlinkSocket {
+	/// Open a new routing netlink socket.
+	pub fn new() -> std::io::Result<Self> {
+		Ok(Self {
+			inner: crate::sys::netlink::NetlinkSocket::new()?,
+		})
+	}
+
+	/// List all CAN interfaces on the system.
+	///
+	/// See also [`CanInterface::list()`].
+	pub fn list_interfaces(&mut self) -> std::io::Result<Vec<CanInterfaceInfo>> {
+		let mut message = MessageBuilder::new(libc::RTM_GETLINK, libc::NLM_F_DUMP);
+		message.push_header(ifinfomsg::new(0).as_bytes());
+		let mut interfaces = Vec::new();
+		for response in self.inner.request(message)? {
+			if let Some(info) = CanInterfaceInfo::parse(&response)? {
+				interfaces.push(info);
+			}
+		}
+		Ok(interfaces)
+	}
+
+	/// Get the CAN configuration of an interface.
+	///
+	/// Virtual CAN interfaces have no CAN specific configuration,
+	/// so all fields of the returned configuration will be `None`.
+	pub fn get_can_config(&mut self, interface: &CanInterface) -> std::io::Result<CanConfig> {
+		let (_header, attributes) = self.get_link(interface)?;
+		let info_data = find_attribute(&attributes, libc::IFLA_LINKINFO)
+			.and_then(|link_info| {
+				let kind = find_attribute(link_info, libc::IFLA_INFO_KIND).and_then(parse_str);
+				if kind.as_deref() == Some("can") {
+					find_attribute(link_info, libc::IFLA_INFO_DATA)
+				} else {
+					None
+				}
+			});
+		Ok(info_data.map(CanConfig::parse).unwrap_or_default())
+	}
+
+	/// Change the CAN configuration of an interface.
+	///
+	/// Most settings can only be changed while the interface is down.
+	pub fn set_can_config(&mut self, interface: &CanInterface, config: &CanConfigUpdate) -> std::io::Result<()> {
+		self.set_can_link_info(interface, |message| config.encode(message))
+	}
+
+	/// Get the controller state and statistics of an interface.
+	///
+	/// See also [`CanInterface::stats()`].
+	pub fn get_stats(&mut self, interface: &CanInterface) -> std::io::Result<CanInterfaceStats> {
+		let (_header, attributes) = self.get_link(interface)?;
+		Ok(CanInterfaceStats::parse(&attributes))
+	}
+
+	/// Check if an interface is administratively up.
+	pub fn is_link_up(&mut self, interface: &CanInterface) -> std::io::Result<bool> {
+		let (header, _attributes) = self.get_link(interface)?;
+		Ok(header.ifi_flags & libc::IFF_UP as u32 != 0)
+	}
+
+	/// Bring an interface up or down.
+	pub fn set_link_up(&mut self, interface: &CanInterface, up: bool) -> std::io::Result<()> {
+		let mut header = ifinfomsg::new(interface.index());
+		header.ifi_change = libc::IFF_UP as u32;
+		if up {
+			header.ifi_flags = libc::IFF_UP as u32;
+		}
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		message.push_header(header.as_bytes());
+		self.inner.request(message)?;
+		Ok(())
+	}
+
+	/// Restart a CAN interface that is in the bus-off state.
+	///
+	/// This fails with [`std::io::ErrorKind::ResourceBusy`] if the interface is not in the bus-off state.
+	pub fn restart(&mut self, interface: &CanInterface) -> std::io::Result<()> {
+		self.set_can_link_info(interface, |message| message.push_u32(config::IFLA_CAN_RESTART, 1))
+	}
+
+	/// Add a routing rule to the kernel CAN gateway.
+	pub fn add_gateway_rule(&mut self, rule: &CanGatewayRule) -> std::io::Result<()> {
+		let mut message = MessageBuilder::new(libc::RTM_NEWROUTE, 0);
+		rule.encode(&mut message);
+		self.inner.request(message)?;
+		Ok(())
+	}
+
+	/// List the routing rules of the kernel CAN gateway.
+	///
+	/// Rules for CAN FD frames are not supported and are not included in the list.
+	pub fn list_gateway_rules(&mut self) -> std::io::Result<Vec<CanGatewayRuleInfo>> {
+		let mut message = MessageBuilder::new(libc::RTM_GETROUTE, libc::NLM_F_DUMP);
+		message.push_header(&gateway::rtcanmsg(0));
+		let mut rules = Vec::new();
+		for response in self.inner.request(message)? {
+			if let Some(rule) = CanGatewayRuleInfo::parse(&response)? {
+				rules.push(rule);
+			}
+		}
+		Ok(rules)
+	}
+
+	/// Delete a routing rule from the kernel CAN gateway.
+	///
+	/// If the rule has a modification UID, the rule with that UID is deleted.
+	/// Otherwise, the rule must exactly match an existing rule.
+	pub fn delete_gateway_rule(&mut self, rule: &CanGatewayRule) -> std::io::Result<()> {
+		let mut message = MessageBuilder::new(libc::RTM_DELROUTE, 0);
+		rule.encode(&mut message);
+		self.inner.request(message)?;
+		Ok(())
+	}
+
+	/// Delete all routing rules from the kernel CAN gateway.
+	pub fn delete_all_gateway_rules(&mut self) -> std::io::Result<()> {
+		let mut message = MessageBuilder::new(libc::RTM_DELROUTE, 0);
+		message.push_header(&gateway::rtcanmsg(0));
+		self.inner.request(message)?;
+		Ok(())
+	}
+
+	/// Get the interface information message and the attributes of an interface.
+	fn get_link(&mut self, interface: &CanInterface) -> std::io::Result<(ifinfomsg, Vec<u8>)> {
+		let mut message = MessageBuilder::new(libc::RTM_GETLINK, 0);
+		message.push_header(ifinfomsg::new(interface.index()).as_bytes());
+		let responses = self.inner.request(message)?;
+		let response = responses.first()
+			.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "no response from kernel"))?;
+		let (header, attributes) = ifinfomsg::parse(response)?;
+		Ok((header, attributes.to_vec()))
+	}
+
+	/// Send a `RTM_NEWLINK` message with CAN specific link info.
+	fn set_can_link_info(&mut self, interface: &CanInterface, encode: impl FnOnce(&mut MessageBuilder)) -> std::io::Result<()> {
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		message.push_header(ifinfomsg::new(interface.index()).as_bytes());
+		let link_info = message.begin_nested(libc::IFLA_LINKINFO);
+		message.push_str(libc::IFLA_INFO_KIND, "can");
+		let info_data = message.begin_nested(libc::IFLA_INFO_DATA);
+		encode(&mut message);
+		message.end_nested(info_data);
+		message.end_nested(link_info);
+		self.inner.request(message)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,259 @@
+use crate::sys::netlink::{find_attribute, parse_pod, parse_str, Attributes};
+
+const IFLA_CAN_STATE: u16 = 4;
+const IFLA_CAN_BERR_COUNTER: u16 = 8;
+
+/// The state of a CAN controller, as reported by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CanState {
+	/// The controller is error active: both error counters are below 96.
+	ErrorActive,
+
+	/// One of the error counters reached the warning level (96).
+	ErrorWarning,
+
+	/// One of the error counters reached the error passive level (128).
+	ErrorPassive,
+
+	/// The controller is in the bus-off state and does not participate in bus traffic.
+	BusOff,
+
+	/// The controller is stopped.
+	Stopped,
+
+	/// The controller is sleeping.
+	Sleeping,
+
+	/// A state not known to this library.
+	Other(u32),
+}
+
+impl CanState {
+	fn from_raw(raw: u32) -> Self {
+		match raw {
+			0 => Self::ErrorActive,
+			1 => Self::ErrorWarning,
+			2 => Self::ErrorPassive,
+			3 => Self::BusOff,
+			4 => Self::Stopped,
+			5 => Self::Sleeping,
+			x => Self::Other(x),
+		}
+	}
+}
+
+/// The bus error counters of a CAN controller.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct BusErrorCounters {
+	/// The transmit error counter.
+	pub tx: u16,
+
+	/// The receive error counter.
+	pub rx: u16,
+}
+
+/// CAN specific statistics of an interface.
+///
+/// These match the fields of `struct can_device_stats` used by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(C)]
+pub struct CanDeviceStats {
+	/// The number of bus errors.
+	pub bus_error: u32,
+
+	/// The number of changes to the error warning state.
+	pub error_warning: u32,
+
+	/// The number of changes to the error passive state.
+	pub error_passive: u32,
+
+	/// The number of changes to the bus-off state.
+	pub bus_off: u32,
+
+	/// The number of times arbitration was lost.
+	pub arbitration_lost: u32,
+
+	/// The number of times the controller was restarted.
+	pub restarts: u32,
+}
+
+/// Generic statistics of a network interface.
+///
+/// These are a subset of the fields of `struct rtnl_link_stats64` used by the kernel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct LinkStats {
+	/// The number of received frames.
+	pub rx_frames: u64,
+
+	/// The number of received bytes.
+	pub rx_bytes: u64,
+
+	/// The number of receive errors.
+	pub rx_errors: u64,
+
+	/// The number of received frames that were dropped.
+	pub rx_dropped: u64,
+
+	/// The number of receive overruns of the controller.
+	pub rx_over_errors: u64,
+
+	/// The number of receive FIFO errors.
+	pub rx_fifo_errors: u64,
+
+	/// The number of frames missed by the controller.
+	pub rx_missed_errors: u64,
+
+	/// The number of transmitted frames.
+	pub tx_frames: u64,
+
+	/// The number of transmitted bytes.
+	pub tx_bytes: u64,
+
+	/// The number of transmit errors.
+	pub tx_errors: u64,
+
+	/// The number of frames that were dropped instead of transmitted.
+	pub tx_dropped: u64,
+
+	/// The number of aborted transmissions.
+	pub tx_aborted_errors: u64,
+
+	/// The number of transmit FIFO errors.
+	pub tx_fifo_errors: u64,
+}
+
+impl LinkStats {
+	/// Parse the statistics from a `IFLA_STATS64` attribute.
+	fn parse(data: &[u8]) -> Option<Self> {
+		let stats: [u64; 19] = parse_pod(data)?;
+		Some(Self {
+			rx_frames: stats[0],
+			tx_frames: stats[1],
+			rx_bytes: stats[2],
+			tx_bytes: stats[3],
+			rx_errors: stats[4],
+			tx_errors: stats[5],
+			rx_dropped: stats[6],
+			tx_dropped: stats[7],
+			rx_over_errors: stats[11],
+			rx_fifo_errors: stats[14],
+			rx_missed_errors: stats[15],
+			tx_aborted_errors: stats[16],
+			tx_fifo_errors: stats[18],
+		})
+	}
+}
+
+/// Statistics and state of a CAN interface.
+///
+/// Fields are `None` if the kernel did not report them.
+/// Virtual CAN interfaces only report the generic link statistics.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct CanInterfaceStats {
+	/// The state of the CAN controller.
+	pub state: Option<CanState>,
+
+	/// The bus error counters of the CAN controller.
+	///
+	/// Not all drivers report the error counters.
+	pub bus_error_counters: Option<BusErrorCounters>,
+
+	/// CAN specific statistics of the interface.
+	pub device_stats: Option<CanDeviceStats>,
+
+	/// Generic statistics of the interface.
+	pub link_stats: Option<LinkStats>,
+}
+
+impl CanInterfaceStats {
+	/// Parse the statistics from the attributes of a `RTM_NEWLINK` message.
+	pub(crate) fn parse(attributes: &[u8]) -> Self {
+		let mut stats = Self {
+			link_stats: find_attribute(attributes, libc::IFLA_STATS64).and_then(LinkStats::parse),
+			..Self::default()
+		};
+
+		let Some(link_info) = find_attribute(attributes, libc::IFLA_LINKINFO) else {
+			return stats;
+		};
+		let kind = find_attribute(link_info, libc::IFLA_INFO_KIND).and_then(parse_str);
+		if kind.as_deref() != Some("can") {
+			return stats;
+		}
+
+		stats.device_stats = find_attribute(link_info, libc::IFLA_INFO_XSTATS).and_then(parse_pod);
+		if let Some(info_data) = find_attribute(link_info, libc::IFLA_INFO_DATA) {
+			for (kind, value) in Attributes::new(info_data) {
+				match kind {
+					IFLA_CAN_STATE => stats.state = parse_pod(value).map(CanState::from_raw),
+					IFLA_CAN_BERR_COUNTER => {
+						stats.bus_error_counters = parse_pod::<[u16; 2]>(value)
+							.map(|[tx, rx]| BusErrorCounters { tx, rx });
+					},
+					_ => (),
+				}
+			}
+		}
+		stats
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::sys::netlink::MessageBuilder;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn parse_stats() {
+		let link_stats: Vec<u8> = (1..=24u64).flat_map(|x| x.to_ne_bytes()).collect();
+		let device_stats: Vec<u8> = [10u32, 11, 12, 13, 14, 15].iter().flat_map(|x| x.to_ne_bytes()).collect();
+
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		message.push_attribute(libc::IFLA_STATS64, &link_stats);
+		let link_info = message.begin_nested(libc::IFLA_LINKINFO);
+		message.push_str(libc::IFLA_INFO_KIND, "can");
+		let info_data = message.begin_nested(libc::IFLA_INFO_DATA);
+		message.push_u32(IFLA_CAN_STATE, 2);
+		message.push_attribute(IFLA_CAN_BERR_COUNTER, &[200u16.to_ne_bytes(), 5u16.to_ne_bytes()].concat());
+		message.end_nested(info_data);
+		message.push_attribute(libc::IFLA_INFO_XSTATS, &device_stats);
+		message.end_nested(link_info);
+
+		let stats = CanInterfaceStats::parse(message.payload());
+		assert!(stats.state == Some(CanState::ErrorPassive));
+		assert!(stats.bus_error_counters == Some(BusErrorCounters { tx: 200, rx: 5 }));
+		assert!(stats.device_stats == Some(CanDeviceStats {
+			bus_error: 10,
+			error_warning: 11,
+			error_passive: 12,
+			bus_off: 13,
+			arbitration_lost: 14,
+			restarts: 15,
+		}));
+		let_assert!(Some(link_stats) = stats.link_stats);
+		assert!(link_stats.rx_frames == 1);
+		assert!(link_stats.tx_frames == 2);
+		assert!(link_stats.rx_bytes == 3);
+		assert!(link_stats.tx_bytes == 4);
+		assert!(link_stats.rx_dropped == 7);
+		assert!(link_stats.rx_over_errors == 12);
+		assert!(link_stats.tx_fifo_errors == 19);
+	}
+
+	#[test]
+	fn parse_vcan_stats() {
+		let link_stats: Vec<u8> = (1..=24u64).flat_map(|x| x.to_ne_bytes()).collect();
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		message.push_attribute(libc::IFLA_STATS64, &link_stats);
+		let link_info = message.begin_nested(libc::IFLA_LINKINFO);
+		message.push_str(libc::IFLA_INFO_KIND, "vcan");
+		message.end_nested(link_info);
+
+		let stats = CanInterfaceStats::parse(message.payload());
+		assert!(let None = stats.state);
+		assert!(let None = stats.bus_error_counters);
+		assert!(let None = stats.device_stats);
+		assert!(let Some(_) = stats.link_stats);
+	}
+}
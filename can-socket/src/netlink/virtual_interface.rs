@@ -0,0 +1,177 @@
+use crate::sys::netlink::{ifinfomsg, MessageBuilder};
+use crate::CanInterface;
+use super::NetlinkSocket;
+
+/// The nested attribute with the peer configuration of a `vxcan` interface.
+const VXCAN_INFO_PEER: u16 = 1;
+
+/// A virtual CAN interface that is deleted when dropped.
+///
+/// This can be a single `vcan` interface, or a pair of `vxcan` interfaces.
+/// Frames sent on one end of a `vxcan` pair are received on the other end.
+///
+/// Creating and deleting interfaces requires the `CAP_NET_ADMIN` capability.
+/// The interfaces are created in the network namespace of the calling thread.
+#[derive(Debug)]
+pub struct VirtualInterface {
+	interface: CanInterface,
+	name: String,
+	peer: Option<(CanInterface, String)>,
+	deleted: bool,
+}
+
+impl VirtualInterface {
+	/// Create a new `vcan` interface and bring it up.
+	pub fn create_vcan(name: &str) -> std::io::Result<Self> {
+		Self::create_vcan_with_mtu(name, None)
+	}
+
+	/// Create a new `vcan` interface with a specific MTU and bring it up.
+	///
+	/// Use an MTU of 72 to support CAN FD frames, or 2060 to support CAN XL frames.
+	pub fn create_vcan_with_mtu(name: &str, mtu: Option<u32>) -> std::io::Result<Self> {
+		let mut netlink = NetlinkSocket::new()?;
+		let interface = netlink.create_vcan(name, mtu)?;
+		Ok(Self {
+			interface,
+			name: name.into(),
+			peer: None,
+			deleted: false,
+		})
+	}
+
+	/// Create a new pair of `vxcan` interfaces and bring them up.
+	pub fn create_vxcan_pair(name: &str, peer_name: &str) -> std::io::Result<Self> {
+		let mut netlink = NetlinkSocket::new()?;
+		let (interface, peer) = netlink.create_vxcan_pair(name, peer_name)?;
+		Ok(Self {
+			interface,
+			name: name.into(),
+			peer: Some((peer, peer_name.into())),
+			deleted: false,
+		})
+	}
+
+	/// Get the interface.
+	pub fn interface(&self) -> &CanInterface {
+		&self.interface
+	}
+
+	/// Get the name of the interface.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the peer interface of a `vxcan` pair.
+	///
+	/// Returns `None` for `vcan` interfaces.
+	pub fn peer(&self) -> Option<&CanInterface> {
+		self.peer.as_ref().map(|(peer, _name)| peer)
+	}
+
+	/// Get the name of the peer interface of a `vxcan` pair.
+	///
+	/// Returns `None` for `vcan` interfaces.
+	pub fn peer_name(&self) -> Option<&str> {
+		self.peer.as_ref().map(|(_peer, name)| name.as_str())
+	}
+
+	/// Delete the interface.
+	///
+	/// Deleting one end of a `vxcan` pair also deletes the peer.
+	///
+	/// The interface is also deleted when the [`VirtualInterface`] is dropped,
+	/// but this function allows you to handle errors.
+	pub fn delete(mut self) -> std::io::Result<()> {
+		self.deleted = true;
+		NetlinkSocket::new()?.delete_interface(&self.interface)
+	}
+}
+
+impl Drop for VirtualInterface {
+	fn drop(&mut self) {
+		if !self.deleted {
+			if let Ok(mut netlink) = NetlinkSocket::new() {
+				netlink.delete_interface(&self.interface).ok();
+			}
+		}
+	}
+}
+
+impl NetlinkSocket {
+	/// Create a new `vcan` interface and bring it up.
+	///
+	/// The interface is not deleted automatically.
+	/// Use [`VirtualInterface::create_vcan()`] to get an interface that is deleted when dropped.
+	#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "vcan")))]
+	pub fn create_vcan(&mut self, name: &str, mtu: Option<u32>) -> std::io::Result<CanInterface> {
+		let mut message = new_link_message(name);
+		if let Some(mtu) = mtu {
+			message.push_u32(libc::IFLA_MTU, mtu);
+		}
+		let link_info = message.begin_nested(libc::IFLA_LINKINFO);
+		message.push_str(libc::IFLA_INFO_KIND, "vcan");
+		message.end_nested(link_info);
+		self.inner.request(message)?;
+		self.lookup_or_delete(name, || CanInterface::from_name(name))
+	}
+
+	/// Create a new pair of `vxcan` interfaces and bring them up.
+	///
+	/// The interfaces are not deleted automatically.
+	/// Use [`VirtualInterface::create_vxcan_pair()`] to get an interface pair that is deleted when dropped.
+	#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "vcan")))]
+	pub fn create_vxcan_pair(&mut self, name: &str, peer_name: &str) -> std::io::Result<(CanInterface, CanInterface)> {
+		let mut message = new_link_message(name);
+		let link_info = message.begin_nested(libc::IFLA_LINKINFO);
+		message.push_str(libc::IFLA_INFO_KIND, "vxcan");
+		let info_data = message.begin_nested(libc::IFLA_INFO_DATA);
+		let peer = message.begin_nested(VXCAN_INFO_PEER);
+		message.push_header(up_header().as_bytes());
+		message.push_str(libc::IFLA_IFNAME, peer_name);
+		message.end_nested(peer);
+		message.end_nested(info_data);
+		message.end_nested(link_info);
+		self.inner.request(message)?;
+		self.lookup_or_delete(name, || Ok((CanInterface::from_name(name)?, CanInterface::from_name(peer_name)?)))
+	}
+
+	/// Delete an interface.
+	#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "vcan")))]
+	pub fn delete_interface(&mut self, interface: &CanInterface) -> std::io::Result<()> {
+		let mut message = MessageBuilder::new(libc::RTM_DELLINK, 0);
+		message.push_header(ifinfomsg::new(interface.index()).as_bytes());
+		self.inner.request(message)?;
+		Ok(())
+	}
+
+	/// Look up a newly created interface, and delete the interface again if that fails.
+	///
+	/// Deleting one end of a `vxcan` pair also deletes the peer.
+	fn lookup_or_delete<T>(&mut self, name: &str, lookup: impl FnOnce() -> std::io::Result<T>) -> std::io::Result<T> {
+		let result = lookup();
+		if result.is_err() {
+			let mut message = MessageBuilder::new(libc::RTM_DELLINK, 0);
+			message.push_header(ifinfomsg::new(0).as_bytes());
+			message.push_str(libc::IFLA_IFNAME, name);
+			self.inner.request(message).ok();
+		}
+		result
+	}
+}
+
+/// Get an interface information message that brings the interface up.
+fn up_header() -> ifinfomsg {
+	let mut header = ifinfomsg::new(0);
+	header.ifi_flags = libc::IFF_UP as u32;
+	header.ifi_change = libc::IFF_UP as u32;
+	header
+}
+
+/// Create a `RTM_NEWLINK` message to create a new interface that is brought up immediately.
+fn new_link_message(name: &str) -> MessageBuilder {
+	let mut message = MessageBuilder::new(libc::RTM_NEWLINK, libc::NLM_F_CREATE | libc::NLM_F_EXCL);
+	message.push_header(up_header().as_bytes());
+	message.push_str(libc::IFLA_IFNAME, name);
+	message
+}
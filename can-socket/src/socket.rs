@@ -1,4 +1,4 @@
-use crate::{CanFilter, CanFrame, CanInterface};
+use crate::{AnyCanFrame, CanErrorMask, CanFdFrame, CanFilter, CanFrame, CanInterface, CanXlFrame, RecvMetadata, TimestampMode, Timestamps};
 
 /// A synchronous CAN socket.
 ///
@@ -69,7 +69,7 @@ impl CanSocket {
 
 	/// Send a frame over the socket.
 	///
-	/// Note that if this function success, it only means that the kernel accepted the frame for transmission.
+	/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
 	/// It does not mean the frame has been successfully transmitted over the CAN bus.
 	pub fn send(&self, frame: &CanFrame) -> std::io::Result<()> {
 		self.inner.send(&frame.inner)
@@ -77,13 +77,72 @@ impl CanSocket {
 
 	/// Send a frame over a particular interface.
 	///
-	/// Note that if this function success, it only means that the kernel accepted the frame for transmission.
+	/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
 	/// It does not mean the frame has been successfully transmitted over the CAN bus.
 	pub fn send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
 		self.inner.send_to(&frame.inner, &interface.inner)
 	}
 
+	/// Send multiple frames over the socket with a single system call.
+	///
+	/// Returns the number of frames that were sent, which may be less than the number of frames given.
+	/// If no frame could be sent at all, an error is returned.
+	///
+	/// Note that if this function succeeds, it only means that the kernel accepted the frames for transmission.
+	/// It does not mean the frames have been successfully transmitted over the CAN bus.
+	pub fn send_batch(&self, frames: &[CanFrame]) -> std::io::Result<usize> {
+		self.inner.send_batch(frames)
+	}
+
+	/// Send a CAN FD frame over the socket.
+	///
+	/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+	/// and the interface must support CAN FD.
+	///
+	/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+	/// It does not mean the frame has been successfully transmitted over the CAN bus.
+	pub fn send_fd(&self, frame: &CanFdFrame) -> std::io::Result<()> {
+		self.inner.send_fd(&frame.inner)
+	}
+
+	/// Send a CAN FD frame over a particular interface.
+	///
+	/// CAN FD frames must be enabled on the socket with [`Self::set_fd_frames()`],
+	/// and the interface must support CAN FD.
+	///
+	/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+	/// It does not mean the frame has been successfully transmitted over the CAN bus.
+	pub fn send_fd_to(&self, frame: &CanFdFrame, interface: &CanInterface) -> std::io::Result<()> {
+		self.inner.send_fd_to(&frame.inner, &interface.inner)
+	}
+
+	/// Send a CAN XL frame over the socket.
+	///
+	/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+	/// and the interface must support CAN XL.
+	///
+	/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+	/// It does not mean the frame has been successfully transmitted over the CAN bus.
+	pub fn send_xl(&self, frame: &CanXlFrame) -> std::io::Result<()> {
+		self.inner.send_xl(&frame.inner)
+	}
+
+	/// Send a CAN XL frame over a particular interface.
+	///
+	/// CAN XL frames must be enabled on the socket with [`Self::set_xl_frames()`],
+	/// and the interface must support CAN XL.
+	///
+	/// Note that if this function succeeds, it only means that the kernel accepted the frame for transmission.
+	/// It does not mean the frame has been successfully transmitted over the CAN bus.
+	pub fn send_xl_to(&self, frame: &CanXlFrame, interface: &CanInterface) -> std::io::Result<()> {
+		self.inner.send_xl_to(&frame.inner, &interface.inner)
+	}
+
 	/// Receive a frame from the socket.
+	///
+	/// If CAN FD frames, CAN XL frames or error frames are enabled on the socket and such a frame is received,
+	/// this function returns an error and the frame is discarded.
+	/// Use [`Self::recv_any()`] to receive all types of frames.
 	pub fn recv(&self) -> std::io::Result<CanFrame> {
 		Ok(CanFrame {
 			inner: self.inner.recv()?,
@@ -91,6 +150,10 @@ impl CanSocket {
 	}
 
 	/// Receive a frame from the socket, including information about which interface the frame was received on.
+	///
+	/// If CAN FD frames, CAN XL frames or error frames are enabled on the socket and such a frame is received,
+	/// this function returns an error and the frame is discarded.
+	/// Use [`Self::recv_any_from()`] to receive all types of frames.
 	pub fn recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
 		let (frame, interface) = self.inner.recv_from()?;
 		let frame = CanFrame { inner: frame };
@@ -98,16 +161,211 @@ impl CanSocket {
 		Ok((frame, interface))
 	}
 
+	/// Receive multiple frames from the socket with a single system call.
+	///
+	/// The received frames are written to the start of `frames`.
+	/// Returns the number of frames received.
+	///
+	/// In blocking mode, this function blocks until at least one frame is available,
+	/// and then returns all frames that are available without blocking (up to `frames.len()`).
+	///
+	/// Frames that are not classic CAN frames are discarded.
+	/// If all received frames are discarded, this function keeps waiting for more frames.
+	pub fn recv_batch(&self, frames: &mut [CanFrame]) -> std::io::Result<usize> {
+		self.inner.recv_batch(frames, None)
+	}
+
+	/// Receive multiple frames from the socket with a single system call, including the interface of each frame.
+	///
+	/// The received frames are written to the start of `frames`,
+	/// and the interface of each frame is written to the same index in `interfaces`.
+	/// At most `min(frames.len(), interfaces.len())` frames are received.
+	/// Returns the number of frames received.
+	///
+	/// In blocking mode, this function blocks until at least one frame is available,
+	/// and then returns all frames that are available without blocking.
+	///
+	/// Frames that are not classic CAN frames are discarded.
+	/// If all received frames are discarded, this function keeps waiting for more frames.
+	pub fn recv_batch_from(&self, frames: &mut [CanFrame], interfaces: &mut [CanInterface]) -> std::io::Result<usize> {
+		self.inner.recv_batch(frames, Some(interfaces))
+	}
+
+	/// Receive a frame from the socket, including the timestamps of the frame.
+	///
+	/// Timestamps must be enabled with [`Self::set_timestamp_mode()`],
+	/// otherwise the returned timestamps are all `None`.
+	///
+	/// Like [`Self::recv()`], this function returns an error if it receives a frame that is not a classic CAN frame.
+	pub fn recv_with_timestamp(&self) -> std::io::Result<(CanFrame, Timestamps)> {
+		let (frame, metadata) = self.inner.recv_with_metadata()?;
+		Ok((CanFrame { inner: frame }, metadata.timestamps))
+	}
+
+	/// Receive a frame from the socket, including metadata such as the interface, timestamps and drop count.
+	///
+	/// Timestamps must be enabled with [`Self::set_timestamp_mode()`],
+	/// otherwise the returned timestamps are all `None`.
+	///
+	/// Like [`Self::recv()`], this function returns an error if it receives a frame that is not a classic CAN frame.
+	pub fn recv_from_with_metadata(&self) -> std::io::Result<(CanFrame, RecvMetadata)> {
+		let (frame, metadata) = self.inner.recv_with_metadata()?;
+		Ok((CanFrame { inner: frame }, RecvMetadata::from_sys(metadata)))
+	}
+
+	/// Receive a frame of any type from the socket.
+	///
+	/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+	/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+	/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+	pub fn recv_any(&self) -> std::io::Result<AnyCanFrame> {
+		Ok(AnyCanFrame::from_sys(self.inner.recv_any()?))
+	}
+
+	/// Receive a frame of any type from the socket, including information about which interface the frame was received on.
+	///
+	/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+	/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+	/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+	pub fn recv_any_from(&self) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+		let (frame, interface) = self.inner.recv_any_from()?;
+		let frame = AnyCanFrame::from_sys(frame);
+		let interface = CanInterface { inner: interface };
+		Ok((frame, interface))
+	}
+
+	/// Receive a frame of any type from the socket, including metadata such as the interface, timestamps and drop count.
+	///
+	/// Timestamps must be enabled with [`Self::set_timestamp_mode()`],
+	/// otherwise the returned timestamps are all `None`.
+	///
+	/// CAN FD frames are only received if they are enabled with [`Self::set_fd_frames()`].
+	/// CAN XL frames are only received if they are enabled with [`Self::set_xl_frames()`].
+	/// Error frames are only received if they are enabled with [`Self::set_error_mask()`].
+	pub fn recv_any_with_metadata(&self) -> std::io::Result<(AnyCanFrame, RecvMetadata)> {
+		let (frame, metadata) = self.inner.recv_any_with_metadata()?;
+		Ok((AnyCanFrame::from_sys(frame), RecvMetadata::from_sys(metadata)))
+	}
+
 	/// Set the list of filters on the socket.
 	///
 	/// When a socket is created, it will receive all frames from the CAN interface.
 	/// You can restrict this by setting the filters with this function.
 	///
-	/// A frame has to match only one of the filters in the list to be received by the socket.
+	/// A frame has to match only one of the filters in the list to be received by the socket,
+	/// unless the filters are joined with [`Self::set_join_filters()`].
 	pub fn set_filters(&self, filters: &[CanFilter]) -> std::io::Result<()> {
 		self.inner.set_filters(filters)
 	}
 
+	/// Get the list of filters on the socket.
+	///
+	/// New sockets have a single filter that matches all frames.
+	pub fn get_filters(&self) -> std::io::Result<Vec<CanFilter>> {
+		let filters = self.inner.get_filters()?;
+		Ok(filters.into_iter().map(|filter| CanFilter { filter }).collect())
+	}
+
+	/// Check if the filters of the socket are joined.
+	///
+	/// When joined, a frame has to match all filters to be received by the socket.
+	pub fn get_join_filters(&self) -> std::io::Result<bool> {
+		self.inner.get_join_filters()
+	}
+
+	/// Set if the filters of the socket should be joined.
+	///
+	/// When disabled (the default for new sockets), a frame has to match only one of the filters to be received by the socket.
+	/// When enabled, a frame has to match all of the filters instead.
+	///
+	/// Joining filters allows you to express things like "ID in range A, but not in range B"
+	/// by combining a normal filter with an inverted filter.
+	pub fn set_join_filters(&self, enable: bool) -> std::io::Result<()> {
+		self.inner.set_join_filters(enable)
+	}
+
+	/// Check if CAN FD frames are enabled on the socket.
+	///
+	/// When disabled (the default for new sockets), the socket can only send and receive classic CAN frames.
+	pub fn get_fd_frames(&self) -> std::io::Result<bool> {
+		self.inner.get_fd_frames()
+	}
+
+	/// Enable or disable CAN FD frames on the socket.
+	///
+	/// When enabled, the socket can send CAN FD frames with [`Self::send_fd()`],
+	/// and it will receive CAN FD frames in addition to classic CAN frames.
+	/// Use [`Self::recv_any()`] to receive both types of frames.
+	///
+	/// Enabling CAN FD frames can fail if the kernel does not support CAN FD.
+	/// Sending CAN FD frames will fail if the interface does not support CAN FD.
+	pub fn set_fd_frames(&self, enable: bool) -> std::io::Result<()> {
+		self.inner.set_fd_frames(enable)
+	}
+
+	/// Get the timestamp mode of the socket.
+	pub fn get_timestamp_mode(&self) -> std::io::Result<TimestampMode> {
+		self.inner.get_timestamp_mode()
+	}
+
+	/// Set the timestamp mode of the socket.
+	///
+	/// When enabled, the kernel generates timestamps for received frames.
+	/// Use [`Self::recv_with_timestamp()`] or [`Self::recv_from_with_metadata()`] to receive the timestamps.
+	pub fn set_timestamp_mode(&self, mode: TimestampMode) -> std::io::Result<()> {
+		self.inner.set_timestamp_mode(mode)
+	}
+
+	/// Check if the drop count is reported for received frames.
+	pub fn get_drop_count_enabled(&self) -> std::io::Result<bool> {
+		self.inner.get_drop_count_enabled()
+	}
+
+	/// Enable or disable reporting the drop count for received frames (`SO_RXQ_OVFL`).
+	///
+	/// When enabled, the kernel reports the total number of frames dropped because the receive queue of the socket was full.
+	/// Use [`Self::recv_from_with_metadata()`] and [`RecvMetadata::drop_count()`] to get the drop count.
+	pub fn set_drop_count_enabled(&self, enable: bool) -> std::io::Result<()> {
+		self.inner.set_drop_count_enabled(enable)
+	}
+
+	/// Get the error mask of the socket.
+	///
+	/// The socket only receives error frames for the error classes in the mask.
+	pub fn get_error_mask(&self) -> std::io::Result<CanErrorMask> {
+		Ok(CanErrorMask::from_bits(self.inner.get_error_mask()?))
+	}
+
+	/// Set the error mask of the socket.
+	///
+	/// The socket will receive error frames for the error classes in the mask.
+	/// The default for new sockets is [`CanErrorMask::NONE`], so no error frames are received.
+	///
+	/// Error frames are received with [`Self::recv_any()`] as [`AnyCanFrame::Error`].
+	/// [`Self::recv()`] returns an error if it receives an error frame.
+	pub fn set_error_mask(&self, mask: CanErrorMask) -> std::io::Result<()> {
+		self.inner.set_error_mask(mask.bits())
+	}
+
+	/// Check if CAN XL frames are enabled on the socket.
+	///
+	/// When disabled (the default for new sockets), the socket can not send or receive CAN XL frames.
+	pub fn get_xl_frames(&self) -> std::io::Result<bool> {
+		self.inner.get_xl_frames()
+	}
+
+	/// Enable or disable CAN XL frames on the socket.
+	///
+	/// When enabled, the socket can send CAN XL frames with [`Self::send_xl()`],
+	/// and it will receive CAN XL frames in addition to other frames.
+	/// Use [`Self::recv_any()`] to receive all types of frames.
+	///
+	/// Enabling CAN XL frames can fail if the kernel does not support CAN XL.
+	/// Sending CAN XL frames will fail if the interface does not support CAN XL.
+	pub fn set_xl_frames(&self, enable: bool) -> std::io::Result<()> {
+		self.inner.set_xl_frames(enable)
+	}
+
 	/// Check if the loopback option of the socket is enabled.
 	///
 	/// When enabled (the default for new sockets),
@@ -0,0 +1,327 @@
+use crate::error::{ParseFilterError, ParseFrameError};
+use crate::{AnyCanFrame, CanData, CanErrorFrame, CanErrorMask, CanFdData, CanFdFrame, CanFilter, CanFrame, CanId, CanXlData, CanXlFrame, StandardId};
+
+/// Flag in the CAN ID of extended frames.
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+
+/// Mask for the ID bits of extended IDs.
+const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+
+/// Flag in the CAN ID of error frames.
+const CAN_ERR_FLAG: u32 = 0x2000_0000;
+
+/// Flag in the CAN ID of a filter to invert the filter.
+const CAN_INV_FILTER: u32 = 0x2000_0000;
+
+/// Mask for the error classes in the CAN ID of error frames.
+const CAN_ERR_MASK: u32 = 0x1FFF_FFFF;
+
+/// Bit rate switch flag of CAN FD frames.
+const CANFD_BRS: u8 = 0x01;
+
+/// Error state indicator flag of CAN FD frames.
+const CANFD_ESI: u8 = 0x02;
+
+/// Flag to mark a frame as CAN FD frame.
+const CANFD_FDF: u8 = 0x04;
+
+/// Simple extended content flag of CAN XL frames.
+const CANXL_SEC: u8 = 0x01;
+
+/// Flag to mark a frame as CAN XL frame.
+const CANXL_XLF: u8 = 0x80;
+
+fn error(message: &'static str) -> ParseFrameError {
+	ParseFrameError { message }
+}
+
+/// Parse a frame in the format used by `candump -l` and `cansend`.
+pub(crate) fn parse_frame(input: &str) -> Result<AnyCanFrame, ParseFrameError> {
+	let (id, body) = input.split_once('#').ok_or(error("missing `#` after the CAN ID"))?;
+	match id.len() {
+		3 => {
+			let id = parse_hex(id)
+				.and_then(|id| CanId::new_standard(id as u16).ok())
+				.ok_or(error("invalid standard CAN ID"))?;
+			parse_can_or_fd_frame(id, body)
+		},
+		8 => {
+			let id = parse_hex(id).ok_or(error("invalid extended CAN ID"))?;
+			if id & CAN_ERR_FLAG != 0 {
+				if id & !(CAN_ERR_FLAG | CAN_ERR_MASK) != 0 {
+					return Err(error("invalid error frame ID"));
+				}
+				parse_error_frame(id & CAN_ERR_MASK, body)
+			} else {
+				let id = CanId::new_extended(id).map_err(|_| error("invalid extended CAN ID"))?;
+				parse_can_or_fd_frame(id, body)
+			}
+		},
+		5 => parse_xl_frame(id, body),
+		_ => Err(error("invalid CAN ID, expected 3, 5 or 8 hexadecimal digits")),
+	}
+}
+
+/// Parse the part after the `#` of a classic CAN frame or CAN FD frame.
+fn parse_can_or_fd_frame(id: CanId, body: &str) -> Result<AnyCanFrame, ParseFrameError> {
+	if let Some(body) = body.strip_prefix('#') {
+		let flags = body.get(..1)
+			.and_then(parse_hex)
+			.ok_or(error("invalid CAN FD flags"))? as u8;
+		let data = parse_data(&body[1..])?;
+		let data = CanFdData::try_from(data.as_slice())
+			.map_err(|_| error("too much data for a CAN FD frame"))?;
+		let frame = CanFdFrame::new(id, data)
+			.with_bit_rate_switch(flags & CANFD_BRS != 0)
+			.with_error_state_indicator(flags & CANFD_ESI != 0);
+		Ok(frame.into())
+	} else if let Some(rtr) = body.strip_prefix(['R', 'r']) {
+		let (len, raw_dlc) = split_raw_dlc(rtr)?;
+		let len = match len {
+			"" => 0,
+			len => parse_hex(len)
+				.filter(|&len| len <= 8)
+				.ok_or(error("invalid length for RTR frame"))? as u8,
+		};
+		if raw_dlc.is_some() && len != 8 {
+			return Err(error("a raw data length code requires a length of 8"));
+		}
+		let frame = CanFrame::new_rtr(id)
+			.with_data_length_code(raw_dlc.unwrap_or(len))
+			.map_err(|_| error("invalid data length code"))?;
+		Ok(frame.into())
+	} else {
+		let (data, raw_dlc) = split_raw_dlc(body)?;
+		let data = parse_data(data)?;
+		let data = CanData::try_from(data.as_slice())
+			.map_err(|_| error("too much data for a classic CAN frame, use `##` for CAN FD frames"))?;
+		if raw_dlc.is_some() && data.len() != 8 {
+			return Err(error("a raw data length code requires 8 data bytes"));
+		}
+		let mut frame = CanFrame::new(id, data);
+		if let Some(dlc) = raw_dlc {
+			frame = frame.with_data_length_code(dlc)
+				.map_err(|_| error("invalid data length code"))?;
+		}
+		Ok(frame.into())
+	}
+}
+
+/// Parse the part after the `#` of an error frame.
+fn parse_error_frame(class: u32, body: &str) -> Result<AnyCanFrame, ParseFrameError> {
+	let data = parse_data(body)?;
+	if data.len() > 8 {
+		return Err(error("too much data for an error frame"));
+	}
+	let mut padded = [0; 8];
+	padded[..data.len()].copy_from_slice(&data);
+	let frame = CanErrorFrame {
+		inner: crate::sys::CanErrorFrame::from_raw(class, padded),
+	};
+	Ok(frame.into())
+}
+
+/// Parse a CAN XL frame in the form `VVPPP#FF:SS:AAAAAAAA#data`.
+fn parse_xl_frame(id: &str, body: &str) -> Result<AnyCanFrame, ParseFrameError> {
+	let vcid = id.get(..2).and_then(parse_hex).ok_or(error("invalid CAN XL virtual CAN ID"))? as u8;
+	let priority = id.get(2..)
+		.and_then(parse_hex)
+		.and_then(|priority| StandardId::new(priority as u16).ok())
+		.ok_or(error("invalid CAN XL priority"))?;
+
+	let (header, data) = body.split_once('#').ok_or(error("missing `#` after the CAN XL header"))?;
+	let mut header = header.split(':');
+	let (Some(flags), Some(sdu_type), Some(acceptance_field), None) = (header.next(), header.next(), header.next(), header.next()) else {
+		return Err(error("invalid CAN XL header"));
+	};
+	let flags = Some(flags).filter(|x| x.len() == 2).and_then(parse_hex).ok_or(error("invalid CAN XL flags"))? as u8;
+	let sdu_type = Some(sdu_type).filter(|x| x.len() == 2).and_then(parse_hex).ok_or(error("invalid CAN XL SDU type"))? as u8;
+	let acceptance_field = Some(acceptance_field).filter(|x| x.len() == 8).and_then(parse_hex).ok_or(error("invalid CAN XL acceptance field"))?;
+	if flags & CANXL_XLF == 0 {
+		return Err(error("missing XLF flag in CAN XL frame"));
+	}
+
+	let data = parse_data(data)?;
+	let data = CanXlData::try_from(data.as_slice())
+		.map_err(|_| error("CAN XL frames must have 1 to 2048 data bytes"))?;
+	let frame = CanXlFrame::new(priority, data)
+		.with_virtual_can_id(vcid)
+		.with_sdu_type(sdu_type)
+		.with_acceptance_field(acceptance_field)
+		.with_simple_extended_content(flags & CANXL_SEC != 0);
+	Ok(frame.into())
+}
+
+/// Split an optional raw data length code in the form `_X` from the end of the input.
+fn split_raw_dlc(input: &str) -> Result<(&str, Option<u8>), ParseFrameError> {
+	match input.split_once('_') {
+		None => Ok((input, None)),
+		Some((input, dlc)) => {
+			let dlc = Some(dlc)
+				.filter(|dlc| dlc.len() == 1)
+				.and_then(parse_hex)
+				.filter(|dlc| (9..=15).contains(dlc))
+				.ok_or(error("invalid raw data length code, expected a value from 9 to F"))?;
+			Ok((input, Some(dlc as u8)))
+		},
+	}
+}
+
+/// Parse hexadecimal frame data, optionally with `.` between the bytes.
+fn parse_data(input: &str) -> Result<Vec<u8>, ParseFrameError> {
+	let mut data = Vec::with_capacity(input.len() / 2);
+	let mut digits = input.bytes().filter(|&c| c != b'.');
+	while let Some(high) = digits.next() {
+		let low = digits.next().ok_or(error("odd number of hexadecimal digits in frame data"))?;
+		let high = hex_digit(high).ok_or(error("invalid hexadecimal digit in frame data"))?;
+		let low = hex_digit(low).ok_or(error("invalid hexadecimal digit in frame data"))?;
+		data.push(high << 4 | low);
+	}
+	Ok(data)
+}
+
+pub(crate) fn parse_hex(input: &str) -> Option<u32> {
+	if input.bytes().all(|c| c.is_ascii_hexdigit()) {
+		u32::from_str_radix(input, 16).ok()
+	} else {
+		None
+	}
+}
+
+fn hex_digit(digit: u8) -> Option<u8> {
+	char::from(digit).to_digit(16).map(|x| x as u8)
+}
+
+/// Write a frame in the format used by `candump -l` and `cansend`.
+pub(crate) fn write_frame(f: &mut std::fmt::Formatter<'_>, frame: &AnyCanFrame) -> std::fmt::Result {
+	match frame {
+		AnyCanFrame::Classic(frame) => write_can_frame(f, frame),
+		AnyCanFrame::Fd(frame) => write_fd_frame(f, frame),
+		AnyCanFrame::Xl(frame) => write_xl_frame(f, frame),
+		AnyCanFrame::Error(frame) => write_error_frame(f, frame),
+	}
+}
+
+pub(crate) fn write_can_frame(f: &mut std::fmt::Formatter<'_>, frame: &CanFrame) -> std::fmt::Result {
+	write_id(f, frame.id())?;
+	f.write_str("#")?;
+	let dlc = frame.data_length_code();
+	match frame.data() {
+		Some(data) => write_data(f, &data)?,
+		None => {
+			f.write_str("R")?;
+			if dlc > 0 {
+				write!(f, "{}", dlc.min(8))?;
+			}
+		},
+	}
+	if dlc > 8 {
+		write!(f, "_{dlc:X}")?;
+	}
+	Ok(())
+}
+
+pub(crate) fn write_fd_frame(f: &mut std::fmt::Formatter<'_>, frame: &CanFdFrame) -> std::fmt::Result {
+	let mut flags = CANFD_FDF;
+	if frame.bit_rate_switch() {
+		flags |= CANFD_BRS;
+	}
+	if frame.error_state_indicator() {
+		flags |= CANFD_ESI;
+	}
+	write_id(f, frame.id())?;
+	write!(f, "##{flags:X}")?;
+	write_data(f, &frame.data())
+}
+
+fn write_xl_frame(f: &mut std::fmt::Formatter<'_>, frame: &CanXlFrame) -> std::fmt::Result {
+	let mut flags = CANXL_XLF;
+	if frame.simple_extended_content() {
+		flags |= CANXL_SEC;
+	}
+	write!(
+		f,
+		"{:02X}{:03X}#{:02X}:{:02X}:{:08X}#",
+		frame.virtual_can_id(),
+		frame.priority().as_u16(),
+		flags,
+		frame.sdu_type(),
+		frame.acceptance_field(),
+	)?;
+	write_data(f, &frame.data())
+}
+
+fn write_error_frame(f: &mut std::fmt::Formatter<'_>, frame: &CanErrorFrame) -> std::fmt::Result {
+	write!(f, "{:08X}#", CAN_ERR_FLAG | frame.error_class().bits())?;
+	write_data(f, &frame.data())
+}
+
+fn write_id(f: &mut std::fmt::Formatter<'_>, id: CanId) -> std::fmt::Result {
+	match id {
+		CanId::Standard(id) => write!(f, "{:03X}", id.as_u16()),
+		CanId::Extended(id) => write!(f, "{:08X}", id.as_u32()),
+	}
+}
+
+fn write_data(f: &mut std::fmt::Formatter<'_>, data: &[u8]) -> std::fmt::Result {
+	for byte in data {
+		write!(f, "{byte:02X}")?;
+	}
+	Ok(())
+}
+
+/// Parse a filter in the format used by `candump`: `<id>:<mask>` or `<id>~<mask>` for an inverted filter.
+///
+/// An ID with 8 hexadecimal digits is an extended ID.
+pub(crate) fn parse_filter(input: &str) -> Result<CanFilter, ParseFilterError> {
+	let filter_error = |message| ParseFilterError { message };
+	let (id, mask, inverted) = if let Some((id, mask)) = input.split_once(':') {
+		(id, mask, false)
+	} else if let Some((id, mask)) = input.split_once('~') {
+		(id, mask, true)
+	} else if input.starts_with('#') {
+		return Err(filter_error("error masks are not CAN filters, parse them as `CanErrorMask` instead"));
+	} else {
+		return Err(filter_error("missing `:` or `~` between the ID and the mask"));
+	};
+
+	let mut can_id = match id.len() {
+		8 => parse_hex(id)
+			.filter(|&id| id <= CAN_EFF_MASK)
+			.map(|id| id | CAN_EFF_FLAG)
+			.ok_or(filter_error("invalid extended CAN ID"))?,
+		_ => parse_hex(id)
+			.filter(|&id| id <= u32::from(crate::MAX_STANDARD_ID))
+			.ok_or(filter_error("invalid standard CAN ID"))?,
+	};
+	if inverted {
+		can_id |= CAN_INV_FILTER;
+	}
+	// The error flag has no meaning in the mask of a filter.
+	let can_mask = parse_hex(mask).ok_or(filter_error("invalid mask"))? & !CAN_ERR_FLAG;
+	Ok(CanFilter {
+		filter: crate::sys::CanFilter::from_raw_parts(can_id, can_mask),
+	})
+}
+
+/// Write a filter in the format used by `candump`.
+pub(crate) fn write_filter(f: &mut std::fmt::Formatter<'_>, filter: &CanFilter) -> std::fmt::Result {
+	let (can_id, can_mask) = filter.filter.raw_parts();
+	let separator = match can_id & CAN_INV_FILTER {
+		0 => ':',
+		_ => '~',
+	};
+	if can_id & CAN_EFF_FLAG != 0 {
+		write!(f, "{:08X}{separator}{can_mask:08X}", can_id & CAN_EFF_MASK)
+	} else {
+		write!(f, "{:03X}{separator}{can_mask:03X}", can_id & CAN_EFF_MASK)
+	}
+}
+
+/// Parse an error mask in the format used by `candump`: `#<mask>`.
+pub(crate) fn parse_error_mask(input: &str) -> Result<CanErrorMask, ParseFilterError> {
+	input.strip_prefix('#')
+		.and_then(parse_hex)
+		.map(CanErrorMask::from_bits)
+		.ok_or(ParseFilterError { message: "invalid error mask, expected `#` followed by a hexadecimal mask" })
+}
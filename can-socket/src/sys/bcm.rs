@@ -0,0 +1,417 @@
+use filedesc::FileDesc;
+use std::ffi::c_long;
+use std::mem::MaybeUninit;
+use std::time::Duration;
+
+use crate::CanId;
+use crate::bcm::{BcmEvent, RxSetup, TxSetup};
+use super::linux::{can_frame, check_int, check_isize, parse_raw_id, raw_id};
+use super::CanInterface;
+
+const TX_SETUP: u32 = 1;
+const TX_DELETE: u32 = 2;
+const TX_SEND: u32 = 4;
+const RX_SETUP: u32 = 5;
+const RX_DELETE: u32 = 6;
+const TX_EXPIRED: u32 = 9;
+const RX_TIMEOUT: u32 = 11;
+const RX_CHANGED: u32 = 12;
+
+const SETTIMER: u32 = 0x0001;
+const STARTTIMER: u32 = 0x0002;
+const TX_COUNTEVT: u32 = 0x0004;
+const TX_ANNOUNCE: u32 = 0x0008;
+const TX_CP_CAN_ID: u32 = 0x0010;
+const RX_FILTER_ID: u32 = 0x0020;
+const RX_CHECK_DLC: u32 = 0x0040;
+const RX_NO_AUTOTIMER: u32 = 0x0080;
+const RX_ANNOUNCE_RESUME: u32 = 0x0100;
+
+/// The maximum number of frames in a single BCM message.
+const MAX_NFRAMES: usize = 256;
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+#[allow(non_camel_case_types)]
+struct bcm_timeval {
+	tv_sec: c_long,
+	tv_usec: c_long,
+}
+
+/// The header of a BCM message.
+///
+/// The frames of the message follow the header, aligned to 8 bytes.
+#[repr(C, align(8))]
+#[derive(Copy, Clone, Default)]
+#[allow(non_camel_case_types)]
+struct bcm_msg_head {
+	opcode: u32,
+	flags: u32,
+	count: u32,
+	ival1: bcm_timeval,
+	ival2: bcm_timeval,
+	can_id: u32,
+	nframes: u32,
+}
+
+/// Buffer large enough to receive any message from a BCM socket.
+#[repr(C)]
+struct ReceiveBuffer {
+	head: bcm_msg_head,
+	frames: [can_frame; MAX_NFRAMES],
+}
+
+pub(crate) struct BcmSocket {
+	fd: FileDesc,
+}
+
+impl BcmSocket {
+	pub fn new(non_blocking: bool) -> std::io::Result<Self> {
+		let flags = match non_blocking {
+			true => libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+			false => libc::SOCK_CLOEXEC,
+		};
+		unsafe {
+			let fd = check_int(libc::socket(libc::PF_CAN, libc::SOCK_DGRAM | flags, libc::CAN_BCM))?;
+			Ok(Self {
+				fd: FileDesc::from_raw_fd(fd),
+			})
+		}
+	}
+
+	pub fn set_nonblocking(&self, non_blocking: bool) -> std::io::Result<()> {
+		unsafe {
+			let flags = check_int(libc::fcntl(self.fd.as_raw_fd(), libc::F_GETFL))?;
+			let flags = match non_blocking {
+				true => flags | libc::O_NONBLOCK,
+				false => flags & !libc::O_NONBLOCK,
+			};
+			check_int(libc::fcntl(self.fd.as_raw_fd(), libc::F_SETFL, flags))?;
+		}
+		Ok(())
+	}
+
+	pub fn connect(&self, interface: &CanInterface) -> std::io::Result<()> {
+		unsafe {
+			let mut addr: libc::sockaddr_can = std::mem::zeroed();
+			addr.can_family = libc::AF_CAN as _;
+			addr.can_ifindex = interface.index() as _;
+			check_int(libc::connect(
+				self.fd.as_raw_fd(),
+				&addr as *const libc::sockaddr_can as *const libc::sockaddr,
+				std::mem::size_of_val(&addr) as _,
+			))?;
+		}
+		Ok(())
+	}
+
+	pub fn tx_setup(&self, setup: &TxSetup) -> std::io::Result<()> {
+		self.send_message(&encode_tx_setup(setup))
+	}
+
+	pub fn tx_delete(&self, id: CanId) -> std::io::Result<()> {
+		let head = bcm_msg_head {
+			opcode: TX_DELETE,
+			can_id: raw_id(id),
+			..Default::default()
+		};
+		self.send_message(&encode_message(&head, &[]))
+	}
+
+	pub fn tx_send(&self, frame: &crate::CanFrame) -> std::io::Result<()> {
+		let head = bcm_msg_head {
+			opcode: TX_SEND,
+			can_id: frame.inner.inner.can_id,
+			nframes: 1,
+			..Default::default()
+		};
+		self.send_message(&encode_message(&head, &[frame.inner.inner]))
+	}
+
+	pub fn rx_setup(&self, setup: &RxSetup) -> std::io::Result<()> {
+		self.send_message(&encode_rx_setup(setup))
+	}
+
+	pub fn rx_delete(&self, id: CanId) -> std::io::Result<()> {
+		let head = bcm_msg_head {
+			opcode: RX_DELETE,
+			can_id: raw_id(id),
+			..Default::default()
+		};
+		self.send_message(&encode_message(&head, &[]))
+	}
+
+	fn send_message(&self, message: &[u8]) -> std::io::Result<()> {
+		unsafe {
+			let written = check_isize(libc::send(self.fd.as_raw_fd(), message.as_ptr().cast(), message.len(), 0))?;
+			if written as usize != message.len() {
+				return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "failed to send complete BCM message"));
+			}
+		}
+		Ok(())
+	}
+
+	pub fn recv(&self) -> std::io::Result<BcmEvent> {
+		let mut buffer = MaybeUninit::<ReceiveBuffer>::zeroed();
+		unsafe {
+			let len = check_isize(libc::recv(
+				self.fd.as_raw_fd(),
+				buffer.as_mut_ptr().cast(),
+				std::mem::size_of::<ReceiveBuffer>(),
+				0,
+			))?;
+			let buffer = buffer.assume_init_ref();
+			parse_event(&buffer.head, &buffer.frames, len as usize)
+		}
+	}
+}
+
+fn timeval(duration: Duration) -> bcm_timeval {
+	bcm_timeval {
+		tv_sec: duration.as_secs().try_into().unwrap_or(c_long::MAX),
+		tv_usec: duration.subsec_micros() as c_long,
+	}
+}
+
+fn encode_tx_setup(setup: &TxSetup) -> Vec<u8> {
+	let mut head = bcm_msg_head {
+		opcode: TX_SETUP,
+		flags: TX_CP_CAN_ID,
+		can_id: raw_id(setup.id),
+		nframes: setup.frames.len().try_into().unwrap_or(u32::MAX),
+		..Default::default()
+	};
+	if let Some((count, interval)) = setup.initial {
+		head.flags |= SETTIMER | STARTTIMER;
+		head.count = count;
+		head.ival1 = timeval(interval);
+	}
+	if let Some(interval) = setup.interval {
+		head.flags |= SETTIMER | STARTTIMER;
+		head.ival2 = timeval(interval);
+	}
+	if setup.announce {
+		head.flags |= TX_ANNOUNCE;
+	}
+	if setup.count_event {
+		head.flags |= TX_COUNTEVT;
+	}
+	let frames: Vec<can_frame> = setup.frames.iter().map(|frame| frame.inner.inner).collect();
+	encode_message(&head, &frames)
+}
+
+fn encode_rx_setup(setup: &RxSetup) -> Vec<u8> {
+	let mut head = bcm_msg_head {
+		opcode: RX_SETUP,
+		can_id: raw_id(setup.id),
+		..Default::default()
+	};
+	let mut frames = Vec::new();
+	match &setup.mask {
+		None => head.flags |= RX_FILTER_ID,
+		Some(mask) => {
+			let mut frame: can_frame = unsafe { std::mem::zeroed() };
+			frame.can_id = head.can_id;
+			frame.can_dlc = 8;
+			frame.data = *mask;
+			frames.push(frame);
+			head.nframes = 1;
+		},
+	}
+	if setup.timeout.is_some() || setup.throttle.is_some() {
+		head.flags |= SETTIMER | STARTTIMER;
+		head.ival1 = timeval(setup.timeout.unwrap_or_default());
+		head.ival2 = timeval(setup.throttle.unwrap_or_default());
+	}
+	if setup.check_dlc {
+		head.flags |= RX_CHECK_DLC;
+	}
+	if !setup.auto_timer {
+		head.flags |= RX_NO_AUTOTIMER;
+	}
+	if setup.announce_resume {
+		head.flags |= RX_ANNOUNCE_RESUME;
+	}
+	encode_message(&head, &frames)
+}
+
+fn encode_message(head: &bcm_msg_head, frames: &[can_frame]) -> Vec<u8> {
+	// Copy the fields one by one into a zeroed header, so the padding bytes are initialized.
+	let mut header = MaybeUninit::<bcm_msg_head>::zeroed();
+	let header = unsafe {
+		let ptr = header.as_mut_ptr();
+		std::ptr::addr_of_mut!((*ptr).opcode).write(head.opcode);
+		std::ptr::addr_of_mut!((*ptr).flags).write(head.flags);
+		std::ptr::addr_of_mut!((*ptr).count).write(head.count);
+		std::ptr::addr_of_mut!((*ptr).ival1).write(head.ival1);
+		std::ptr::addr_of_mut!((*ptr).ival2).write(head.ival2);
+		std::ptr::addr_of_mut!((*ptr).can_id).write(head.can_id);
+		std::ptr::addr_of_mut!((*ptr).nframes).write(head.nframes);
+		std::slice::from_raw_parts(header.as_ptr().cast::<u8>(), std::mem::size_of::<bcm_msg_head>())
+	};
+
+	// SAFETY: `can_frame` has no padding bytes.
+	let frames = unsafe {
+		std::slice::from_raw_parts(frames.as_ptr().cast::<u8>(), std::mem::size_of_val(frames))
+	};
+
+	let mut message = Vec::with_capacity(header.len() + frames.len());
+	message.extend_from_slice(header);
+	message.extend_from_slice(frames);
+	message
+}
+
+fn parse_event(head: &bcm_msg_head, frames: &[can_frame], len: usize) -> std::io::Result<BcmEvent> {
+	let head_size = std::mem::size_of::<bcm_msg_head>();
+	if len < head_size {
+		return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "received BCM message is too short"));
+	}
+	let frame_count = (len - head_size) / std::mem::size_of::<can_frame>();
+	match head.opcode {
+		RX_CHANGED => {
+			if frame_count < 1 || head.nframes < 1 {
+				return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "received BCM RX_CHANGED message without frame"));
+			}
+			Ok(BcmEvent::RxChanged(crate::CanFrame {
+				inner: super::CanFrame { inner: frames[0] },
+			}))
+		},
+		RX_TIMEOUT => Ok(BcmEvent::RxTimeout(parse_raw_id(head.can_id))),
+		TX_EXPIRED => Ok(BcmEvent::TxExpired(parse_raw_id(head.can_id))),
+		opcode => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("received BCM message with unexpected opcode: {opcode}"))),
+	}
+}
+
+impl std::os::fd::AsFd for BcmSocket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.fd.as_fd()
+	}
+}
+
+impl From<BcmSocket> for std::os::fd::OwnedFd {
+	fn from(value: BcmSocket) -> Self {
+		value.fd.into()
+	}
+}
+
+impl From<std::os::fd::OwnedFd> for BcmSocket {
+	fn from(value: std::os::fd::OwnedFd) -> Self {
+		Self {
+			fd: FileDesc::from(value),
+		}
+	}
+}
+
+impl std::os::fd::AsRawFd for BcmSocket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.fd.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for BcmSocket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.fd.into_raw_fd()
+	}
+}
+
+impl std::os::fd::FromRawFd for BcmSocket {
+	unsafe fn from_raw_fd(fd: std::os::fd::RawFd) -> Self {
+		Self {
+			fd: FileDesc::from_raw_fd(fd)
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::{CanFrame, StandardId};
+
+	fn parse_head(message: &[u8]) -> bcm_msg_head {
+		assert!(message.len() >= std::mem::size_of::<bcm_msg_head>());
+		unsafe { message.as_ptr().cast::<bcm_msg_head>().read_unaligned() }
+	}
+
+	#[test]
+	fn header_layout() {
+		assert!(std::mem::align_of::<bcm_msg_head>() == 8);
+		#[cfg(target_pointer_width = "64")]
+		assert!(std::mem::size_of::<bcm_msg_head>() == 56);
+		assert!(std::mem::size_of::<can_frame>() == 16);
+	}
+
+	#[test]
+	fn encode_cyclic_tx_setup() {
+		let id = StandardId::new(0x80).unwrap();
+		let setup = TxSetup::new(CanFrame::new(id, []))
+			.with_interval(Duration::from_millis(10))
+			.with_initial(3, Duration::from_micros(1_500_000));
+		let message = encode_tx_setup(&setup);
+		assert!(message.len() == std::mem::size_of::<bcm_msg_head>() + 16);
+
+		let head = parse_head(&message);
+		assert!(head.opcode == TX_SETUP);
+		assert!(head.flags == SETTIMER | STARTTIMER | TX_CP_CAN_ID);
+		assert!(head.count == 3);
+		assert!(head.ival1.tv_sec == 1);
+		assert!(head.ival1.tv_usec == 500_000);
+		assert!(head.ival2.tv_sec == 0);
+		assert!(head.ival2.tv_usec == 10_000);
+		assert!(head.can_id == 0x80);
+		assert!(head.nframes == 1);
+	}
+
+	#[test]
+	fn encode_rx_setup_with_mask() {
+		let id = crate::ExtendedId::new(0x1234).unwrap();
+		let setup = RxSetup::new(id)
+			.with_mask([0xFF, 0, 0, 0, 0, 0, 0, 0x0F])
+			.with_timeout(Duration::from_secs(2));
+		let message = encode_rx_setup(&setup);
+		assert!(message.len() == std::mem::size_of::<bcm_msg_head>() + 16);
+
+		let head = parse_head(&message);
+		assert!(head.opcode == RX_SETUP);
+		assert!(head.flags == SETTIMER | STARTTIMER);
+		assert!(head.can_id == 0x1234 | libc::CAN_EFF_FLAG);
+		assert!(head.ival1.tv_sec == 2);
+		assert!(head.nframes == 1);
+		assert!(message[message.len() - 8..] == [0xFF, 0, 0, 0, 0, 0, 0, 0x0F]);
+	}
+
+	#[test]
+	fn encode_rx_setup_id_filter() {
+		let setup = RxSetup::new(StandardId::new(0x123).unwrap())
+			.with_auto_timer(false);
+		let message = encode_rx_setup(&setup);
+		assert!(message.len() == std::mem::size_of::<bcm_msg_head>());
+
+		let head = parse_head(&message);
+		assert!(head.flags == RX_FILTER_ID | RX_NO_AUTOTIMER);
+		assert!(head.nframes == 0);
+	}
+
+	#[test]
+	fn parse_events() {
+		let mut frames: [can_frame; 1] = unsafe { std::mem::zeroed() };
+		frames[0].can_id = 0x10;
+		frames[0].can_dlc = 2;
+		frames[0].data[..2].copy_from_slice(&[1, 2]);
+		let head_size = std::mem::size_of::<bcm_msg_head>();
+
+		let head = bcm_msg_head { opcode: RX_CHANGED, can_id: 0x10, nframes: 1, ..Default::default() };
+		let_assert!(Ok(BcmEvent::RxChanged(frame)) = parse_event(&head, &frames, head_size + 16));
+		assert!(frame.id() == StandardId::new(0x10).unwrap());
+		let_assert!(Some(data) = frame.data());
+		assert!(data == [1, 2]);
+
+		let head = bcm_msg_head { opcode: RX_TIMEOUT, can_id: 0x10, ..Default::default() };
+		let_assert!(Ok(BcmEvent::RxTimeout(id)) = parse_event(&head, &frames, head_size));
+		assert!(id == StandardId::new(0x10).unwrap());
+
+		let head = bcm_msg_head { opcode: RX_CHANGED, can_id: 0x10, nframes: 1, ..Default::default() };
+		assert!(let Err(_) = parse_event(&head, &frames, head_size));
+		assert!(let Err(_) = parse_event(&head, &frames, 8));
+	}
+}
@@ -0,0 +1,246 @@
+use filedesc::FileDesc;
+use std::ffi::c_int;
+use std::sync::{Mutex, PoisonError};
+
+use crate::isotp::{FlowControlOptions, IsoTpOptions, LinkLayerOptions};
+use crate::CanId;
+use super::linux::{check_int, check_isize, get_socket_option, raw_id, set_socket_option};
+use super::CanInterface;
+
+const SOL_CAN_ISOTP: c_int = libc::SOL_CAN_BASE + libc::CAN_ISOTP;
+const CAN_ISOTP_OPTS: c_int = 1;
+const CAN_ISOTP_RECV_FC: c_int = 2;
+const CAN_ISOTP_TX_STMIN: c_int = 3;
+const CAN_ISOTP_RX_STMIN: c_int = 4;
+const CAN_ISOTP_LL_OPTS: c_int = 5;
+
+/// A CAN socket address with the transport protocol address fields.
+///
+/// Has the same layout as `struct sockaddr_can` with the `tp` member of the address union.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+#[allow(non_camel_case_types)]
+struct sockaddr_can_tp {
+	can_family: libc::sa_family_t,
+	can_ifindex: c_int,
+	rx_id: u32,
+	tx_id: u32,
+	_padding: [u8; 8],
+}
+
+pub(crate) struct IsoTpSocket {
+	fd: FileDesc,
+
+	/// Lock to make peeking at the length of a message and receiving it atomic when the socket is shared between threads.
+	recv_lock: Mutex<()>,
+}
+
+impl IsoTpSocket {
+	pub fn new(non_blocking: bool) -> std::io::Result<Self> {
+		let flags = match non_blocking {
+			true => libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+			false => libc::SOCK_CLOEXEC,
+		};
+		unsafe {
+			let fd = check_int(libc::socket(libc::PF_CAN, libc::SOCK_DGRAM | flags, libc::CAN_ISOTP))?;
+			Ok(Self {
+				fd: FileDesc::from_raw_fd(fd),
+				recv_lock: Mutex::new(()),
+			})
+		}
+	}
+
+	pub fn set_nonblocking(&self, non_blocking: bool) -> std::io::Result<()> {
+		unsafe {
+			let flags = check_int(libc::fcntl(self.fd.as_raw_fd(), libc::F_GETFL))?;
+			let flags = match non_blocking {
+				true => flags | libc::O_NONBLOCK,
+				false => flags & !libc::O_NONBLOCK,
+			};
+			check_int(libc::fcntl(self.fd.as_raw_fd(), libc::F_SETFL, flags))?;
+		}
+		Ok(())
+	}
+
+	pub fn bind(&self, interface: &CanInterface, rx_id: CanId, tx_id: CanId) -> std::io::Result<()> {
+		let addr = sockaddr_can_tp {
+			can_family: libc::AF_CAN as _,
+			can_ifindex: interface.index() as _,
+			rx_id: raw_id(rx_id),
+			tx_id: raw_id(tx_id),
+			_padding: [0; 8],
+		};
+		unsafe {
+			check_int(libc::bind(
+				self.fd.as_raw_fd(),
+				&addr as *const sockaddr_can_tp as *const libc::sockaddr,
+				std::mem::size_of_val(&addr) as _,
+			))?;
+		}
+		Ok(())
+	}
+
+	pub fn local_addr(&self) -> std::io::Result<CanInterface> {
+		let mut addr = sockaddr_can_tp::default();
+		let mut addr_len: libc::socklen_t = std::mem::size_of_val(&addr) as _;
+		unsafe {
+			check_int(libc::getsockname(
+				self.fd.as_raw_fd(),
+				&mut addr as *mut sockaddr_can_tp as *mut libc::sockaddr,
+				&mut addr_len,
+			))?;
+		}
+		Ok(CanInterface::from_index(addr.can_ifindex as u32))
+	}
+
+	pub fn send(&self, data: &[u8]) -> std::io::Result<()> {
+		unsafe {
+			let written = check_isize(libc::send(self.fd.as_raw_fd(), data.as_ptr().cast(), data.len(), 0))?;
+			if written as usize != data.len() {
+				return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "failed to send complete message"));
+			}
+		}
+		Ok(())
+	}
+
+	pub fn recv(&self) -> std::io::Result<Vec<u8>> {
+		// Without the lock, another thread could take the message we peeked at,
+		// and we would receive a different message that may not fit in the buffer.
+		let _guard = self.recv_lock.lock().unwrap_or_else(PoisonError::into_inner);
+		unsafe {
+			// Peek at the next message to get the length.
+			let mut byte = 0u8;
+			let len = check_isize(libc::recv(
+				self.fd.as_raw_fd(),
+				(&mut byte as *mut u8).cast(),
+				0,
+				libc::MSG_PEEK | libc::MSG_TRUNC,
+			))?;
+
+			let mut buffer = vec![0u8; len as usize];
+			let read = check_isize(libc::recv(
+				self.fd.as_raw_fd(),
+				buffer.as_mut_ptr().cast(),
+				buffer.len(),
+				libc::MSG_TRUNC,
+			))?;
+			if read as usize != buffer.len() {
+				// Another process using the same socket took the message we peeked at.
+				return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "message was truncated"));
+			}
+			Ok(buffer)
+		}
+	}
+
+	pub fn get_options(&self) -> std::io::Result<IsoTpOptions> {
+		unsafe {
+			get_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_OPTS)
+		}
+	}
+
+	pub fn set_options(&self, options: &IsoTpOptions) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_OPTS, options)
+		}
+	}
+
+	pub fn get_flow_control_options(&self) -> std::io::Result<FlowControlOptions> {
+		unsafe {
+			get_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_RECV_FC)
+		}
+	}
+
+	pub fn set_flow_control_options(&self, options: &FlowControlOptions) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_RECV_FC, options)
+		}
+	}
+
+	pub fn get_link_layer_options(&self) -> std::io::Result<LinkLayerOptions> {
+		unsafe {
+			get_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_LL_OPTS)
+		}
+	}
+
+	pub fn set_link_layer_options(&self, options: &LinkLayerOptions) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_LL_OPTS, options)
+		}
+	}
+
+	pub fn get_tx_stmin(&self) -> std::io::Result<std::time::Duration> {
+		let nanos: u32 = unsafe {
+			get_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_TX_STMIN)?
+		};
+		Ok(std::time::Duration::from_nanos(nanos.into()))
+	}
+
+	pub fn set_tx_stmin(&self, stmin: std::time::Duration) -> std::io::Result<()> {
+		let nanos: u32 = duration_to_nanos(stmin)?;
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_TX_STMIN, &nanos)
+		}
+	}
+
+	pub fn get_rx_stmin(&self) -> std::io::Result<std::time::Duration> {
+		let nanos: u32 = unsafe {
+			get_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_RX_STMIN)?
+		};
+		Ok(std::time::Duration::from_nanos(nanos.into()))
+	}
+
+	pub fn set_rx_stmin(&self, stmin: std::time::Duration) -> std::io::Result<()> {
+		let nanos: u32 = duration_to_nanos(stmin)?;
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_ISOTP, CAN_ISOTP_RX_STMIN, &nanos)
+		}
+	}
+}
+
+fn duration_to_nanos(duration: std::time::Duration) -> std::io::Result<u32> {
+	duration.as_nanos()
+		.try_into()
+		.map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "separation time too large"))
+}
+
+impl std::os::fd::AsFd for IsoTpSocket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.fd.as_fd()
+	}
+}
+
+impl From<IsoTpSocket> for std::os::fd::OwnedFd {
+	fn from(value: IsoTpSocket) -> Self {
+		value.fd.into()
+	}
+}
+
+impl From<std::os::fd::OwnedFd> for IsoTpSocket {
+	fn from(value: std::os::fd::OwnedFd) -> Self {
+		Self {
+			fd: FileDesc::from(value),
+			recv_lock: Mutex::new(()),
+		}
+	}
+}
+
+impl std::os::fd::AsRawFd for IsoTpSocket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.fd.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for IsoTpSocket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.fd.into_raw_fd()
+	}
+}
+
+impl std::os::fd::FromRawFd for IsoTpSocket {
+	unsafe fn from_raw_fd(fd: std::os::fd::RawFd) -> Self {
+		Self {
+			fd: FileDesc::from_raw_fd(fd),
+			recv_lock: Mutex::new(()),
+		}
+	}
+}
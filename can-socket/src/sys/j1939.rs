@@ -0,0 +1,302 @@
+use filedesc::FileDesc;
+use std::ffi::c_int;
+use std::mem::MaybeUninit;
+
+use crate::j1939::{J1939Address, J1939Message, J1939MessageInfo, MAX_TP_MESSAGE_SIZE};
+use super::linux::{check_int, check_isize, get_socket_option, set_socket_option};
+use super::CanInterface;
+
+const SOL_CAN_J1939: c_int = libc::SOL_CAN_BASE + libc::CAN_J1939;
+const SO_J1939_PROMISC: c_int = 2;
+const SO_J1939_SEND_PRIO: c_int = 3;
+const SCM_J1939_DEST_ADDR: c_int = 1;
+const SCM_J1939_DEST_NAME: c_int = 2;
+const SCM_J1939_PRIO: c_int = 3;
+
+/// A CAN socket address with the J1939 address fields.
+///
+/// Has the same layout as `struct sockaddr_can` with the `j1939` member of the address union.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+#[allow(non_camel_case_types)]
+struct sockaddr_can_j1939 {
+	can_family: libc::sa_family_t,
+	can_ifindex: c_int,
+	name: u64,
+	pgn: u32,
+	addr: u8,
+	_padding: [u8; 3],
+}
+
+impl sockaddr_can_j1939 {
+	fn new(interface_index: u32, address: &J1939Address) -> Self {
+		Self {
+			can_family: libc::AF_CAN as _,
+			can_ifindex: interface_index as _,
+			name: address.name,
+			pgn: address.pgn,
+			addr: address.address,
+			_padding: [0; 3],
+		}
+	}
+
+	fn to_address(self) -> J1939Address {
+		J1939Address::new(self.name, self.pgn, self.addr)
+	}
+}
+
+/// Buffer for the ancillary data of received messages.
+#[repr(C, align(8))]
+struct ControlBuffer {
+	data: [u8; 128],
+}
+
+pub(crate) struct J1939Socket {
+	fd: FileDesc,
+}
+
+impl J1939Socket {
+	pub fn new(non_blocking: bool) -> std::io::Result<Self> {
+		let flags = match non_blocking {
+			true => libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+			false => libc::SOCK_CLOEXEC,
+		};
+		unsafe {
+			let fd = check_int(libc::socket(libc::PF_CAN, libc::SOCK_DGRAM | flags, libc::CAN_J1939))?;
+			Ok(Self {
+				fd: FileDesc::from_raw_fd(fd),
+			})
+		}
+	}
+
+	pub fn set_nonblocking(&self, non_blocking: bool) -> std::io::Result<()> {
+		unsafe {
+			let flags = check_int(libc::fcntl(self.fd.as_raw_fd(), libc::F_GETFL))?;
+			let flags = match non_blocking {
+				true => flags | libc::O_NONBLOCK,
+				false => flags & !libc::O_NONBLOCK,
+			};
+			check_int(libc::fcntl(self.fd.as_raw_fd(), libc::F_SETFL, flags))?;
+		}
+		Ok(())
+	}
+
+	pub fn bind(&self, interface: &CanInterface, address: &J1939Address) -> std::io::Result<()> {
+		let addr = sockaddr_can_j1939::new(interface.index(), address);
+		unsafe {
+			check_int(libc::bind(
+				self.fd.as_raw_fd(),
+				&addr as *const sockaddr_can_j1939 as *const libc::sockaddr,
+				std::mem::size_of_val(&addr) as _,
+			))?;
+		}
+		Ok(())
+	}
+
+	pub fn connect(&self, address: &J1939Address) -> std::io::Result<()> {
+		let addr = sockaddr_can_j1939::new(0, address);
+		unsafe {
+			check_int(libc::connect(
+				self.fd.as_raw_fd(),
+				&addr as *const sockaddr_can_j1939 as *const libc::sockaddr,
+				std::mem::size_of_val(&addr) as _,
+			))?;
+		}
+		Ok(())
+	}
+
+	pub fn local_addr(&self) -> std::io::Result<(CanInterface, J1939Address)> {
+		let mut addr = sockaddr_can_j1939::default();
+		let mut addr_len: libc::socklen_t = std::mem::size_of_val(&addr) as _;
+		unsafe {
+			check_int(libc::getsockname(
+				self.fd.as_raw_fd(),
+				&mut addr as *mut sockaddr_can_j1939 as *mut libc::sockaddr,
+				&mut addr_len,
+			))?;
+		}
+		Ok((CanInterface::from_index(addr.can_ifindex as u32), addr.to_address()))
+	}
+
+	pub fn peer_addr(&self) -> std::io::Result<J1939Address> {
+		let mut addr = sockaddr_can_j1939::default();
+		let mut addr_len: libc::socklen_t = std::mem::size_of_val(&addr) as _;
+		unsafe {
+			check_int(libc::getpeername(
+				self.fd.as_raw_fd(),
+				&mut addr as *mut sockaddr_can_j1939 as *mut libc::sockaddr,
+				&mut addr_len,
+			))?;
+		}
+		Ok(addr.to_address())
+	}
+
+	pub fn send(&self, data: &[u8]) -> std::io::Result<()> {
+		unsafe {
+			let written = check_isize(libc::send(self.fd.as_raw_fd(), data.as_ptr().cast(), data.len(), 0))?;
+			check_complete(written, data)
+		}
+	}
+
+	pub fn send_to(&self, data: &[u8], address: &J1939Address) -> std::io::Result<()> {
+		let addr = sockaddr_can_j1939::new(0, address);
+		unsafe {
+			let written = check_isize(libc::sendto(
+				self.fd.as_raw_fd(),
+				data.as_ptr().cast(),
+				data.len(),
+				0,
+				&addr as *const sockaddr_can_j1939 as *const libc::sockaddr,
+				std::mem::size_of_val(&addr) as _,
+			))?;
+			check_complete(written, data)
+		}
+	}
+
+	pub fn recv(&self) -> std::io::Result<J1939Message> {
+		let mut data = vec![0u8; MAX_TP_MESSAGE_SIZE];
+		let info = self.recv_into(&mut data)?;
+		data.truncate(info.len);
+		Ok(J1939Message {
+			data,
+			source: info.source,
+			destination_address: info.destination_address,
+			destination_name: info.destination_name,
+			priority: info.priority,
+		})
+	}
+
+	pub fn recv_into(&self, buffer: &mut [u8]) -> std::io::Result<J1939MessageInfo> {
+		let mut addr = sockaddr_can_j1939::default();
+		let mut control = MaybeUninit::<ControlBuffer>::zeroed();
+
+		unsafe {
+			let mut iov = libc::iovec {
+				iov_base: buffer.as_mut_ptr().cast(),
+				iov_len: buffer.len(),
+			};
+			let mut header: libc::msghdr = std::mem::zeroed();
+			header.msg_name = (&mut addr as *mut sockaddr_can_j1939).cast();
+			header.msg_namelen = std::mem::size_of_val(&addr) as _;
+			header.msg_iov = &mut iov;
+			header.msg_iovlen = 1;
+			header.msg_control = control.as_mut_ptr().cast();
+			header.msg_controllen = std::mem::size_of::<ControlBuffer>() as _;
+
+			let len = check_isize(libc::recvmsg(self.fd.as_raw_fd(), &mut header, 0))?;
+			if header.msg_flags & libc::MSG_TRUNC != 0 {
+				return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "message too large for receive buffer"));
+			}
+
+			let mut info = J1939MessageInfo {
+				len: len as usize,
+				source: addr.to_address(),
+				destination_address: None,
+				destination_name: None,
+				priority: None,
+			};
+
+			let mut cmsg = libc::CMSG_FIRSTHDR(&header);
+			while let Some(current) = cmsg.as_ref() {
+				if current.cmsg_level == SOL_CAN_J1939 {
+					let data = libc::CMSG_DATA(current);
+					match current.cmsg_type {
+						SCM_J1939_DEST_ADDR => info.destination_address = Some(data.read()),
+						SCM_J1939_DEST_NAME => info.destination_name = Some(data.cast::<u64>().read_unaligned()),
+						SCM_J1939_PRIO => info.priority = Some(data.read()),
+						_ => (),
+					}
+				}
+				cmsg = libc::CMSG_NXTHDR(&header, cmsg);
+			}
+
+			Ok(info)
+		}
+	}
+
+	pub fn get_promiscuous(&self) -> std::io::Result<bool> {
+		let enabled: c_int = unsafe {
+			get_socket_option(&self.fd, SOL_CAN_J1939, SO_J1939_PROMISC)?
+		};
+		Ok(enabled != 0)
+	}
+
+	pub fn set_promiscuous(&self, enable: bool) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_J1939, SO_J1939_PROMISC, &c_int::from(enable))
+		}
+	}
+
+	pub fn get_send_priority(&self) -> std::io::Result<u8> {
+		let priority: c_int = unsafe {
+			get_socket_option(&self.fd, SOL_CAN_J1939, SO_J1939_SEND_PRIO)?
+		};
+		Ok(priority as u8)
+	}
+
+	pub fn set_send_priority(&self, priority: u8) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(&self.fd, SOL_CAN_J1939, SO_J1939_SEND_PRIO, &c_int::from(priority))
+		}
+	}
+
+	pub fn get_broadcast(&self) -> std::io::Result<bool> {
+		let enabled: c_int = unsafe {
+			get_socket_option(&self.fd, libc::SOL_SOCKET, libc::SO_BROADCAST)?
+		};
+		Ok(enabled != 0)
+	}
+
+	pub fn set_broadcast(&self, enable: bool) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(&self.fd, libc::SOL_SOCKET, libc::SO_BROADCAST, &c_int::from(enable))
+		}
+	}
+}
+
+fn check_complete(written: isize, data: &[u8]) -> std::io::Result<()> {
+	if written as usize != data.len() {
+		return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "failed to send complete message"));
+	}
+	Ok(())
+}
+
+impl std::os::fd::AsFd for J1939Socket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.fd.as_fd()
+	}
+}
+
+impl From<J1939Socket> for std::os::fd::OwnedFd {
+	fn from(value: J1939Socket) -> Self {
+		value.fd.into()
+	}
+}
+
+impl From<std::os::fd::OwnedFd> for J1939Socket {
+	fn from(value: std::os::fd::OwnedFd) -> Self {
+		Self {
+			fd: FileDesc::from(value),
+		}
+	}
+}
+
+impl std::os::fd::AsRawFd for J1939Socket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.fd.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for J1939Socket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.fd.into_raw_fd()
+	}
+}
+
+impl std::os::fd::FromRawFd for J1939Socket {
+	unsafe fn from_raw_fd(fd: std::os::fd::RawFd) -> Self {
+		Self {
+			fd: FileDesc::from_raw_fd(fd)
+		}
+	}
+}
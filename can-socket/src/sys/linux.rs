@@ -1,13 +1,53 @@
 use filedesc::FileDesc;
-use std::ffi::{c_int, c_void, CString};
+use std::ffi::{c_int, c_uint, c_void, CString};
 use std::mem::MaybeUninit;
 
-use crate::{CanData, CanId, ExtendedId, StandardId};
+use crate::{CanData, CanFdData, CanId, CanXlData, ExtendedId, StandardId};
+use crate::error_frame::{ControllerStatus, ErrorCounters, ProtocolViolation, ProtocolViolationKind, ProtocolViolationLocation, TransceiverStatus};
+
+/// Flag to mark a CAN FD frame, set by the kernel on all received CAN FD frames.
+const CANFD_FDF: u8 = 0x04;
+
+/// Flag to mark a CAN XL frame, must always be set for CAN XL frames.
+const CANXL_XLF: u8 = 0x80;
+
+/// Simple Extended Content flag of a CAN XL frame.
+const CANXL_SEC: u8 = 0x01;
+
+/// Mask for the virtual CAN network ID in the priority field of a CAN XL frame.
+const CANXL_VCID_MASK: u32 = 0xFF << CANXL_VCID_OFFSET;
+
+/// Offset of the virtual CAN network ID in the priority field of a CAN XL frame.
+const CANXL_VCID_OFFSET: u32 = 16;
+
+/// Error class: lost arbitration, bit position in data[0].
+const CAN_ERR_LOSTARB: u32 = 0x0002;
+
+/// Error class: controller problems, details in data[1].
+const CAN_ERR_CRTL: u32 = 0x0004;
+
+/// Error class: protocol violations, type in data[2] and location in data[3].
+const CAN_ERR_PROT: u32 = 0x0008;
+
+/// Error class: transceiver status, details in data[4].
+const CAN_ERR_TRX: u32 = 0x0010;
+
+/// Error class: TX and RX error counters in data[6] and data[7].
+const CAN_ERR_CNT: u32 = 0x0200;
+
+/// Maximum number of filters that can be set on a CAN socket.
+const CAN_RAW_FILTER_MAX: usize = 512;
+
+/// Maximum number of frames sent or received by a single call to `send_batch()` or `recv_batch()`.
+const MAX_BATCH_SIZE: usize = 64;
+
+/// Size of the header of a CAN XL frame.
+const CANXL_HDR_SIZE: usize = std::mem::offset_of!(canxl_frame, data);
 
 #[repr(C)]
 #[derive(Copy, Clone)]
 #[allow(non_camel_case_types)]
-struct can_frame {
+pub(super) struct can_frame {
 	pub can_id: u32,
 	pub can_dlc: u8,
 	_pad: u8,
@@ -16,13 +56,74 @@ struct can_frame {
 	pub data: [u8; 8],
 }
 
+#[repr(C)]
+#[derive(Copy, Clone)]
+#[allow(non_camel_case_types)]
+struct canfd_frame {
+	pub can_id: u32,
+	pub len: u8,
+	pub flags: u8,
+	_res0: u8,
+	_res1: u8,
+	pub data: [u8; 64],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+#[allow(non_camel_case_types)]
+struct canxl_frame {
+	pub prio: u32,
+	pub flags: u8,
+	pub sdt: u8,
+	pub len: u16,
+	pub af: u32,
+	pub data: [u8; 2048],
+}
+
+/// Buffer large enough to receive any type of frame from a CAN socket.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+union any_frame {
+	can: can_frame,
+	fd: canfd_frame,
+	xl: canxl_frame,
+}
+
 pub(crate) struct Socket {
 	fd: FileDesc,
 }
 
 #[derive(Copy, Clone)]
 pub(crate) struct CanFrame {
-	inner: can_frame
+	pub(super) inner: can_frame
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct CanFdFrame {
+	inner: canfd_frame
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct CanXlFrame {
+	inner: canxl_frame
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct CanErrorFrame {
+	inner: can_frame,
+}
+
+pub(crate) struct RecvMetadata {
+	pub interface: CanInterface,
+	pub timestamps: crate::Timestamps,
+	pub drop_count: u32,
+}
+
+pub(crate) enum AnyFrame {
+	Can(CanFrame),
+	Fd(CanFdFrame),
+	Xl(Box<CanXlFrame>),
+	Error(CanErrorFrame),
 }
 
 #[repr(transparent)]
@@ -37,42 +138,48 @@ pub struct CanFilter {
 	filter: libc::can_filter,
 }
 
+/// Convert a CAN ID to the raw representation used by the kernel.
+pub(super) fn raw_id(id: CanId) -> u32 {
+	match id {
+		CanId::Extended(x) => x.as_u32() | libc::CAN_EFF_FLAG,
+		CanId::Standard(x) => x.as_u16().into(),
+	}
+}
+
+/// Convert a raw CAN ID from the kernel to a [`CanId`].
+///
+/// The flag bits (EFF, RTR and ERR) are masked off, so this never fails.
+/// Error frames are never parsed as a [`CanFrame`] though: they are reported as a [`CanErrorFrame`].
+pub(super) fn parse_raw_id(can_id: u32) -> CanId {
+	// SAFETY: The masks ensure the ID is within the valid range.
+	unsafe {
+		if can_id & libc::CAN_EFF_FLAG == 0 {
+			CanId::Standard(StandardId::new_unchecked((can_id & libc::CAN_SFF_MASK) as u16))
+		} else {
+			CanId::Extended(ExtendedId::new_unchecked(can_id & libc::CAN_EFF_MASK))
+		}
+	}
+}
+
 impl CanFrame {
 	pub fn new(id: impl Into<CanId>, data: &crate::CanData) -> Self {
-		let id = id.into();
-
 		let mut inner: can_frame = unsafe { std::mem::zeroed() };
-		inner.can_id = match id {
-			CanId::Extended(x) => x.as_u32() | libc::CAN_EFF_FLAG,
-			CanId::Standard(x) => x.as_u16().into(),
-		};
+		inner.can_id = raw_id(id.into());
 		inner.can_dlc = data.len() as u8;
 		inner.data[..data.len()].copy_from_slice(data);
 		Self { inner }
 	}
 
 	pub fn new_rtr(id: impl Into<CanId>) -> Self {
-		let id = id.into();
-
 		let mut inner: can_frame = unsafe { std::mem::zeroed() };
-		inner.can_id = match id {
-			CanId::Extended(x) => x.as_u32() | libc::CAN_EFF_FLAG,
-			CanId::Standard(x) => x.as_u16().into(),
-		};
-		inner.can_id |= libc::CAN_RTR_FLAG;
+		inner.can_id = raw_id(id.into()) | libc::CAN_RTR_FLAG;
 		inner.can_dlc = 0;
 		inner.len8_dlc = 0;
 		Self { inner }
 	}
 
 	pub fn id(&self) -> CanId {
-		// Unwrap should be fine: the kernel should never give us an invalid CAN ID,
-		// and the Rust constructor doesn't allow it.
-		if self.inner.can_id & libc::CAN_EFF_FLAG == 0 {
-			CanId::new_standard((self.inner.can_id & libc::CAN_SFF_MASK) as u16).unwrap()
-		} else {
-			CanId::new_extended(self.inner.can_id & libc::CAN_EFF_MASK).unwrap()
-		}
+		parse_raw_id(self.inner.can_id)
 	}
 
 	pub fn is_rtr(&self) -> bool {
@@ -119,6 +226,266 @@ impl CanFrame {
 	}
 }
 
+impl CanFdFrame {
+	pub fn new(id: impl Into<CanId>, data: &CanFdData) -> Self {
+		let mut inner: canfd_frame = unsafe { std::mem::zeroed() };
+		inner.can_id = raw_id(id.into());
+		inner.flags = CANFD_FDF;
+		inner.len = fd_padded_len(data.len() as u8);
+		inner.data[..data.len()].copy_from_slice(data);
+		Self { inner }
+	}
+
+	pub fn id(&self) -> CanId {
+		parse_raw_id(self.inner.can_id)
+	}
+
+	pub fn data(&self) -> CanFdData {
+		CanFdData {
+			data: self.inner.data,
+			len: self.inner.len,
+		}
+	}
+
+	pub fn data_length_code(&self) -> u8 {
+		fd_len_to_dlc(self.inner.len)
+	}
+
+	pub fn bit_rate_switch(&self) -> bool {
+		self.inner.flags & libc::CANFD_BRS as u8 != 0
+	}
+
+	pub fn set_bit_rate_switch(&mut self, enable: bool) {
+		set_flag(&mut self.inner.flags, libc::CANFD_BRS as u8, enable)
+	}
+
+	pub fn error_state_indicator(&self) -> bool {
+		self.inner.flags & libc::CANFD_ESI as u8 != 0
+	}
+
+	pub fn set_error_state_indicator(&mut self, enable: bool) {
+		set_flag(&mut self.inner.flags, libc::CANFD_ESI as u8, enable)
+	}
+
+	fn as_c_void_ptr(&self) -> *const c_void {
+		(self as *const Self).cast()
+	}
+}
+
+impl CanXlFrame {
+	pub fn new(priority: StandardId, data: &CanXlData) -> Self {
+		let mut inner: canxl_frame = unsafe { std::mem::zeroed() };
+		inner.prio = priority.as_u16().into();
+		inner.flags = CANXL_XLF;
+		inner.len = data.len() as u16;
+		inner.data[..data.len()].copy_from_slice(data);
+		Self { inner }
+	}
+
+	pub fn priority(&self) -> StandardId {
+		// Unwrap should be fine: the value is masked to 11 bits.
+		StandardId::new((self.inner.prio & libc::CAN_SFF_MASK) as u16).unwrap()
+	}
+
+	pub fn set_priority(&mut self, priority: StandardId) {
+		self.inner.prio = (self.inner.prio & !libc::CAN_SFF_MASK) | u32::from(priority.as_u16());
+	}
+
+	pub fn virtual_can_id(&self) -> u8 {
+		((self.inner.prio & CANXL_VCID_MASK) >> CANXL_VCID_OFFSET) as u8
+	}
+
+	pub fn set_virtual_can_id(&mut self, vcid: u8) {
+		self.inner.prio = (self.inner.prio & !CANXL_VCID_MASK) | u32::from(vcid) << CANXL_VCID_OFFSET;
+	}
+
+	pub fn sdu_type(&self) -> u8 {
+		self.inner.sdt
+	}
+
+	pub fn set_sdu_type(&mut self, sdu_type: u8) {
+		self.inner.sdt = sdu_type;
+	}
+
+	pub fn acceptance_field(&self) -> u32 {
+		self.inner.af
+	}
+
+	pub fn set_acceptance_field(&mut self, acceptance_field: u32) {
+		self.inner.af = acceptance_field;
+	}
+
+	pub fn simple_extended_content(&self) -> bool {
+		self.inner.flags & CANXL_SEC != 0
+	}
+
+	pub fn set_simple_extended_content(&mut self, enable: bool) {
+		set_flag(&mut self.inner.flags, CANXL_SEC, enable)
+	}
+
+	pub fn data(&self) -> CanXlData {
+		CanXlData {
+			data: self.inner.data,
+			len: self.inner.len,
+		}
+	}
+
+	fn as_c_void_ptr(&self) -> *const c_void {
+		(self as *const Self).cast()
+	}
+
+	/// The number of bytes that are sent over the socket for this frame.
+	fn wire_size(&self) -> usize {
+		CANXL_HDR_SIZE + usize::from(self.inner.len)
+	}
+}
+
+impl CanErrorFrame {
+	pub fn from_raw(class: u32, data: [u8; 8]) -> Self {
+		let mut inner: can_frame = unsafe { std::mem::zeroed() };
+		inner.can_id = libc::CAN_ERR_FLAG | (class & libc::CAN_ERR_MASK);
+		inner.can_dlc = 8;
+		inner.data = data;
+		Self { inner }
+	}
+
+	pub fn class(&self) -> u32 {
+		self.inner.can_id & libc::CAN_ERR_MASK
+	}
+
+	pub fn data(&self) -> [u8; 8] {
+		self.inner.data
+	}
+
+	fn has_class(&self, class: u32) -> bool {
+		self.class() & class != 0
+	}
+
+	pub fn lost_arbitration_bit(&self) -> Option<u8> {
+		self.has_class(CAN_ERR_LOSTARB)
+			.then_some(self.inner.data[0])
+	}
+
+	pub fn controller_status(&self) -> Option<ControllerStatus> {
+		if !self.has_class(CAN_ERR_CRTL) {
+			return None;
+		}
+		let flags = self.inner.data[1];
+		Some(ControllerStatus {
+			rx_overflow: flags & 0x01 != 0,
+			tx_overflow: flags & 0x02 != 0,
+			rx_warning: flags & 0x04 != 0,
+			tx_warning: flags & 0x08 != 0,
+			rx_passive: flags & 0x10 != 0,
+			tx_passive: flags & 0x20 != 0,
+			active: flags & 0x40 != 0,
+		})
+	}
+
+	pub fn protocol_violation(&self) -> Option<ProtocolViolation> {
+		if !self.has_class(CAN_ERR_PROT) {
+			return None;
+		}
+		let flags = self.inner.data[2];
+		let kind = ProtocolViolationKind {
+			bit: flags & 0x01 != 0,
+			form: flags & 0x02 != 0,
+			stuff: flags & 0x04 != 0,
+			bit0: flags & 0x08 != 0,
+			bit1: flags & 0x10 != 0,
+			overload: flags & 0x20 != 0,
+			active: flags & 0x40 != 0,
+			tx: flags & 0x80 != 0,
+		};
+		let location = match self.inner.data[3] {
+			0x00 => ProtocolViolationLocation::Unspecified,
+			0x03 => ProtocolViolationLocation::StartOfFrame,
+			0x02 => ProtocolViolationLocation::Id28To21,
+			0x06 => ProtocolViolationLocation::Id20To18,
+			0x04 => ProtocolViolationLocation::SubstituteRtr,
+			0x05 => ProtocolViolationLocation::IdentifierExtension,
+			0x07 => ProtocolViolationLocation::Id17To13,
+			0x0F => ProtocolViolationLocation::Id12To5,
+			0x0E => ProtocolViolationLocation::Id4To0,
+			0x0C => ProtocolViolationLocation::Rtr,
+			0x0D => ProtocolViolationLocation::Reserved1,
+			0x09 => ProtocolViolationLocation::Reserved0,
+			0x0B => ProtocolViolationLocation::DataLengthCode,
+			0x0A => ProtocolViolationLocation::Data,
+			0x08 => ProtocolViolationLocation::CrcSequence,
+			0x18 => ProtocolViolationLocation::CrcDelimiter,
+			0x19 => ProtocolViolationLocation::AckSlot,
+			0x1B => ProtocolViolationLocation::AckDelimiter,
+			0x1A => ProtocolViolationLocation::EndOfFrame,
+			0x12 => ProtocolViolationLocation::Intermission,
+			other => ProtocolViolationLocation::Other(other),
+		};
+		Some(ProtocolViolation { kind, location })
+	}
+
+	pub fn transceiver_status(&self) -> Option<TransceiverStatus> {
+		if !self.has_class(CAN_ERR_TRX) {
+			return None;
+		}
+		let status = match self.inner.data[4] {
+			0x00 => TransceiverStatus::Unspecified,
+			0x04 => TransceiverStatus::CanHighNoWire,
+			0x05 => TransceiverStatus::CanHighShortToBattery,
+			0x06 => TransceiverStatus::CanHighShortToVcc,
+			0x07 => TransceiverStatus::CanHighShortToGround,
+			0x40 => TransceiverStatus::CanLowNoWire,
+			0x50 => TransceiverStatus::CanLowShortToBattery,
+			0x60 => TransceiverStatus::CanLowShortToVcc,
+			0x70 => TransceiverStatus::CanLowShortToGround,
+			0x80 => TransceiverStatus::CanLowShortToCanHigh,
+			other => TransceiverStatus::Other(other),
+		};
+		Some(status)
+	}
+
+	pub fn error_counters(&self) -> Option<ErrorCounters> {
+		self.has_class(CAN_ERR_CNT)
+			.then_some(ErrorCounters {
+				tx: self.inner.data[6],
+				rx: self.inner.data[7],
+			})
+	}
+}
+
+/// The valid data lengths of a CAN FD frame, indexed by data length code.
+const FD_DLC_TO_LEN: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// Get the data length for a CAN FD data length code.
+pub const fn fd_dlc_to_len(dlc: u8) -> Option<u8> {
+	if dlc < 16 {
+		Some(FD_DLC_TO_LEN[dlc as usize])
+	} else {
+		None
+	}
+}
+
+/// Get the smallest data length code that can hold the given number of bytes in a CAN FD frame.
+pub const fn fd_len_to_dlc(len: u8) -> u8 {
+	let mut dlc = 0;
+	while dlc < 15 && FD_DLC_TO_LEN[dlc as usize] < len {
+		dlc += 1;
+	}
+	dlc
+}
+
+/// Round a data length up to the nearest valid data length of a CAN FD frame.
+pub const fn fd_padded_len(len: u8) -> u8 {
+	FD_DLC_TO_LEN[fd_len_to_dlc(len) as usize]
+}
+
+fn set_flag(flags: &mut u8, flag: u8, enable: bool) {
+	if enable {
+		*flags |= flag;
+	} else {
+		*flags &= !flag;
+	}
+}
+
 impl CanInterface {
 	pub fn from_index(index: u32) -> Self {
 		Self { index }
@@ -230,63 +597,218 @@ impl Socket {
 	}
 
 	pub fn send(&self, frame: &CanFrame) -> std::io::Result<()> {
+		self.send_raw(frame.as_c_void_ptr(), std::mem::size_of_val(frame), None)
+	}
+
+	pub fn send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
+		self.send_raw(frame.as_c_void_ptr(), std::mem::size_of_val(frame), Some(interface))
+	}
+
+	pub fn send_fd(&self, frame: &CanFdFrame) -> std::io::Result<()> {
+		self.send_raw(frame.as_c_void_ptr(), std::mem::size_of_val(frame), None)
+	}
+
+	pub fn send_fd_to(&self, frame: &CanFdFrame, interface: &CanInterface) -> std::io::Result<()> {
+		self.send_raw(frame.as_c_void_ptr(), std::mem::size_of_val(frame), Some(interface))
+	}
+
+	pub fn send_xl(&self, frame: &CanXlFrame) -> std::io::Result<()> {
+		self.send_raw(frame.as_c_void_ptr(), frame.wire_size(), None)
+	}
+
+	pub fn send_xl_to(&self, frame: &CanXlFrame, interface: &CanInterface) -> std::io::Result<()> {
+		self.send_raw(frame.as_c_void_ptr(), frame.wire_size(), Some(interface))
+	}
+
+	fn send_raw(&self, data: *const c_void, len: usize, interface: Option<&CanInterface>) -> std::io::Result<()> {
 		unsafe {
-			let written = check_isize(libc::send(
-				self.fd.as_raw_fd(),
-				frame.as_c_void_ptr(),
-				std::mem::size_of_val(frame),
-				0,
-			))?;
-			debug_assert!(written as usize == std::mem::size_of_val(frame));
+			let written = match interface {
+				None => check_isize(libc::send(self.fd.as_raw_fd(), data, len, 0))?,
+				Some(interface) => {
+					let address = interface.to_address();
+					check_isize(libc::sendto(
+						self.fd.as_raw_fd(),
+						data,
+						len,
+						0,
+						&address as *const _ as *const _,
+						std::mem::size_of_val(&address) as _,
+					))?
+				},
+			};
+			debug_assert!(written as usize == len);
 			Ok(())
 		}
 	}
 
-	pub fn send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
+	pub fn send_batch(&self, frames: &[crate::CanFrame]) -> std::io::Result<usize> {
+		let count = frames.len().min(MAX_BATCH_SIZE);
+		if count == 0 {
+			return Ok(0);
+		}
 		unsafe {
-			let address = interface.to_address();
-			let written = check_isize(libc::sendto(
+			let mut iovecs: [libc::iovec; MAX_BATCH_SIZE] = std::mem::zeroed();
+			let mut headers: [libc::mmsghdr; MAX_BATCH_SIZE] = std::mem::zeroed();
+			for ((frame, iovec), header) in frames[..count].iter().zip(&mut iovecs).zip(&mut headers) {
+				iovec.iov_base = (&frame.inner.inner as *const can_frame).cast_mut().cast();
+				iovec.iov_len = std::mem::size_of::<can_frame>();
+				header.msg_hdr.msg_iov = iovec;
+				header.msg_hdr.msg_iovlen = 1;
+			}
+			let sent = check_int(libc::sendmmsg(
 				self.fd.as_raw_fd(),
-				&frame as *const _ as *const _,
-				std::mem::size_of_val(frame),
+				headers.as_mut_ptr(),
+				count as c_uint,
 				0,
-				&address as *const _ as *const _,
-				std::mem::size_of_val(&address) as _,
 			))?;
-			debug_assert!(written as usize == std::mem::size_of_val(frame));
-			Ok(())
+			Ok(sent as usize)
+		}
+	}
+
+	pub fn recv_batch(&self, frames: &mut [crate::CanFrame], mut interfaces: Option<&mut [crate::CanInterface]>) -> std::io::Result<usize> {
+		let count = match &interfaces {
+			Some(interfaces) => frames.len().min(interfaces.len()),
+			None => frames.len(),
+		};
+		let count = count.min(MAX_BATCH_SIZE);
+		if count == 0 {
+			return Ok(0);
+		}
+
+		// Keep receiving until we get at least one classic CAN frame.
+		// If the socket is non-blocking, `recvmmsg()` fails with `WouldBlock` when there are no more frames.
+		loop {
+			unsafe {
+				// Receive into a local buffer, because the kernel may also write (truncated) CAN FD, CAN XL and error frames,
+				// which must never end up in a `CanFrame`.
+				let mut buffers: [can_frame; MAX_BATCH_SIZE] = std::mem::zeroed();
+				let mut addresses: [libc::sockaddr_can; MAX_BATCH_SIZE] = std::mem::zeroed();
+				let mut iovecs: [libc::iovec; MAX_BATCH_SIZE] = std::mem::zeroed();
+				let mut headers: [libc::mmsghdr; MAX_BATCH_SIZE] = std::mem::zeroed();
+				for (((buffer, iovec), header), address) in buffers[..count].iter_mut().zip(&mut iovecs).zip(&mut headers).zip(&mut addresses) {
+					iovec.iov_base = (buffer as *mut can_frame).cast();
+					iovec.iov_len = std::mem::size_of::<can_frame>();
+					header.msg_hdr.msg_name = (address as *mut libc::sockaddr_can).cast();
+					header.msg_hdr.msg_namelen = std::mem::size_of::<libc::sockaddr_can>() as _;
+					header.msg_hdr.msg_iov = iovec;
+					header.msg_hdr.msg_iovlen = 1;
+				}
+
+				// Use MSG_WAITFORONE to block only until the first frame is received.
+				let received = check_int(libc::recvmmsg(
+					self.fd.as_raw_fd(),
+					headers.as_mut_ptr(),
+					count as c_uint,
+					libc::MSG_WAITFORONE as _,
+					std::ptr::null_mut(),
+				))? as usize;
+
+				// Copy only the classic CAN frames to the output.
+				let mut stored = 0;
+				for (i, header) in headers[..received].iter().enumerate() {
+					let is_classic = header.msg_len as usize == std::mem::size_of::<can_frame>()
+						&& header.msg_hdr.msg_flags & libc::MSG_TRUNC == 0
+						&& buffers[i].can_id & libc::CAN_ERR_FLAG == 0;
+					if !is_classic {
+						continue;
+					}
+					frames[stored] = crate::CanFrame { inner: CanFrame { inner: buffers[i] } };
+					if let Some(interfaces) = &mut interfaces {
+						interfaces[stored] = crate::CanInterface {
+							inner: CanInterface { index: addresses[i].can_ifindex as u32 },
+						};
+					}
+					stored += 1;
+				}
+
+				if stored > 0 || received == 0 {
+					return Ok(stored);
+				}
+			}
 		}
 	}
 
 	pub fn recv(&self) -> std::io::Result<CanFrame> {
+		match self.recv_any()? {
+			AnyFrame::Can(frame) => Ok(frame),
+			other => Err(unexpected_frame(&other)),
+		}
+	}
+
+	pub fn recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
+		match self.recv_any_from()? {
+			(AnyFrame::Can(frame), interface) => Ok((frame, interface)),
+			(other, _) => Err(unexpected_frame(&other)),
+		}
+	}
+
+	pub fn recv_any(&self) -> std::io::Result<AnyFrame> {
 		unsafe {
-			let mut frame: MaybeUninit<CanFrame> = MaybeUninit::uninit();
+			// Zero the buffer, since CAN XL frames do not fill the entire buffer.
+			let mut frame: MaybeUninit<any_frame> = MaybeUninit::zeroed();
 			let read = check_isize(libc::recv(
 				self.fd.as_raw_fd(),
 				frame.as_mut_ptr().cast(),
 				std::mem::size_of_val(&frame),
 				0,
 			))?;
-			debug_assert!(read as usize == std::mem::size_of_val(&frame));
-			Ok(frame.assume_init())
+			parse_any_frame(frame, read as usize)
 		}
 	}
 
-	pub fn recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
+	pub fn recv_any_from(&self) -> std::io::Result<(AnyFrame, CanInterface)> {
 		unsafe {
-			let mut frame: MaybeUninit<CanFrame> = MaybeUninit::uninit();
+			// Zero the buffer, since CAN XL frames do not fill the entire buffer.
+			let mut frame: MaybeUninit<any_frame> = MaybeUninit::zeroed();
 			let mut addr: libc::sockaddr_can = std::mem::zeroed();
+			let mut addr_len: libc::socklen_t = std::mem::size_of_val(&addr) as _;
 			let read = check_isize(libc::recvfrom(
 				self.fd.as_raw_fd(),
 				frame.as_mut_ptr().cast(),
 				std::mem::size_of_val(&frame),
 				0,
 				&mut addr as *mut _ as *mut _,
-				std::mem::size_of_val(&addr) as _,
+				&mut addr_len,
 			))?;
-			debug_assert!(read as usize == std::mem::size_of_val(&frame));
+			let frame = parse_any_frame(frame, read as usize)?;
+			Ok((frame, CanInterface { index: addr.can_ifindex as u32 }))
+		}
+	}
 
-			Ok((frame.assume_init(), CanInterface { index: addr.can_ifindex as u32 }))
+	pub fn recv_with_metadata(&self) -> std::io::Result<(CanFrame, RecvMetadata)> {
+		match self.recv_any_with_metadata()? {
+			(AnyFrame::Can(frame), metadata) => Ok((frame, metadata)),
+			(other, _) => Err(unexpected_frame(&other)),
+		}
+	}
+
+	pub fn recv_any_with_metadata(&self) -> std::io::Result<(AnyFrame, RecvMetadata)> {
+		unsafe {
+			// Zero the buffer, since CAN XL frames do not fill the entire buffer.
+			let mut frame: MaybeUninit<any_frame> = MaybeUninit::zeroed();
+			let mut addr: libc::sockaddr_can = std::mem::zeroed();
+			let mut control = ControlBuffer::new();
+			let mut iov = libc::iovec {
+				iov_base: frame.as_mut_ptr().cast(),
+				iov_len: std::mem::size_of_val(&frame),
+			};
+			let mut header: libc::msghdr = std::mem::zeroed();
+			header.msg_name = (&mut addr as *mut libc::sockaddr_can).cast();
+			header.msg_namelen = std::mem::size_of_val(&addr) as _;
+			header.msg_iov = &mut iov;
+			header.msg_iovlen = 1;
+			header.msg_control = control.as_mut_ptr();
+			header.msg_controllen = control.len() as _;
+
+			let read = check_isize(libc::recvmsg(self.fd.as_raw_fd(), &mut header, 0))?;
+			let frame = parse_any_frame(frame, read as usize)?;
+			let mut metadata = RecvMetadata {
+				interface: CanInterface { index: addr.can_ifindex as u32 },
+				timestamps: crate::Timestamps::default(),
+				drop_count: 0,
+			};
+			parse_ancillary_data(&header, &mut metadata);
+			Ok((frame, metadata))
 		}
 	}
 
@@ -302,6 +824,180 @@ impl Socket {
 		}
 	}
 
+	pub fn get_fd_frames(&self) -> std::io::Result<bool> {
+		let enabled: c_int = unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_FD_FRAMES,
+			)?
+		};
+		Ok(enabled != 0)
+	}
+
+	pub fn set_fd_frames(&self, enable: bool) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_FD_FRAMES,
+				&c_int::from(enable),
+			)
+		}
+	}
+
+	pub fn get_filters(&self) -> std::io::Result<Vec<CanFilter>> {
+		let empty = CanFilter { filter: libc::can_filter { can_id: 0, can_mask: 0 } };
+		let mut filters = vec![empty; CAN_RAW_FILTER_MAX];
+		let mut len: libc::socklen_t = std::mem::size_of_val(filters.as_slice()) as _;
+		unsafe {
+			check_int(libc::getsockopt(
+				self.fd.as_raw_fd(),
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_FILTER,
+				filters.as_mut_ptr().cast(),
+				&mut len,
+			))?;
+		}
+		filters.truncate(len as usize / std::mem::size_of::<CanFilter>());
+		Ok(filters)
+	}
+
+	pub fn get_join_filters(&self) -> std::io::Result<bool> {
+		let enabled: c_int = unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_JOIN_FILTERS,
+			)?
+		};
+		Ok(enabled != 0)
+	}
+
+	pub fn set_join_filters(&self, enable: bool) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_JOIN_FILTERS,
+				&c_int::from(enable),
+			)
+		}
+	}
+
+	pub fn get_timestamp_mode(&self) -> std::io::Result<crate::TimestampMode> {
+		let timestamping: c_int = unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_SOCKET,
+				libc::SO_TIMESTAMPING,
+			)?
+		};
+		if timestamping as u32 & libc::SOF_TIMESTAMPING_RAW_HARDWARE != 0 {
+			return Ok(crate::TimestampMode::SoftwareAndHardware);
+		}
+
+		let timestamp_ns: c_int = unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_SOCKET,
+				libc::SO_TIMESTAMPNS,
+			)?
+		};
+		if timestamp_ns != 0 {
+			Ok(crate::TimestampMode::Software)
+		} else {
+			Ok(crate::TimestampMode::Disabled)
+		}
+	}
+
+	pub fn set_timestamp_mode(&self, mode: crate::TimestampMode) -> std::io::Result<()> {
+		let (timestamp_ns, timestamping) = match mode {
+			crate::TimestampMode::Disabled => (0, 0),
+			crate::TimestampMode::Software => (1, 0),
+			crate::TimestampMode::SoftwareAndHardware => (0, TIMESTAMPING_FLAGS as c_int),
+		};
+		unsafe {
+			set_socket_option(
+				&self.fd,
+				libc::SOL_SOCKET,
+				libc::SO_TIMESTAMPING,
+				&timestamping,
+			)?;
+			set_socket_option(
+				&self.fd,
+				libc::SOL_SOCKET,
+				libc::SO_TIMESTAMPNS,
+				&timestamp_ns,
+			)
+		}
+	}
+
+	pub fn get_drop_count_enabled(&self) -> std::io::Result<bool> {
+		let enabled: c_int = unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_SOCKET,
+				libc::SO_RXQ_OVFL,
+			)?
+		};
+		Ok(enabled != 0)
+	}
+
+	pub fn set_drop_count_enabled(&self, enable: bool) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(
+				&self.fd,
+				libc::SOL_SOCKET,
+				libc::SO_RXQ_OVFL,
+				&c_int::from(enable),
+			)
+		}
+	}
+
+	pub fn get_error_mask(&self) -> std::io::Result<u32> {
+		unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_ERR_FILTER,
+			)
+		}
+	}
+
+	pub fn set_error_mask(&self, mask: u32) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_ERR_FILTER,
+				&(mask & libc::CAN_ERR_MASK),
+			)
+		}
+	}
+
+	pub fn get_xl_frames(&self) -> std::io::Result<bool> {
+		let enabled: c_int = unsafe {
+			get_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_XL_FRAMES,
+			)?
+		};
+		Ok(enabled != 0)
+	}
+
+	pub fn set_xl_frames(&self, enable: bool) -> std::io::Result<()> {
+		unsafe {
+			set_socket_option(
+				&self.fd,
+				libc::SOL_CAN_RAW,
+				libc::CAN_RAW_XL_FRAMES,
+				&c_int::from(enable),
+			)
+		}
+	}
+
 	pub fn get_loopback(&self) -> std::io::Result<bool> {
 		let enabled: c_int = unsafe {
 			get_socket_option(
@@ -349,6 +1045,16 @@ impl Socket {
 }
 
 impl CanFilter {
+	pub const fn from_raw_parts(can_id: u32, can_mask: u32) -> Self {
+		Self {
+			filter: libc::can_filter { can_id, can_mask },
+		}
+	}
+
+	pub const fn raw_parts(self) -> (u32, u32) {
+		(self.filter.can_id, self.filter.can_mask)
+	}
+
 	pub const fn new_standard(id: StandardId) -> Self {
 		Self {
 			filter: libc::can_filter {
@@ -361,7 +1067,7 @@ impl CanFilter {
 	pub const fn new_extended(id: ExtendedId) -> Self {
 		Self {
 			filter: libc::can_filter {
-				can_id: id.as_u32(),
+				can_id: id.as_u32() | libc::CAN_EFF_FLAG,
 				can_mask: 0,
 			},
 		}
@@ -452,17 +1158,123 @@ impl CanFilter {
 	}
 
 	pub const fn test(self, frame: &CanFrame) -> bool {
+		self.test_raw_id(frame.inner.can_id)
+	}
+
+	pub const fn test_fd(self, frame: &CanFdFrame) -> bool {
+		self.test_raw_id(frame.inner.can_id)
+	}
+
+	const fn test_raw_id(self, can_id: u32) -> bool {
 		let id = self.filter.can_id & !libc::CAN_INV_FILTER;
-		let frame_matches = frame.inner.can_id & self.filter.can_mask == id & self.filter.can_mask;
+		let frame_matches = can_id & self.filter.can_mask == id & self.filter.can_mask;
 		if self.is_inverted() {
+			!frame_matches
+		} else {
 			frame_matches
+		}
+	}
+}
+
+/// Interpret the data received from a CAN socket as a frame.
+///
+/// # Safety
+/// At least `len` bytes of `frame` must be initialized.
+unsafe fn parse_any_frame(frame: MaybeUninit<any_frame>, len: usize) -> std::io::Result<AnyFrame> {
+	// CAN XL frames have a variable size, so check the XLF flag first.
+	// The flag overlaps with the length field of classic and CAN FD frames, which never has the highest bit set.
+	if len > CANXL_HDR_SIZE && std::ptr::addr_of!((*frame.as_ptr()).xl.flags).read() & CANXL_XLF != 0 {
+		let frame = std::ptr::addr_of!((*frame.as_ptr()).xl).read();
+		if len != CANXL_HDR_SIZE + usize::from(frame.len) {
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("received CAN XL frame with unexpected size: {len} bytes")));
+		}
+		Ok(AnyFrame::Xl(Box::new(CanXlFrame { inner: frame })))
+	} else if len == std::mem::size_of::<can_frame>() {
+		let frame = std::ptr::addr_of!((*frame.as_ptr()).can).read();
+		if frame.can_id & libc::CAN_ERR_FLAG != 0 {
+			Ok(AnyFrame::Error(CanErrorFrame { inner: frame }))
 		} else {
-			!frame_matches
+			Ok(AnyFrame::Can(CanFrame { inner: frame }))
+		}
+	} else if len == std::mem::size_of::<canfd_frame>() {
+		Ok(AnyFrame::Fd(CanFdFrame { inner: std::ptr::addr_of!((*frame.as_ptr()).fd).read() }))
+	} else {
+		Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("received frame with unexpected size: {len} bytes")))
+	}
+}
+
+/// The flags for `SO_TIMESTAMPING` to request software and raw hardware receive timestamps.
+const TIMESTAMPING_FLAGS: u32 = libc::SOF_TIMESTAMPING_SOFTWARE
+	| libc::SOF_TIMESTAMPING_RX_SOFTWARE
+	| libc::SOF_TIMESTAMPING_RAW_HARDWARE
+	| libc::SOF_TIMESTAMPING_RX_HARDWARE;
+
+/// Buffer for the ancillary data of `recvmsg()`, aligned for `cmsghdr`.
+#[repr(C, align(8))]
+struct ControlBuffer {
+	data: [u8; 256],
+}
+
+impl ControlBuffer {
+	fn new() -> Self {
+		Self { data: [0; 256] }
+	}
+
+	fn as_mut_ptr(&mut self) -> *mut c_void {
+		self.data.as_mut_ptr().cast()
+	}
+
+	fn len(&self) -> usize {
+		self.data.len()
+	}
+}
+
+/// Parse the timestamps and drop count from the ancillary data of a message received with `recvmsg()`.
+unsafe fn parse_ancillary_data(header: &libc::msghdr, metadata: &mut RecvMetadata) {
+	let mut cmsg = libc::CMSG_FIRSTHDR(header);
+	while let Some(current) = cmsg.as_ref() {
+		if current.cmsg_level == libc::SOL_SOCKET {
+			let data = libc::CMSG_DATA(current);
+			if current.cmsg_type == libc::SCM_TIMESTAMPNS {
+				let time: libc::timespec = data.cast::<libc::timespec>().read_unaligned();
+				metadata.timestamps.software = timespec_to_duration(time).map(|x| std::time::UNIX_EPOCH + x);
+			} else if current.cmsg_type == libc::SCM_TIMESTAMPING {
+				// The kernel sends three timestamps: software, deprecated and raw hardware.
+				let times: [libc::timespec; 3] = data.cast::<[libc::timespec; 3]>().read_unaligned();
+				if let Some(software) = timespec_to_duration(times[0]) {
+					metadata.timestamps.software = Some(std::time::UNIX_EPOCH + software);
+				}
+				metadata.timestamps.hardware = timespec_to_duration(times[2]);
+			} else if current.cmsg_type == libc::SO_RXQ_OVFL {
+				// The kernel only sends the drop count if it is not zero.
+				metadata.drop_count = data.cast::<u32>().read_unaligned();
+			}
 		}
+		cmsg = libc::CMSG_NXTHDR(header, current);
 	}
 }
 
-fn check_int(return_value: c_int) -> std::io::Result<c_int> {
+/// Convert a timespec to a duration, or `None` if it is zero or invalid.
+fn timespec_to_duration(time: libc::timespec) -> Option<std::time::Duration> {
+	if time.tv_sec == 0 && time.tv_nsec == 0 {
+		return None;
+	}
+	let secs = u64::try_from(time.tv_sec).ok()?;
+	let nanos = u32::try_from(time.tv_nsec).ok()?;
+	Some(std::time::Duration::new(secs, nanos))
+}
+
+fn unexpected_frame(frame: &AnyFrame) -> std::io::Error {
+	let message = match frame {
+		AnyFrame::Can(_) => "received an unexpected classic CAN frame",
+		AnyFrame::Fd(_) => "received a CAN FD frame, use `recv_any()` to receive CAN FD frames",
+		AnyFrame::Xl(_) => "received a CAN XL frame, use `recv_any()` to receive CAN XL frames",
+		AnyFrame::Error(_) => "received a CAN error frame, use `recv_any()` to receive CAN error frames",
+	};
+	std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+pub(super) fn check_int(return_value: c_int) -> std::io::Result<c_int> {
 	if return_value == -1 {
 		Err(std::io::Error::last_os_error())
 	} else {
@@ -470,7 +1282,7 @@ fn check_int(return_value: c_int) -> std::io::Result<c_int> {
 	}
 }
 
-fn check_isize(return_value: isize) -> std::io::Result<isize> {
+pub(super) fn check_isize(return_value: isize) -> std::io::Result<isize> {
 	if return_value == -1 {
 		Err(std::io::Error::last_os_error())
 	} else {
@@ -478,7 +1290,7 @@ fn check_isize(return_value: isize) -> std::io::Result<isize> {
 	}
 }
 
-unsafe fn set_socket_option<T: Copy>(socket: &FileDesc, level: c_int, option: c_int, value: &T) -> std::io::Result<()> {
+pub(super) unsafe fn set_socket_option<T: Copy>(socket: &FileDesc, level: c_int, option: c_int, value: &T) -> std::io::Result<()> {
 	let len = std::mem::size_of_val(value).try_into().map_err(|_| std::io::ErrorKind::InvalidInput)?;
 	let value: *const T = value;
 	check_int(libc::setsockopt(socket.as_raw_fd(), level, option, value.cast(), len))?;
@@ -492,7 +1304,7 @@ unsafe fn set_socket_option_slice<T: Copy>(socket: &FileDesc, level: c_int, opti
 	Ok(())
 }
 
-unsafe fn get_socket_option<T: Copy + Default>(socket: &FileDesc, level: c_int, option: c_int) -> std::io::Result<T> {
+pub(super) unsafe fn get_socket_option<T: Copy + Default>(socket: &FileDesc, level: c_int, option: c_int) -> std::io::Result<T> {
 	let mut value = T::default();
 	let mut len = std::mem::size_of::<T>().try_into().unwrap();
 	{
@@ -3,3 +3,24 @@ mod linux;
 
 #[cfg(target_os = "linux")]
 pub(crate) use linux::*;
+
+#[cfg(target_os = "linux")]
+pub(crate) mod netlink;
+
+#[cfg(target_os = "linux")]
+mod isotp;
+
+#[cfg(target_os = "linux")]
+pub(crate) use isotp::IsoTpSocket;
+
+#[cfg(target_os = "linux")]
+mod j1939;
+
+#[cfg(target_os = "linux")]
+pub(crate) use j1939::J1939Socket;
+
+#[cfg(target_os = "linux")]
+mod bcm;
+
+#[cfg(target_os = "linux")]
+pub(crate) use bcm::BcmSocket;
@@ -0,0 +1,541 @@
+use filedesc::FileDesc;
+use std::ffi::{c_int, c_void};
+
+/// The size of the buffer used to receive netlink messages.
+const RECV_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Interface information message, prefixed to link messages.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+#[allow(non_camel_case_types)]
+pub(crate) struct ifinfomsg {
+	pub ifi_family: u8,
+	pub ifi_pad: u8,
+	pub ifi_type: u16,
+	pub ifi_index: i32,
+	pub ifi_flags: u32,
+	pub ifi_change: u32,
+}
+
+impl ifinfomsg {
+	/// Create an interface information message for the interface with the given index.
+	pub fn new(index: u32) -> Self {
+		Self {
+			ifi_index: index as i32,
+			..Default::default()
+		}
+	}
+
+	/// Parse the interface information message from the start of a message payload.
+	///
+	/// Returns the message and the remaining data.
+	pub fn parse(payload: &[u8]) -> std::io::Result<(Self, &[u8])> {
+		let size = std::mem::size_of::<Self>();
+		if payload.len() < size {
+			return Err(invalid_data("netlink message too short for interface information"));
+		}
+		// SAFETY: We checked the length, and all bit patterns are valid for the struct.
+		let header = unsafe { payload.as_ptr().cast::<Self>().read_unaligned() };
+		Ok((header, &payload[align(size)..]))
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		// SAFETY: The struct is `repr(C)` without padding, so all bytes are initialized.
+		unsafe {
+			std::slice::from_raw_parts((self as *const Self).cast(), std::mem::size_of::<Self>())
+		}
+	}
+}
+
+/// A socket for the routing netlink protocol (`NETLINK_ROUTE`).
+pub(crate) struct NetlinkSocket {
+	fd: FileDesc,
+	sequence: u32,
+}
+
+impl NetlinkSocket {
+	pub fn new() -> std::io::Result<Self> {
+		unsafe {
+			let fd = check_int(libc::socket(
+				libc::AF_NETLINK,
+				libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+				libc::NETLINK_ROUTE,
+			))?;
+			let fd = FileDesc::from_raw_fd(fd);
+
+			let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+			addr.nl_family = libc::AF_NETLINK as _;
+			check_int(libc::bind(
+				fd.as_raw_fd(),
+				&addr as *const libc::sockaddr_nl as *const _,
+				std::mem::size_of_val(&addr) as _,
+			))?;
+
+			// Enable extended ACKs to get error messages from the kernel,
+			// and ask the kernel not to echo the failed request in error messages.
+			// Ignore errors on older kernels: `parse_error()` can deal with both.
+			let enable: c_int = 1;
+			for option in [libc::NETLINK_EXT_ACK, NETLINK_CAP_ACK] {
+				libc::setsockopt(
+					fd.as_raw_fd(),
+					libc::SOL_NETLINK,
+					option,
+					(&enable as *const c_int).cast(),
+					std::mem::size_of_val(&enable) as _,
+				);
+			}
+
+			Ok(Self { fd, sequence: 0 })
+		}
+	}
+
+	/// Send a request and wait for the kernel to acknowledge it.
+	///
+	/// Returns the payload of all response messages (excluding the acknowledgement).
+	pub fn request(&mut self, message: MessageBuilder) -> std::io::Result<Vec<Vec<u8>>> {
+		self.sequence = self.sequence.wrapping_add(1);
+		let sequence = self.sequence;
+		let is_dump = message.flags & libc::NLM_F_DUMP as u16 == libc::NLM_F_DUMP as u16;
+		let data = message.finish(sequence);
+
+		unsafe {
+			let written = check_isize(libc::send(self.fd.as_raw_fd(), data.as_ptr().cast(), data.len(), 0))?;
+			if written as usize != data.len() {
+				return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "failed to send complete netlink message"));
+			}
+		}
+
+		let mut responses = Vec::new();
+		let mut buffer = vec![0u8; RECV_BUFFER_SIZE];
+		loop {
+			let read = unsafe {
+				check_isize(libc::recv(self.fd.as_raw_fd(), buffer.as_mut_ptr().cast::<c_void>(), buffer.len(), 0))?
+			};
+			for message in Messages::new(&buffer[..read as usize]) {
+				let message = message?;
+				if message.sequence != sequence {
+					continue;
+				}
+				match message.kind {
+					libc::NLMSG_DONE => {
+						parse_done(message.payload, message.flags)?;
+						return Ok(responses);
+					},
+					libc::NLMSG_ERROR => {
+						parse_error(message.payload, message.flags)?;
+						if !is_dump {
+							return Ok(responses);
+						}
+					},
+					libc::NLMSG_NOOP => (),
+					_ => responses.push(message.payload.to_vec()),
+				}
+			}
+		}
+	}
+}
+
+/// A netlink message received from the kernel.
+pub(crate) struct Message<'a> {
+	pub kind: c_int,
+	pub flags: u16,
+	pub sequence: u32,
+	pub payload: &'a [u8],
+}
+
+/// Iterator over the netlink messages in a buffer.
+pub(crate) struct Messages<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> Messages<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data }
+	}
+}
+
+impl<'a> Iterator for Messages<'a> {
+	type Item = std::io::Result<Message<'a>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let header_size = std::mem::size_of::<libc::nlmsghdr>();
+		if self.data.len() < header_size {
+			return None;
+		}
+		// SAFETY: We checked the length, and all bit patterns are valid for the struct.
+		let header = unsafe { self.data.as_ptr().cast::<libc::nlmsghdr>().read_unaligned() };
+		let len = header.nlmsg_len as usize;
+		if len < header_size || len > self.data.len() {
+			self.data = &[];
+			return Some(Err(invalid_data("invalid netlink message length")));
+		}
+		let message = Message {
+			kind: header.nlmsg_type.into(),
+			flags: header.nlmsg_flags,
+			sequence: header.nlmsg_seq,
+			payload: &self.data[align(header_size)..len],
+		};
+		self.data = &self.data[align(len).min(self.data.len())..];
+		Some(Ok(message))
+	}
+}
+
+/// Parse the payload of an `NLMSG_ERROR` message.
+///
+/// Returns `Ok(())` if the message is an acknowledgement.
+fn parse_error(payload: &[u8], flags: u16) -> std::io::Result<()> {
+	let error = parse_error_code(payload, "netlink error message too short")?;
+	if error == 0 {
+		return Ok(());
+	}
+
+	// The error code is followed by the header of the failed request.
+	// Unless the kernel capped the message, the header is followed by the rest of the request.
+	// Any extended ACK attributes come after that.
+	let request_start = std::mem::size_of::<c_int>();
+	let request_len = if flags & NLM_F_CAPPED != 0 {
+		std::mem::size_of::<libc::nlmsghdr>()
+	} else {
+		match payload.get(request_start..request_start + 4) {
+			Some(len) => u32::from_ne_bytes(len.try_into().unwrap()) as usize,
+			None => 0,
+		}
+	};
+	let attributes = payload.get(request_start + align(request_len)..).unwrap_or_default();
+	Err(os_error(error, flags, attributes))
+}
+
+/// Parse the payload of an `NLMSG_DONE` message.
+///
+/// Returns `Ok(())` if the dump completed without errors.
+fn parse_done(payload: &[u8], flags: u16) -> std::io::Result<()> {
+	// Tolerate `NLMSG_DONE` messages without an error code.
+	if payload.is_empty() {
+		return Ok(());
+	}
+	let error = parse_error_code(payload, "netlink done message too short")?;
+	if error == 0 {
+		return Ok(());
+	}
+	let attributes = payload.get(std::mem::size_of::<c_int>()..).unwrap_or_default();
+	Err(os_error(error, flags, attributes))
+}
+
+/// Parse the error code at the start of an `NLMSG_ERROR` or `NLMSG_DONE` message.
+fn parse_error_code(payload: &[u8], too_short: &'static str) -> std::io::Result<c_int> {
+	match payload.get(..std::mem::size_of::<c_int>()) {
+		Some(error) => Ok(c_int::from_ne_bytes(error.try_into().unwrap())),
+		None => Err(invalid_data(too_short)),
+	}
+}
+
+/// Create an error from a negative error code, including the extended error message if there is one.
+fn os_error(error: c_int, flags: u16, attributes: &[u8]) -> std::io::Error {
+	let error = std::io::Error::from_raw_os_error(-error);
+	if flags & NLM_F_ACK_TLVS == 0 {
+		return error;
+	}
+	for (kind, value) in Attributes::new(attributes) {
+		if kind == NLMSGERR_ATTR_MSG {
+			let message = value.split(|&c| c == 0).next().unwrap_or_default();
+			let message = String::from_utf8_lossy(message);
+			return std::io::Error::new(error.kind(), format!("{error}: {message}"));
+		}
+	}
+	error
+}
+
+/// Socket option to leave out the payload of the failed request in error messages.
+const NETLINK_CAP_ACK: c_int = 10;
+
+/// Flag set on error messages if the payload of the failed request was left out.
+const NLM_F_CAPPED: u16 = 0x100;
+
+/// Flag set on error and done messages if they contain extended ACK attributes.
+const NLM_F_ACK_TLVS: u16 = 0x200;
+
+/// Extended ACK attribute with an error message.
+const NLMSGERR_ATTR_MSG: u16 = 1;
+
+/// Builder for netlink messages.
+pub(crate) struct MessageBuilder {
+	kind: u16,
+	flags: u16,
+	payload: Vec<u8>,
+}
+
+impl MessageBuilder {
+	/// Create a new request message.
+	///
+	/// Requests that are not dump requests always ask for an acknowledgement.
+	pub fn new(kind: u16, flags: c_int) -> Self {
+		let flags = if flags & libc::NLM_F_DUMP == libc::NLM_F_DUMP {
+			flags | libc::NLM_F_REQUEST
+		} else {
+			flags | libc::NLM_F_REQUEST | libc::NLM_F_ACK
+		};
+		Self {
+			kind,
+			flags: flags as u16,
+			payload: Vec::new(),
+		}
+	}
+
+	/// Append a fixed header to the message, padded to the netlink alignment.
+	pub fn push_header(&mut self, data: &[u8]) {
+		self.payload.extend_from_slice(data);
+		self.pad();
+	}
+
+	/// Append an attribute to the message.
+	pub fn push_attribute(&mut self, kind: u16, data: &[u8]) {
+		let len = std::mem::size_of::<libc::nlattr>() + data.len();
+		self.payload.extend_from_slice(&(len as u16).to_ne_bytes());
+		self.payload.extend_from_slice(&kind.to_ne_bytes());
+		self.payload.extend_from_slice(data);
+		self.pad();
+	}
+
+	/// Append a `u32` attribute to the message.
+	pub fn push_u32(&mut self, kind: u16, value: u32) {
+		self.push_attribute(kind, &value.to_ne_bytes())
+	}
+
+	/// Append a null terminated string attribute to the message.
+	pub fn push_str(&mut self, kind: u16, value: &str) {
+		let mut data = Vec::with_capacity(value.len() + 1);
+		data.extend_from_slice(value.as_bytes());
+		data.push(0);
+		self.push_attribute(kind, &data)
+	}
+
+	/// Start a nested attribute.
+	///
+	/// All attributes added until the matching call to [`Self::end_nested()`] are part of the nested attribute.
+	pub fn begin_nested(&mut self, kind: u16) -> usize {
+		let start = self.payload.len();
+		self.push_attribute(kind | libc::NLA_F_NESTED as u16, &[]);
+		start
+	}
+
+	/// End a nested attribute started with [`Self::begin_nested()`].
+	pub fn end_nested(&mut self, start: usize) {
+		let len = (self.payload.len() - start) as u16;
+		self.payload[start..start + 2].copy_from_slice(&len.to_ne_bytes());
+	}
+
+	/// Finish the message by prepending the message header.
+	fn finish(self, sequence: u32) -> Vec<u8> {
+		let header_size = align(std::mem::size_of::<libc::nlmsghdr>());
+		let header = libc::nlmsghdr {
+			nlmsg_len: (header_size + self.payload.len()) as u32,
+			nlmsg_type: self.kind,
+			nlmsg_flags: self.flags,
+			nlmsg_seq: sequence,
+			nlmsg_pid: 0,
+		};
+		let mut data = Vec::with_capacity(header.nlmsg_len as usize);
+		// SAFETY: `nlmsghdr` is `repr(C)` without padding, so all bytes are initialized.
+		data.extend_from_slice(unsafe {
+			std::slice::from_raw_parts((&header as *const libc::nlmsghdr).cast::<u8>(), std::mem::size_of_val(&header))
+		});
+		data.resize(header_size, 0);
+		data.extend_from_slice(&self.payload);
+		data
+	}
+
+	/// Get the payload of the message, without the message header.
+	#[cfg(test)]
+	pub fn payload(&self) -> &[u8] {
+		&self.payload
+	}
+
+	fn pad(&mut self) {
+		self.payload.resize(align(self.payload.len()), 0);
+	}
+}
+
+/// Iterator over the netlink attributes in a buffer.
+///
+/// Yields the attribute type (without the nested and byte order flags) and the attribute value.
+/// Iteration stops at the first malformed attribute.
+#[derive(Clone)]
+pub(crate) struct Attributes<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> Attributes<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data }
+	}
+}
+
+impl<'a> Iterator for Attributes<'a> {
+	type Item = (u16, &'a [u8]);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let header_size = std::mem::size_of::<libc::nlattr>();
+		if self.data.len() < header_size {
+			return None;
+		}
+		let len = u16::from_ne_bytes([self.data[0], self.data[1]]) as usize;
+		let kind = u16::from_ne_bytes([self.data[2], self.data[3]]) & libc::NLA_TYPE_MASK as u16;
+		if len < header_size || len > self.data.len() {
+			self.data = &[];
+			return None;
+		}
+		let value = &self.data[header_size..len];
+		self.data = &self.data[align(len).min(self.data.len())..];
+		Some((kind, value))
+	}
+}
+
+/// Find the first attribute of the given type.
+pub(crate) fn find_attribute(data: &[u8], kind: u16) -> Option<&[u8]> {
+	Attributes::new(data).find(|&(k, _)| k == kind).map(|(_, value)| value)
+}
+
+/// Parse a netlink attribute as a plain old data type.
+///
+/// Returns `None` if the attribute is too short.
+/// Longer attributes are allowed, since newer kernels may extend structs.
+pub(crate) fn parse_pod<T: Copy>(data: &[u8]) -> Option<T> {
+	if data.len() < std::mem::size_of::<T>() {
+		return None;
+	}
+	// SAFETY: We checked the length, and this function is only used with integer types and structs of integers.
+	Some(unsafe { data.as_ptr().cast::<T>().read_unaligned() })
+}
+
+/// Parse a null terminated string attribute.
+pub(crate) fn parse_str(data: &[u8]) -> Option<String> {
+	let data = data.split(|&c| c == 0).next()?;
+	String::from_utf8(data.to_vec()).ok()
+}
+
+/// Round up a length to the netlink alignment.
+const fn align(len: usize) -> usize {
+	let align = libc::NLA_ALIGNTO as usize;
+	(len + align - 1) & !(align - 1)
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+	std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn check_int(return_value: c_int) -> std::io::Result<c_int> {
+	if return_value == -1 {
+		Err(std::io::Error::last_os_error())
+	} else {
+		Ok(return_value)
+	}
+}
+
+fn check_isize(return_value: isize) -> std::io::Result<isize> {
+	if return_value == -1 {
+		Err(std::io::Error::last_os_error())
+	} else {
+		Ok(return_value)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn build_and_parse_attributes() {
+		let mut message = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		message.push_header(ifinfomsg::new(7).as_bytes());
+		message.push_u32(1, 0x1234_5678);
+		let nested = message.begin_nested(2);
+		message.push_str(3, "can");
+		message.push_attribute(4, &[1, 2, 3]);
+		message.end_nested(nested);
+		message.push_u32(5, 42);
+		let data = message.finish(9);
+
+		let mut messages = Messages::new(&data);
+		let_assert!(Some(Ok(message)) = messages.next());
+		assert!(let None = messages.next());
+		assert!(message.kind == libc::RTM_NEWLINK.into());
+		assert!(message.sequence == 9);
+
+		let_assert!(Ok((header, attributes)) = ifinfomsg::parse(message.payload));
+		assert!(header.ifi_index == 7);
+
+		let attributes: Vec<_> = Attributes::new(attributes).collect();
+		assert!(attributes.len() == 3);
+		assert!(attributes[0].0 == 1);
+		assert!(parse_pod::<u32>(attributes[0].1) == Some(0x1234_5678));
+		assert!(attributes[1].0 == 2);
+		assert!(attributes[2].0 == 5);
+		assert!(parse_pod::<u32>(attributes[2].1) == Some(42));
+
+		let nested: Vec<_> = Attributes::new(attributes[1].1).collect();
+		assert!(nested.len() == 2);
+		assert!(parse_str(nested[0].1).as_deref() == Some("can"));
+		assert!(nested[1] == (4, &[1, 2, 3][..]));
+	}
+
+	#[test]
+	fn parse_error_message() {
+		let mut payload = Vec::new();
+		payload.extend_from_slice(&(-libc::EBUSY).to_ne_bytes());
+		payload.resize(std::mem::size_of::<libc::nlmsgerr>(), 0);
+		let_assert!(Err(e) = parse_error(&payload, 0));
+		assert!(e.raw_os_error() == Some(libc::EBUSY));
+
+		let mut payload = Vec::new();
+		payload.extend_from_slice(&0i32.to_ne_bytes());
+		payload.resize(std::mem::size_of::<libc::nlmsgerr>(), 0);
+		assert!(let Ok(()) = parse_error(&payload, 0));
+	}
+
+	fn error_attributes(message: &str) -> Vec<u8> {
+		let mut attributes = MessageBuilder::new(0, 0);
+		attributes.push_str(NLMSGERR_ATTR_MSG, message);
+		attributes.payload().to_vec()
+	}
+
+	#[test]
+	fn parse_extended_error_message() {
+		let mut request = MessageBuilder::new(libc::RTM_NEWLINK, 0);
+		request.push_header(ifinfomsg::new(7).as_bytes());
+		request.push_str(3, "can0");
+		let request = request.finish(1);
+
+		// Without NETLINK_CAP_ACK, the full request is echoed before the attributes.
+		let mut payload = Vec::new();
+		payload.extend_from_slice(&(-libc::EBUSY).to_ne_bytes());
+		payload.extend_from_slice(&request);
+		payload.extend_from_slice(&error_attributes("device is busy"));
+		let_assert!(Err(e) = parse_error(&payload, NLM_F_ACK_TLVS));
+		assert!(e.kind() == std::io::ErrorKind::ResourceBusy);
+		assert!(e.to_string().ends_with(": device is busy"));
+
+		// With NETLINK_CAP_ACK, only the header of the request is echoed.
+		let mut payload = Vec::new();
+		payload.extend_from_slice(&(-libc::EBUSY).to_ne_bytes());
+		payload.extend_from_slice(&request[..std::mem::size_of::<libc::nlmsghdr>()]);
+		payload.extend_from_slice(&error_attributes("device is busy"));
+		let_assert!(Err(e) = parse_error(&payload, NLM_F_CAPPED | NLM_F_ACK_TLVS));
+		assert!(e.to_string().ends_with(": device is busy"));
+	}
+
+	#[test]
+	fn parse_done_message() {
+		assert!(let Ok(()) = parse_done(&[], 0));
+		assert!(let Ok(()) = parse_done(&0i32.to_ne_bytes(), 0));
+
+		let mut payload = Vec::new();
+		payload.extend_from_slice(&(-libc::EINVAL).to_ne_bytes());
+		let_assert!(Err(e) = parse_done(&payload, 0));
+		assert!(e.raw_os_error() == Some(libc::EINVAL));
+
+		payload.extend_from_slice(&error_attributes("invalid dump request"));
+		let_assert!(Err(e) = parse_done(&payload, NLM_F_ACK_TLVS));
+		assert!(e.to_string().ends_with(": invalid dump request"));
+	}
+}
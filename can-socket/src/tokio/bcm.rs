@@ -0,0 +1,145 @@
+use std::task::{Context, Poll};
+use tokio::io::unix::AsyncFd;
+
+use crate::sys;
+use crate::tokio::io::{poll_read_io, read_io, timeout_at, try_read_io, write_io};
+use crate::bcm::BcmEvent;
+use crate::bcm::RxSetup;
+use crate::bcm::TxSetup;
+use crate::CanFrame;
+use crate::CanId;
+use crate::Deadline;
+
+/// An asynchronous broadcast manager socket for `tokio`.
+///
+/// See [`crate::bcm`] for more information.
+pub struct BcmSocket {
+	io: AsyncFd<sys::BcmSocket>,
+}
+
+impl std::fmt::Debug for BcmSocket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("BcmSocket");
+		#[cfg(unix)]
+		{
+			use std::os::unix::io::AsRawFd;
+			debug.field("fd", &self.as_raw_fd());
+			debug.finish()
+		}
+
+		#[cfg(not(unix))]
+		debug.finish_non_exhaustive()
+	}
+}
+
+impl BcmSocket {
+	/// Create a new broadcast manager socket connected to a named CAN interface.
+	///
+	/// This function is not async as it will either succeed or fail immediately.
+	pub fn connect(interface: impl AsRef<str>) -> std::io::Result<Self> {
+		let inner = sys::BcmSocket::new(true)?;
+		let interface = sys::CanInterface::from_name(interface.as_ref())?;
+		inner.connect(&interface)?;
+		let io = AsyncFd::new(inner)?;
+		Ok(Self { io })
+	}
+
+	/// Set up or update a cyclic transmission job.
+	pub async fn tx_setup(&self, setup: &TxSetup) -> std::io::Result<()> {
+		write_io(&self.io, |inner| {
+			inner.tx_setup(setup)
+		}).await
+	}
+
+	/// Delete the transmission job for a CAN ID.
+	pub async fn tx_delete(&self, id: impl Into<CanId>) -> std::io::Result<()> {
+		let id = id.into();
+		write_io(&self.io, |inner| {
+			inner.tx_delete(id)
+		}).await
+	}
+
+	/// Send a single frame through the broadcast manager.
+	pub async fn tx_send(&self, frame: &CanFrame) -> std::io::Result<()> {
+		write_io(&self.io, |inner| {
+			inner.tx_send(frame)
+		}).await
+	}
+
+	/// Set up or update a receive job.
+	pub async fn rx_setup(&self, setup: &RxSetup) -> std::io::Result<()> {
+		write_io(&self.io, |inner| {
+			inner.rx_setup(setup)
+		}).await
+	}
+
+	/// Delete the receive job for a CAN ID.
+	pub async fn rx_delete(&self, id: impl Into<CanId>) -> std::io::Result<()> {
+		let id = id.into();
+		write_io(&self.io, |inner| {
+			inner.rx_delete(id)
+		}).await
+	}
+
+	/// Receive a notification from the broadcast manager.
+	pub async fn recv(&self) -> std::io::Result<BcmEvent> {
+		read_io(&self.io, |inner| {
+			inner.recv()
+		}).await
+	}
+
+	/// Receive a notification from the broadcast manager with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<BcmEvent> {
+		timeout_at(timeout, self.recv()).await
+	}
+
+	/// Receive a notification from the broadcast manager, without waiting for one to become available.
+	pub fn try_recv(&self) -> std::io::Result<BcmEvent> {
+		try_read_io(&self.io, |inner| {
+			inner.recv()
+		})
+	}
+
+	/// Poll for a notification from the broadcast manager.
+	///
+	/// This can be used to consume the notifications as a stream.
+	/// If no notification is available, the current task is scheduled to be woken up when the socket becomes readable.
+	pub fn poll_recv(&self, context: &mut Context<'_>) -> Poll<std::io::Result<BcmEvent>> {
+		poll_read_io(&self.io, context, |inner| inner.recv())
+	}
+}
+
+impl std::os::fd::AsFd for BcmSocket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.io.as_fd()
+	}
+}
+
+impl From<BcmSocket> for std::os::fd::OwnedFd {
+	fn from(value: BcmSocket) -> Self {
+		value.io.into_inner().into()
+	}
+}
+
+impl TryFrom<std::os::fd::OwnedFd> for BcmSocket {
+	type Error = std::io::Error;
+
+	fn try_from(value: std::os::fd::OwnedFd) -> std::io::Result<Self> {
+		let io = AsyncFd::new(sys::BcmSocket::from(value))?;
+		Ok(Self { io })
+	}
+}
+
+impl std::os::fd::AsRawFd for BcmSocket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.io.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for BcmSocket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.io.into_inner().into_raw_fd()
+	}
+}
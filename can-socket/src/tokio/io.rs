@@ -0,0 +1,62 @@
+//! Helpers to perform non-blocking I/O on a file descriptor registered with the `tokio` reactor.
+//!
+//! All `tokio` sockets use these functions, so they share the same cancel safety and timeout behaviour.
+
+use std::future::Future;
+use std::os::fd::AsRawFd;
+use std::task::{Context, Poll};
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+
+use crate::Deadline;
+
+/// Wait for the file descriptor to become readable and perform a non-blocking read operation.
+///
+/// If the operation fails with [`std::io::ErrorKind::WouldBlock`], the readiness is cleared and the operation is retried when the file descriptor becomes readable again.
+pub(crate) async fn read_io<T: AsRawFd, R>(io: &AsyncFd<T>, f: impl FnMut(&T) -> std::io::Result<R>) -> std::io::Result<R> {
+	io.async_io(Interest::READABLE, f).await
+}
+
+/// Wait for the file descriptor to become writable and perform a non-blocking write operation.
+///
+/// If the operation fails with [`std::io::ErrorKind::WouldBlock`], the readiness is cleared and the operation is retried when the file descriptor becomes writable again.
+pub(crate) async fn write_io<T: AsRawFd, R>(io: &AsyncFd<T>, f: impl FnMut(&T) -> std::io::Result<R>) -> std::io::Result<R> {
+	io.async_io(Interest::WRITABLE, f).await
+}
+
+/// Perform a non-blocking read operation without waiting for the file descriptor to become readable.
+pub(crate) fn try_read_io<T: AsRawFd, R>(io: &AsyncFd<T>, f: impl FnOnce(&T) -> std::io::Result<R>) -> std::io::Result<R> {
+	io.try_io(Interest::READABLE, f)
+}
+
+/// Perform a non-blocking write operation without waiting for the file descriptor to become writable.
+pub(crate) fn try_write_io<T: AsRawFd, R>(io: &AsyncFd<T>, f: impl FnOnce(&T) -> std::io::Result<R>) -> std::io::Result<R> {
+	io.try_io(Interest::WRITABLE, f)
+}
+
+/// Perform a non-blocking read operation, or schedule the current task to be woken up when the file descriptor becomes readable.
+pub(crate) fn poll_read_io<T: AsRawFd, R>(io: &AsyncFd<T>, context: &mut Context<'_>, mut f: impl FnMut(&T) -> std::io::Result<R>) -> Poll<std::io::Result<R>> {
+	loop {
+		let mut guard = std::task::ready!(io.poll_read_ready(context))?;
+		match guard.try_io(|inner| f(inner.get_ref())) {
+			Ok(result) => return Poll::Ready(result),
+			Err(_would_block) => continue,
+		}
+	}
+}
+
+/// Perform a non-blocking write operation, or schedule the current task to be woken up when the file descriptor becomes writable.
+pub(crate) fn poll_write_io<T: AsRawFd, R>(io: &AsyncFd<T>, context: &mut Context<'_>, mut f: impl FnMut(&T) -> std::io::Result<R>) -> Poll<std::io::Result<R>> {
+	loop {
+		let mut guard = std::task::ready!(io.poll_write_ready(context))?;
+		match guard.try_io(|inner| f(inner.get_ref())) {
+			Ok(result) => return Poll::Ready(result),
+			Err(_would_block) => continue,
+		}
+	}
+}
+
+/// Wait for a future to complete, or fail with [`std::io::ErrorKind::TimedOut`] when the deadline expires.
+pub(crate) async fn timeout_at<T>(timeout: impl Deadline, future: impl Future<Output = std::io::Result<T>>) -> std::io::Result<T> {
+	tokio::time::timeout_at(timeout.deadline().into(), future).await?
+}
@@ -0,0 +1,170 @@
+use tokio::io::unix::AsyncFd;
+
+use crate::sys;
+use crate::tokio::io::{read_io, timeout_at, try_read_io, try_write_io, write_io};
+use crate::isotp::FlowControlOptions;
+use crate::isotp::IsoTpConfig;
+use crate::isotp::IsoTpOptions;
+use crate::isotp::LinkLayerOptions;
+use crate::CanId;
+use crate::CanInterface;
+use crate::Deadline;
+
+/// An asynchronous ISO-TP socket for `tokio`.
+///
+/// See [`crate::isotp`] for more information.
+pub struct IsoTpSocket {
+	io: AsyncFd<sys::IsoTpSocket>,
+}
+
+impl std::fmt::Debug for IsoTpSocket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("IsoTpSocket");
+		#[cfg(unix)]
+		{
+			use std::os::unix::io::AsRawFd;
+			debug.field("fd", &self.as_raw_fd());
+			debug.finish()
+		}
+
+		#[cfg(not(unix))]
+		debug.finish_non_exhaustive()
+	}
+}
+
+impl IsoTpSocket {
+	/// Create a new ISO-TP socket bound to a named CAN interface.
+	///
+	/// The socket receives messages sent with CAN ID `rx_id`,
+	/// and sends messages (and flow control frames) with CAN ID `tx_id`.
+	///
+	/// This function is not async as it will either succeed or fail immediately.
+	pub fn bind(interface: impl AsRef<str>, rx_id: impl Into<CanId>, tx_id: impl Into<CanId>) -> std::io::Result<Self> {
+		Self::bind_with_config(interface, rx_id, tx_id, &IsoTpConfig::default())
+	}
+
+	/// Create a new ISO-TP socket with a custom configuration, bound to a named CAN interface.
+	///
+	/// The socket receives messages sent with CAN ID `rx_id`,
+	/// and sends messages (and flow control frames) with CAN ID `tx_id`.
+	///
+	/// This function is not async as it will either succeed or fail immediately.
+	pub fn bind_with_config(interface: impl AsRef<str>, rx_id: impl Into<CanId>, tx_id: impl Into<CanId>, config: &IsoTpConfig) -> std::io::Result<Self> {
+		let inner = sys::IsoTpSocket::new(true)?;
+		crate::isotp::socket::configure(&inner, config)?;
+		let interface = sys::CanInterface::from_name(interface.as_ref())?;
+		inner.bind(&interface, rx_id.into(), tx_id.into())?;
+		let io = AsyncFd::new(inner)?;
+		Ok(Self { io })
+	}
+
+	/// Get the interface this socket is bound to.
+	pub fn local_addr(&self) -> std::io::Result<CanInterface> {
+		Ok(CanInterface {
+			inner: self.io.get_ref().local_addr()?,
+		})
+	}
+
+	/// Send a complete message over the socket.
+	///
+	/// Unless the [`IsoTpFlags::WAIT_TX_DONE`][crate::isotp::IsoTpFlags::WAIT_TX_DONE] flag is set,
+	/// this returns as soon as the kernel accepted the message for transmission.
+	pub async fn send(&self, data: &[u8]) -> std::io::Result<()> {
+		write_io(&self.io, |inner| {
+			inner.send(data)
+		}).await
+	}
+
+	/// Send a complete message over the socket with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn send_timeout(&self, data: &[u8], timeout: impl Deadline) -> std::io::Result<()> {
+		timeout_at(timeout, self.send(data)).await
+	}
+
+	/// Try to send a complete message over the socket without waiting for the socket to become writable.
+	pub fn try_send(&self, data: &[u8]) -> std::io::Result<()> {
+		try_write_io(&self.io, |inner| {
+			inner.send(data)
+		})
+	}
+
+	/// Receive a complete message from the socket.
+	pub async fn recv(&self) -> std::io::Result<Vec<u8>> {
+		read_io(&self.io, |inner| {
+			inner.recv()
+		}).await
+	}
+
+	/// Receive a complete message from the socket with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<Vec<u8>> {
+		timeout_at(timeout, self.recv()).await
+	}
+
+	/// Receive a complete message from the socket, without waiting for one to become available.
+	pub fn try_recv(&self) -> std::io::Result<Vec<u8>> {
+		try_read_io(&self.io, |inner| {
+			inner.recv()
+		})
+	}
+
+	/// Get the general options of the socket.
+	pub fn get_options(&self) -> std::io::Result<IsoTpOptions> {
+		self.io.get_ref().get_options()
+	}
+
+	/// Get the flow control options of the socket.
+	pub fn get_flow_control_options(&self) -> std::io::Result<FlowControlOptions> {
+		self.io.get_ref().get_flow_control_options()
+	}
+
+	/// Get the link layer options of the socket.
+	pub fn get_link_layer_options(&self) -> std::io::Result<LinkLayerOptions> {
+		self.io.get_ref().get_link_layer_options()
+	}
+
+	/// Get the forced separation time between transmitted consecutive frames.
+	pub fn get_tx_stmin(&self) -> std::io::Result<std::time::Duration> {
+		self.io.get_ref().get_tx_stmin()
+	}
+
+	/// Get the minimum separation time between received consecutive frames.
+	pub fn get_rx_stmin(&self) -> std::io::Result<std::time::Duration> {
+		self.io.get_ref().get_rx_stmin()
+	}
+}
+
+impl std::os::fd::AsFd for IsoTpSocket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.io.as_fd()
+	}
+}
+
+impl From<IsoTpSocket> for std::os::fd::OwnedFd {
+	fn from(value: IsoTpSocket) -> Self {
+		value.io.into_inner().into()
+	}
+}
+
+impl TryFrom<std::os::fd::OwnedFd> for IsoTpSocket {
+	type Error = std::io::Error;
+
+	fn try_from(value: std::os::fd::OwnedFd) -> std::io::Result<Self> {
+		let io = AsyncFd::new(sys::IsoTpSocket::from(value))?;
+		Ok(Self { io })
+	}
+}
+
+impl std::os::fd::AsRawFd for IsoTpSocket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.io.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for IsoTpSocket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.io.into_inner().into_raw_fd()
+	}
+}
@@ -0,0 +1,229 @@
+use tokio::io::unix::AsyncFd;
+
+use crate::sys;
+use crate::tokio::io::{read_io, timeout_at, try_read_io, try_write_io, write_io};
+use crate::j1939::J1939Address;
+use crate::j1939::J1939Message;
+use crate::j1939::J1939MessageInfo;
+use crate::CanInterface;
+use crate::Deadline;
+
+/// An asynchronous J1939 socket for `tokio`.
+///
+/// See [`crate::j1939`] for more information.
+pub struct J1939Socket {
+	io: AsyncFd<sys::J1939Socket>,
+}
+
+impl std::fmt::Debug for J1939Socket {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut debug = f.debug_struct("J1939Socket");
+		#[cfg(unix)]
+		{
+			use std::os::unix::io::AsRawFd;
+			debug.field("fd", &self.as_raw_fd());
+			debug.finish()
+		}
+
+		#[cfg(not(unix))]
+		debug.finish_non_exhaustive()
+	}
+}
+
+impl J1939Socket {
+	/// Create a new J1939 socket bound to a named CAN interface.
+	///
+	/// See [`crate::J1939Socket::bind()`] for the meaning of the local address.
+	///
+	/// This function is not async as it will either succeed or fail immediately.
+	pub fn bind(interface: impl AsRef<str>, address: &J1939Address) -> std::io::Result<Self> {
+		let inner = sys::J1939Socket::new(true)?;
+		let interface = sys::CanInterface::from_name(interface.as_ref())?;
+		inner.bind(&interface, address)?;
+		let io = AsyncFd::new(inner)?;
+		Ok(Self { io })
+	}
+
+	/// Set the default destination of the socket.
+	///
+	/// Messages sent with [`Self::send()`] go to this destination.
+	/// The PGN of the address is used as PGN for sent messages.
+	///
+	/// This function is not async as it will either succeed or fail immediately.
+	pub fn connect(&self, address: &J1939Address) -> std::io::Result<()> {
+		self.io.get_ref().connect(address)
+	}
+
+	/// Get the interface and the J1939 address this socket is bound to.
+	pub fn local_addr(&self) -> std::io::Result<(CanInterface, J1939Address)> {
+		let (interface, address) = self.io.get_ref().local_addr()?;
+		Ok((CanInterface { inner: interface }, address))
+	}
+
+	/// Get the address this socket is connected to.
+	pub fn peer_addr(&self) -> std::io::Result<J1939Address> {
+		self.io.get_ref().peer_addr()
+	}
+
+	/// Send a message to the destination the socket is connected to.
+	pub async fn send(&self, data: &[u8]) -> std::io::Result<()> {
+		write_io(&self.io, |inner| {
+			inner.send(data)
+		}).await
+	}
+
+	/// Send a message to the destination the socket is connected to with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn send_timeout(&self, data: &[u8], timeout: impl Deadline) -> std::io::Result<()> {
+		timeout_at(timeout, self.send(data)).await
+	}
+
+	/// Try to send a message to the destination the socket is connected to without waiting for the socket to become writable.
+	pub fn try_send(&self, data: &[u8]) -> std::io::Result<()> {
+		try_write_io(&self.io, |inner| {
+			inner.send(data)
+		})
+	}
+
+	/// Send a message to a specific destination.
+	///
+	/// The PGN of the address is used as PGN for the message.
+	/// To send to the global address ([`NO_ADDR`][crate::j1939::NO_ADDR]), broadcast must be enabled with [`Self::set_broadcast()`].
+	pub async fn send_to(&self, data: &[u8], address: &J1939Address) -> std::io::Result<()> {
+		write_io(&self.io, |inner| {
+			inner.send_to(data, address)
+		}).await
+	}
+
+	/// Send a message to a specific destination with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn send_to_timeout(&self, data: &[u8], address: &J1939Address, timeout: impl Deadline) -> std::io::Result<()> {
+		timeout_at(timeout, self.send_to(data, address)).await
+	}
+
+	/// Try to send a message to a specific destination without waiting for the socket to become writable.
+	pub fn try_send_to(&self, data: &[u8], address: &J1939Address) -> std::io::Result<()> {
+		try_write_io(&self.io, |inner| {
+			inner.send_to(data, address)
+		})
+	}
+
+	/// Receive a message from the socket.
+	///
+	/// Messages larger than [`MAX_TP_MESSAGE_SIZE`][crate::j1939::MAX_TP_MESSAGE_SIZE] bytes are discarded and reported as an error.
+	/// Use [`Self::recv_into()`] to receive larger messages sent with the extended transport protocol.
+	pub async fn recv(&self) -> std::io::Result<J1939Message> {
+		read_io(&self.io, |inner| {
+			inner.recv()
+		}).await
+	}
+
+	/// Receive a message from the socket with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<J1939Message> {
+		timeout_at(timeout, self.recv()).await
+	}
+
+	/// Receive a message from the socket, without waiting for one to become available.
+	pub fn try_recv(&self) -> std::io::Result<J1939Message> {
+		try_read_io(&self.io, |inner| {
+			inner.recv()
+		})
+	}
+
+	/// Receive a message from the socket into a buffer.
+	///
+	/// Returns the length of the message and its addresses.
+	/// Messages that do not fit in the buffer are discarded and reported as an error.
+	pub async fn recv_into(&self, buffer: &mut [u8]) -> std::io::Result<J1939MessageInfo> {
+		read_io(&self.io, |inner| {
+			inner.recv_into(buffer)
+		}).await
+	}
+
+	/// Receive a message from the socket into a buffer with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	pub async fn recv_into_timeout(&self, buffer: &mut [u8], timeout: impl Deadline) -> std::io::Result<J1939MessageInfo> {
+		timeout_at(timeout, self.recv_into(buffer)).await
+	}
+
+	/// Receive a message from the socket into a buffer, without waiting for one to become available.
+	pub fn try_recv_into(&self, buffer: &mut [u8]) -> std::io::Result<J1939MessageInfo> {
+		try_read_io(&self.io, |inner| {
+			inner.recv_into(buffer)
+		})
+	}
+
+	/// Check if promiscuous mode is enabled on the socket.
+	pub fn get_promiscuous(&self) -> std::io::Result<bool> {
+		self.io.get_ref().get_promiscuous()
+	}
+
+	/// Enable or disable promiscuous mode.
+	///
+	/// In promiscuous mode, the socket receives all messages on the bus,
+	/// regardless of the destination address and the PGN the socket is bound to.
+	pub fn set_promiscuous(&self, enable: bool) -> std::io::Result<()> {
+		self.io.get_ref().set_promiscuous(enable)
+	}
+
+	/// Get the priority of sent messages.
+	pub fn get_send_priority(&self) -> std::io::Result<u8> {
+		self.io.get_ref().get_send_priority()
+	}
+
+	/// Set the priority of sent messages (0 is the highest priority, 7 the lowest).
+	///
+	/// The default priority is 6.
+	/// Setting a priority of 0 or 1 requires the `CAP_NET_ADMIN` capability.
+	pub fn set_send_priority(&self, priority: u8) -> std::io::Result<()> {
+		self.io.get_ref().set_send_priority(priority)
+	}
+
+	/// Check if sending to the global address is allowed.
+	pub fn get_broadcast(&self) -> std::io::Result<bool> {
+		self.io.get_ref().get_broadcast()
+	}
+
+	/// Allow or disallow sending messages to the global address.
+	pub fn set_broadcast(&self, enable: bool) -> std::io::Result<()> {
+		self.io.get_ref().set_broadcast(enable)
+	}
+}
+
+impl std::os::fd::AsFd for J1939Socket {
+	fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+		self.io.as_fd()
+	}
+}
+
+impl From<J1939Socket> for std::os::fd::OwnedFd {
+	fn from(value: J1939Socket) -> Self {
+		value.io.into_inner().into()
+	}
+}
+
+impl TryFrom<std::os::fd::OwnedFd> for J1939Socket {
+	type Error = std::io::Error;
+
+	fn try_from(value: std::os::fd::OwnedFd) -> std::io::Result<Self> {
+		let io = AsyncFd::new(sys::J1939Socket::from(value))?;
+		Ok(Self { io })
+	}
+}
+
+impl std::os::fd::AsRawFd for J1939Socket {
+	fn as_raw_fd(&self) -> std::os::fd::RawFd {
+		self.io.as_raw_fd()
+	}
+}
+
+impl std::os::fd::IntoRawFd for J1939Socket {
+	fn into_raw_fd(self) -> std::os::fd::RawFd {
+		self.io.into_inner().into_raw_fd()
+	}
+}
@@ -1,4 +1,27 @@
 //! Support for [`tokio`].
 
+mod io;
+
 mod socket;
 pub use socket::CanSocket;
+
+mod split;
+pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, WriteHalf};
+
+mod isotp;
+pub use isotp::IsoTpSocket;
+
+mod j1939;
+pub use j1939::J1939Socket;
+
+mod bcm;
+pub use bcm::BcmSocket;
+
+mod replay;
+pub use replay::TraceReplayer;
+
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "futures")))]
+pub use stream::{BcmStream, RecvFromStream, RecvStream, SendSink};
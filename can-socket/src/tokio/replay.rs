@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use crate::log::LogEntry;
+use crate::tokio::CanSocket;
+use crate::AnyCanFrame;
+use crate::CanFilter;
+use crate::CanInterface;
+use crate::Deadline;
+
+/// Replays a recorded trace on a [`CanSocket`], honoring the time between the frames.
+///
+/// The trace is a slice of [`LogEntry`], as read by the readers in the [`crate::log`] module.
+/// The first replayed frame is sent immediately, and each following frame is sent at the same offset from that frame as in the trace.
+/// Frames that are skipped because of the filters or the interface mapping do not delay the replay.
+///
+/// The deadlines are computed with the [`Deadline`] trait,
+/// so [`tokio::time::pause()`] and [`tokio::time::advance()`] work as expected.
+///
+/// Error frames can not be sent on a socket, so they are always skipped.
+#[derive(Debug, Clone)]
+pub struct TraceReplayer {
+	speed: f64,
+	looping: bool,
+	interfaces: Vec<(String, CanInterface)>,
+	filters: Vec<CanFilter>,
+}
+
+impl TraceReplayer {
+	/// Create a new replayer with the default options.
+	///
+	/// By default, the trace is replayed once at the original speed,
+	/// all frames are sent on the interface the socket is bound to.
+	pub fn new() -> Self {
+		Self {
+			speed: 1.0,
+			looping: false,
+			interfaces: Vec::new(),
+			filters: Vec::new(),
+		}
+	}
+
+	/// Set the speed factor of the replay.
+	///
+	/// A speed of `2.0` replays the trace twice as fast as it was recorded.
+	///
+	/// # Panics
+	/// This function panics if the speed is not a finite number larger than zero.
+	#[must_use = "this function returns a new replayer, it does not modify self"]
+	pub fn with_speed(mut self, speed: f64) -> Self {
+		assert!(speed.is_finite() && speed > 0.0, "replay speed must be a finite number larger than zero, got {speed}");
+		self.speed = speed;
+		self
+	}
+
+	/// Replay the trace in a loop until the future is dropped or an error occurs.
+	///
+	/// The first frame of the next iteration is sent at the same time as the last frame of the previous iteration.
+	#[must_use = "this function returns a new replayer, it does not modify self"]
+	pub fn with_loop(mut self, looping: bool) -> Self {
+		self.looping = looping;
+		self
+	}
+
+	/// Send the frames recorded on the interface named `trace_interface` to `interface`.
+	///
+	/// If any interface is mapped, frames from interfaces that are not mapped are skipped.
+	/// If no interface is mapped, all frames are sent on the interface the socket is bound to.
+	#[must_use = "this function returns a new replayer, it does not modify self"]
+	pub fn with_interface(mut self, trace_interface: impl Into<String>, interface: CanInterface) -> Self {
+		let trace_interface = trace_interface.into();
+		self.interfaces.retain(|(name, _)| *name != trace_interface);
+		self.interfaces.push((trace_interface, interface));
+		self
+	}
+
+	/// Only replay the frames that match at least one of the filters.
+	///
+	/// If no filters are set, all frames are replayed.
+	/// CAN XL frames never match a filter, so they are skipped if any filter is set.
+	#[must_use = "this function returns a new replayer, it does not modify self"]
+	pub fn with_filters(mut self, filters: impl Into<Vec<CanFilter>>) -> Self {
+		self.filters = filters.into();
+		self
+	}
+
+	/// Replay a trace on a socket.
+	///
+	/// If looping is enabled, this only returns when an error occurs,
+	/// or when all frames are skipped because of the filters and the interface mapping.
+	///
+	/// This function is cancel safe: the replay simply stops when the future is dropped.
+	pub async fn replay(&self, socket: &CanSocket, trace: &[LogEntry]) -> std::io::Result<()> {
+		// The time base is the first entry that is actually replayed.
+		let Some(first) = trace.iter().find(|entry| self.is_replayed(entry)) else {
+			return Ok(());
+		};
+		let mut start = Duration::ZERO.deadline();
+
+		loop {
+			let mut offset = Duration::ZERO;
+			for entry in trace {
+				// Use the same check as for the time base, so skipped entries never delay the replay.
+				if !self.is_replayed(entry) {
+					continue;
+				}
+				let interface = self.map_interface(&entry.interface).ok().flatten();
+
+				offset = entry.timestamp.duration_since(first.timestamp).unwrap_or_default().div_f64(self.speed);
+				tokio::time::sleep_until((start + offset).into()).await;
+				send(socket, &entry.frame, interface).await?;
+			}
+
+			if !self.looping {
+				return Ok(());
+			}
+			start += offset;
+		}
+	}
+
+	/// Check if an entry is replayed, or skipped because of the filters, the interface mapping or because it is an error frame.
+	fn is_replayed(&self, entry: &LogEntry) -> bool {
+		!matches!(entry.frame, AnyCanFrame::Error(_))
+			&& self.matches_filters(&entry.frame)
+			&& self.map_interface(&entry.interface).is_ok()
+	}
+
+	fn matches_filters(&self, frame: &AnyCanFrame) -> bool {
+		if self.filters.is_empty() {
+			return true;
+		}
+		match frame {
+			AnyCanFrame::Classic(frame) => self.filters.iter().any(|filter| filter.test(frame)),
+			AnyCanFrame::Fd(frame) => self.filters.iter().any(|filter| filter.test_fd(frame)),
+			AnyCanFrame::Xl(_) => false,
+			AnyCanFrame::Error(_) => false,
+		}
+	}
+
+	/// Get the interface to send a frame from the given trace interface to.
+	///
+	/// Returns `Ok(None)` to send the frame on the interface of the socket,
+	/// and `Err(())` if the frame should be skipped.
+	fn map_interface(&self, trace_interface: &str) -> Result<Option<&CanInterface>, ()> {
+		if self.interfaces.is_empty() {
+			return Ok(None);
+		}
+		self.interfaces.iter()
+			.find(|(name, _)| name == trace_interface)
+			.map(|(_, interface)| Some(interface))
+			.ok_or(())
+	}
+}
+
+impl Default for TraceReplayer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Send a frame on a socket.
+///
+/// Error frames can not be sent, and are ignored.
+async fn send(socket: &CanSocket, frame: &AnyCanFrame, interface: Option<&CanInterface>) -> std::io::Result<()> {
+	match (frame, interface) {
+		(AnyCanFrame::Classic(frame), None) => socket.send(frame).await?,
+		(AnyCanFrame::Classic(frame), Some(interface)) => socket.send_to(frame, interface).await?,
+		(AnyCanFrame::Fd(frame), None) => socket.send_fd(frame).await?,
+		(AnyCanFrame::Fd(frame), Some(interface)) => socket.send_fd_to(frame, interface).await?,
+		(AnyCanFrame::Xl(frame), None) => socket.send_xl(frame).await?,
+		(AnyCanFrame::Xl(frame), Some(interface)) => socket.send_xl_to(frame, interface).await?,
+		(AnyCanFrame::Error(_), _) => (),
+	}
+	Ok(())
+}
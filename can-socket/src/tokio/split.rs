@@ -0,0 +1,290 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::error::ReuniteError;
+use crate::tokio::CanSocket;
+use crate::AnyCanFrame;
+use crate::CanFdFrame;
+use crate::CanFrame;
+use crate::CanInterface;
+use crate::CanXlFrame;
+use crate::Deadline;
+
+/// The receiving half of a [`CanSocket`], borrowed from the socket.
+///
+/// Created by [`CanSocket::split()`].
+/// All receive functions are cancel safe: if a future is dropped before it completes, no frame has been received.
+///
+/// The other functions of the socket are available through [`AsRef<CanSocket>`].
+#[derive(Debug)]
+pub struct ReadHalf<'a> {
+	socket: &'a CanSocket,
+}
+
+/// The sending half of a [`CanSocket`], borrowed from the socket.
+///
+/// Created by [`CanSocket::split()`].
+/// All send functions are cancel safe: if a future is dropped before it completes, the frame has not been sent.
+///
+/// The other functions of the socket are available through [`AsRef<CanSocket>`].
+#[derive(Debug)]
+pub struct WriteHalf<'a> {
+	socket: &'a CanSocket,
+}
+
+/// The receiving half of a [`CanSocket`], which can be moved to a different task.
+///
+/// Created by [`CanSocket::into_split()`].
+/// All receive functions are cancel safe: if a future is dropped before it completes, no frame has been received.
+///
+/// The other functions of the socket are available through [`AsRef<CanSocket>`].
+#[derive(Debug)]
+pub struct OwnedReadHalf {
+	socket: Arc<CanSocket>,
+}
+
+/// The sending half of a [`CanSocket`], which can be moved to a different task.
+///
+/// Created by [`CanSocket::into_split()`].
+/// All send functions are cancel safe: if a future is dropped before it completes, the frame has not been sent.
+///
+/// The other functions of the socket are available through [`AsRef<CanSocket>`].
+#[derive(Debug)]
+pub struct OwnedWriteHalf {
+	socket: Arc<CanSocket>,
+}
+
+pub(crate) fn split(socket: &CanSocket) -> (ReadHalf<'_>, WriteHalf<'_>) {
+	(ReadHalf { socket }, WriteHalf { socket })
+}
+
+pub(crate) fn into_split(socket: CanSocket) -> (OwnedReadHalf, OwnedWriteHalf) {
+	let socket = Arc::new(socket);
+	let read = OwnedReadHalf { socket: socket.clone() };
+	let write = OwnedWriteHalf { socket };
+	(read, write)
+}
+
+fn reunite(read: OwnedReadHalf, write: OwnedWriteHalf) -> Result<CanSocket, ReuniteError> {
+	if !Arc::ptr_eq(&read.socket, &write.socket) {
+		return Err(ReuniteError(read, write));
+	}
+	drop(write);
+	// The halves are not clonable, so `read` now holds the only reference.
+	match Arc::try_unwrap(read.socket) {
+		Ok(socket) => Ok(socket),
+		Err(_) => unreachable!("the halves of a CanSocket hold the only references to the socket"),
+	}
+}
+
+impl OwnedReadHalf {
+	/// Reunite the two halves of a socket.
+	///
+	/// Returns an error with both halves if they do not belong to the same socket.
+	pub fn reunite(self, other: OwnedWriteHalf) -> Result<CanSocket, ReuniteError> {
+		reunite(self, other)
+	}
+}
+
+impl OwnedWriteHalf {
+	/// Reunite the two halves of a socket.
+	///
+	/// Returns an error with both halves if they do not belong to the same socket.
+	pub fn reunite(self, other: OwnedReadHalf) -> Result<CanSocket, ReuniteError> {
+		reunite(other, self)
+	}
+}
+
+macro_rules! impl_read_half {
+	($type:ty) => {
+		impl $type {
+			/// Receive a frame from the socket.
+			///
+			/// See [`CanSocket::recv()`].
+			pub async fn recv(&self) -> std::io::Result<CanFrame> {
+				self.socket.recv().await
+			}
+
+			/// Receive a frame from the socket with a timeout.
+			///
+			/// See [`CanSocket::recv_timeout()`].
+			pub async fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<CanFrame> {
+				self.socket.recv_timeout(timeout).await
+			}
+
+			/// Receive a frame from the socket, without waiting for one to become available.
+			pub fn try_recv(&self) -> std::io::Result<CanFrame> {
+				self.socket.try_recv()
+			}
+
+			/// Poll to receive a frame from the socket.
+			///
+			/// See [`CanSocket::poll_recv()`].
+			pub fn poll_recv(&self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>> {
+				self.socket.poll_recv(context)
+			}
+
+			/// Receive a frame from the socket, including information about which interface the frame was received on.
+			pub async fn recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
+				self.socket.recv_from().await
+			}
+
+			/// Receive a frame from the socket with a timeout, including information about which interface the frame was received on.
+			pub async fn recv_from_timeout(&self, timeout: impl Deadline) -> std::io::Result<(CanFrame, CanInterface)> {
+				self.socket.recv_from_timeout(timeout).await
+			}
+
+			/// Receive a frame from the socket, without waiting for one to become available.
+			pub fn try_recv_from(&self) -> std::io::Result<(CanFrame, CanInterface)> {
+				self.socket.try_recv_from()
+			}
+
+			/// Poll to receive a frame from the socket, including information about which interface the frame was received on.
+			pub fn poll_recv_from(&self, context: &mut Context<'_>) -> Poll<std::io::Result<(CanFrame, CanInterface)>> {
+				self.socket.poll_recv_from(context)
+			}
+
+			/// Receive a frame of any type from the socket.
+			///
+			/// See [`CanSocket::recv_any()`].
+			pub async fn recv_any(&self) -> std::io::Result<AnyCanFrame> {
+				self.socket.recv_any().await
+			}
+
+			/// Receive a frame of any type from the socket with a timeout.
+			pub async fn recv_any_timeout(&self, timeout: impl Deadline) -> std::io::Result<AnyCanFrame> {
+				self.socket.recv_any_timeout(timeout).await
+			}
+
+			/// Receive a frame of any type from the socket, without waiting for one to become available.
+			pub fn try_recv_any(&self) -> std::io::Result<AnyCanFrame> {
+				self.socket.try_recv_any()
+			}
+
+			/// Receive a frame of any type from the socket, including information about which interface the frame was received on.
+			pub async fn recv_any_from(&self) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+				self.socket.recv_any_from().await
+			}
+
+			/// Receive a frame of any type from the socket with a timeout, including information about which interface the frame was received on.
+			pub async fn recv_any_from_timeout(&self, timeout: impl Deadline) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+				self.socket.recv_any_from_timeout(timeout).await
+			}
+
+			/// Receive a frame of any type from the socket, without waiting for one to become available.
+			pub fn try_recv_any_from(&self) -> std::io::Result<(AnyCanFrame, CanInterface)> {
+				self.socket.try_recv_any_from()
+			}
+		}
+
+		impl AsRef<CanSocket> for $type {
+			fn as_ref(&self) -> &CanSocket {
+				&self.socket
+			}
+		}
+	};
+}
+
+macro_rules! impl_write_half {
+	($type:ty) => {
+		impl $type {
+			/// Send a frame over the socket.
+			///
+			/// See [`CanSocket::send()`].
+			pub async fn send(&self, frame: &CanFrame) -> std::io::Result<()> {
+				self.socket.send(frame).await
+			}
+
+			/// Send a frame over the socket with a timeout.
+			///
+			/// See [`CanSocket::send_timeout()`].
+			pub async fn send_timeout(&self, frame: &CanFrame, timeout: impl Deadline) -> std::io::Result<()> {
+				self.socket.send_timeout(frame, timeout).await
+			}
+
+			/// Try to send a frame over the socket without waiting for the socket to become writable.
+			pub fn try_send(&self, frame: &CanFrame) -> std::io::Result<()> {
+				self.socket.try_send(frame)
+			}
+
+			/// Poll to send a frame over the socket.
+			///
+			/// See [`CanSocket::poll_send()`].
+			pub fn poll_send(&self, context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>> {
+				self.socket.poll_send(context, frame)
+			}
+
+			/// Send a frame over a particular interface.
+			///
+			/// See [`CanSocket::send_to()`].
+			pub async fn send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.socket.send_to(frame, interface).await
+			}
+
+			/// Send a frame over a particular interface with a timeout.
+			pub async fn send_to_timeout(&self, frame: &CanFrame, interface: &CanInterface, timeout: impl Deadline) -> std::io::Result<()> {
+				self.socket.send_to_timeout(frame, interface, timeout).await
+			}
+
+			/// Try to send a frame over a particular interface without waiting for the socket to become writable.
+			pub fn try_send_to(&self, frame: &CanFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.socket.try_send_to(frame, interface)
+			}
+
+			/// Send a CAN FD frame over the socket.
+			///
+			/// See [`CanSocket::send_fd()`].
+			pub async fn send_fd(&self, frame: &CanFdFrame) -> std::io::Result<()> {
+				self.socket.send_fd(frame).await
+			}
+
+			/// Send a CAN FD frame over the socket with a timeout.
+			pub async fn send_fd_timeout(&self, frame: &CanFdFrame, timeout: impl Deadline) -> std::io::Result<()> {
+				self.socket.send_fd_timeout(frame, timeout).await
+			}
+
+			/// Try to send a CAN FD frame over the socket without waiting for the socket to become writable.
+			pub fn try_send_fd(&self, frame: &CanFdFrame) -> std::io::Result<()> {
+				self.socket.try_send_fd(frame)
+			}
+
+			/// Send a CAN FD frame over a particular interface.
+			pub async fn send_fd_to(&self, frame: &CanFdFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.socket.send_fd_to(frame, interface).await
+			}
+
+			/// Send a CAN XL frame over the socket.
+			///
+			/// See [`CanSocket::send_xl()`].
+			pub async fn send_xl(&self, frame: &CanXlFrame) -> std::io::Result<()> {
+				self.socket.send_xl(frame).await
+			}
+
+			/// Send a CAN XL frame over the socket with a timeout.
+			pub async fn send_xl_timeout(&self, frame: &CanXlFrame, timeout: impl Deadline) -> std::io::Result<()> {
+				self.socket.send_xl_timeout(frame, timeout).await
+			}
+
+			/// Try to send a CAN XL frame over the socket without waiting for the socket to become writable.
+			pub fn try_send_xl(&self, frame: &CanXlFrame) -> std::io::Result<()> {
+				self.socket.try_send_xl(frame)
+			}
+
+			/// Send a CAN XL frame over a particular interface.
+			pub async fn send_xl_to(&self, frame: &CanXlFrame, interface: &CanInterface) -> std::io::Result<()> {
+				self.socket.send_xl_to(frame, interface).await
+			}
+		}
+
+		impl AsRef<CanSocket> for $type {
+			fn as_ref(&self) -> &CanSocket {
+				&self.socket
+			}
+		}
+	};
+}
+
+impl_read_half!(ReadHalf<'_>);
+impl_read_half!(OwnedReadHalf);
+impl_write_half!(WriteHalf<'_>);
+impl_write_half!(OwnedWriteHalf);
@@ -0,0 +1,178 @@
+use std::borrow::Borrow;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::bcm::BcmEvent;
+use crate::tokio::BcmSocket;
+use crate::tokio::CanSocket;
+use crate::CanFrame;
+use crate::CanInterface;
+
+/// A [`Stream`][futures_core::Stream] of frames received from a [`CanSocket`].
+///
+/// The stream can own the socket, or hold a reference or an [`Arc`][std::sync::Arc] to it.
+/// The stream never ends: each receive error is yielded as an item and the stream continues after it.
+#[derive(Debug)]
+pub struct RecvStream<S = CanSocket> {
+	socket: S,
+}
+
+impl<S: Borrow<CanSocket>> RecvStream<S> {
+	/// Create a stream that receives frames from a socket.
+	pub fn new(socket: S) -> Self {
+		Self { socket }
+	}
+
+	/// Get a reference to the socket.
+	pub fn get_ref(&self) -> &S {
+		&self.socket
+	}
+
+	/// Consume the stream and return the socket.
+	pub fn into_inner(self) -> S {
+		self.socket
+	}
+}
+
+impl<S: Borrow<CanSocket> + Unpin> futures_core::Stream for RecvStream<S> {
+	type Item = std::io::Result<CanFrame>;
+
+	fn poll_next(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.socket.borrow().poll_recv(context).map(Some)
+	}
+}
+
+/// A [`Stream`][futures_core::Stream] of frames received from a [`CanSocket`], together with the interface they were received on.
+///
+/// The stream can own the socket, or hold a reference or an [`Arc`][std::sync::Arc] to it.
+/// The stream never ends: each receive error is yielded as an item and the stream continues after it.
+#[derive(Debug)]
+pub struct RecvFromStream<S = CanSocket> {
+	socket: S,
+}
+
+impl<S: Borrow<CanSocket>> RecvFromStream<S> {
+	/// Create a stream that receives frames from a socket.
+	pub fn new(socket: S) -> Self {
+		Self { socket }
+	}
+
+	/// Get a reference to the socket.
+	pub fn get_ref(&self) -> &S {
+		&self.socket
+	}
+
+	/// Consume the stream and return the socket.
+	pub fn into_inner(self) -> S {
+		self.socket
+	}
+}
+
+impl<S: Borrow<CanSocket> + Unpin> futures_core::Stream for RecvFromStream<S> {
+	type Item = std::io::Result<(CanFrame, CanInterface)>;
+
+	fn poll_next(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.socket.borrow().poll_recv_from(context).map(Some)
+	}
+}
+
+/// A [`Stream`][futures_core::Stream] of notifications received from a [`BcmSocket`].
+///
+/// The stream yields the [`RxChanged`][BcmEvent::RxChanged] and [`RxTimeout`][BcmEvent::RxTimeout] events of the receive jobs,
+/// and the [`TxExpired`][BcmEvent::TxExpired] events of the transmission jobs of the socket.
+///
+/// The stream can own the socket, or hold a reference or an [`Arc`][std::sync::Arc] to it.
+/// The stream never ends: each receive error is yielded as an item and the stream continues after it.
+#[derive(Debug)]
+pub struct BcmStream<S = BcmSocket> {
+	socket: S,
+}
+
+impl<S: Borrow<BcmSocket>> BcmStream<S> {
+	/// Create a stream that receives notifications from a broadcast manager socket.
+	pub fn new(socket: S) -> Self {
+		Self { socket }
+	}
+
+	/// Get a reference to the socket.
+	pub fn get_ref(&self) -> &S {
+		&self.socket
+	}
+
+	/// Consume the stream and return the socket.
+	pub fn into_inner(self) -> S {
+		self.socket
+	}
+}
+
+impl<S: Borrow<BcmSocket> + Unpin> futures_core::Stream for BcmStream<S> {
+	type Item = std::io::Result<BcmEvent>;
+
+	fn poll_next(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.socket.borrow().poll_recv(context).map(Some)
+	}
+}
+
+/// A [`Sink`][futures_sink::Sink] that sends frames over a [`CanSocket`].
+///
+/// The sink can own the socket, or hold a reference or an [`Arc`][std::sync::Arc] to it.
+/// It buffers at most one frame: [`poll_ready()`][futures_sink::Sink::poll_ready] waits until the previous frame has been sent.
+///
+/// If sending a frame fails, the error is returned and the frame is discarded.
+#[derive(Debug)]
+pub struct SendSink<S = CanSocket> {
+	socket: S,
+	pending: Option<CanFrame>,
+}
+
+impl<S: Borrow<CanSocket>> SendSink<S> {
+	/// Create a sink that sends frames over a socket.
+	pub fn new(socket: S) -> Self {
+		Self {
+			socket,
+			pending: None,
+		}
+	}
+
+	/// Get a reference to the socket.
+	pub fn get_ref(&self) -> &S {
+		&self.socket
+	}
+
+	/// Consume the sink and return the socket.
+	///
+	/// A frame that has not been sent yet is discarded.
+	pub fn into_inner(self) -> S {
+		self.socket
+	}
+
+	fn poll_send_pending(&mut self, context: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+		if let Some(frame) = &self.pending {
+			let result = std::task::ready!(self.socket.borrow().poll_send(context, frame));
+			self.pending = None;
+			result?;
+		}
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<S: Borrow<CanSocket> + Unpin> futures_sink::Sink<CanFrame> for SendSink<S> {
+	type Error = std::io::Error;
+
+	fn poll_ready(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.get_mut().poll_send_pending(context)
+	}
+
+	fn start_send(self: Pin<&mut Self>, frame: CanFrame) -> Result<(), Self::Error> {
+		self.get_mut().pending = Some(frame);
+		Ok(())
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.get_mut().poll_send_pending(context)
+	}
+
+	fn poll_close(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.get_mut().poll_send_pending(context)
+	}
+}
@@ -0,0 +1,171 @@
+//! Traits to abstract over sockets and other sources and sinks of CAN frames.
+//!
+//! Code written against these traits can run on a real CAN socket,
+//! or on an endpoint of an in-memory [`virtual_bus`][crate::virtual_bus] in tests.
+//! You can also implement them for your own types, for example to record the frames or to inject faults.
+//!
+//! The blocking traits [`CanTransmit`] and [`CanReceive`] are implemented by [`CanSocket`] and [`BusSocket`].
+//! The async traits [`AsyncCanTransmit`] and [`AsyncCanReceive`] are implemented by the async sockets and their halves.
+//! All traits are also implemented for mutable references and boxes of implementing types.
+
+use std::task::{Context, Poll};
+
+use crate::virtual_bus::BusSocket;
+use crate::{CanFrame, CanSocket};
+
+/// Transmit CAN frames, blocking until the frame is sent.
+pub trait CanTransmit {
+	/// Send a frame.
+	fn send(&mut self, frame: &CanFrame) -> std::io::Result<()>;
+}
+
+/// Receive CAN frames, blocking until a frame is available.
+pub trait CanReceive {
+	/// Receive a frame.
+	fn recv(&mut self) -> std::io::Result<CanFrame>;
+}
+
+/// Asynchronously transmit CAN frames.
+pub trait AsyncCanTransmit {
+	/// Poll to send a frame.
+	///
+	/// If the frame can not be sent yet, the current task is scheduled to be woken up when it can,
+	/// and `Poll::Pending` is returned.
+	/// In that case, the frame has not been sent and you should try again with the same frame when woken up.
+	fn poll_send(&mut self, context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>>;
+}
+
+/// Asynchronously receive CAN frames.
+pub trait AsyncCanReceive {
+	/// Poll to receive a frame.
+	///
+	/// If no frame is available, the current task is scheduled to be woken up when one is,
+	/// and `Poll::Pending` is returned.
+	fn poll_recv(&mut self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>>;
+}
+
+macro_rules! impl_forward {
+	($($type:ty),*) => {
+		$(
+			impl<T: CanTransmit + ?Sized> CanTransmit for $type {
+				fn send(&mut self, frame: &CanFrame) -> std::io::Result<()> {
+					(**self).send(frame)
+				}
+			}
+
+			impl<T: CanReceive + ?Sized> CanReceive for $type {
+				fn recv(&mut self) -> std::io::Result<CanFrame> {
+					(**self).recv()
+				}
+			}
+
+			impl<T: AsyncCanTransmit + ?Sized> AsyncCanTransmit for $type {
+				fn poll_send(&mut self, context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>> {
+					(**self).poll_send(context, frame)
+				}
+			}
+
+			impl<T: AsyncCanReceive + ?Sized> AsyncCanReceive for $type {
+				fn poll_recv(&mut self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>> {
+					(**self).poll_recv(context)
+				}
+			}
+		)*
+	};
+}
+
+impl_forward!(&mut T, Box<T>);
+
+macro_rules! impl_blocking {
+	($($type:ty),*) => {
+		$(
+			impl CanTransmit for $type {
+				fn send(&mut self, frame: &CanFrame) -> std::io::Result<()> {
+					<$type>::send(self, frame)
+				}
+			}
+
+			impl CanReceive for $type {
+				fn recv(&mut self) -> std::io::Result<CanFrame> {
+					<$type>::recv(self)
+				}
+			}
+		)*
+	};
+}
+
+impl_blocking!(CanSocket, BusSocket);
+
+macro_rules! impl_async_transmit {
+	($($type:ty),*) => {
+		$(
+			impl AsyncCanTransmit for $type {
+				fn poll_send(&mut self, context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>> {
+					<$type>::poll_send(self, context, frame)
+				}
+			}
+		)*
+	};
+}
+
+macro_rules! impl_async_receive {
+	($($type:ty),*) => {
+		$(
+			impl AsyncCanReceive for $type {
+				fn poll_recv(&mut self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>> {
+					<$type>::poll_recv(self, context)
+				}
+			}
+		)*
+	};
+}
+
+#[cfg(feature = "tokio")]
+impl_async_transmit!(
+	crate::tokio::CanSocket,
+	crate::tokio::WriteHalf<'_>,
+	crate::tokio::OwnedWriteHalf
+);
+
+#[cfg(feature = "tokio")]
+impl_async_receive!(
+	crate::tokio::CanSocket,
+	crate::tokio::ReadHalf<'_>,
+	crate::tokio::OwnedReadHalf
+);
+
+#[cfg(feature = "async-io")]
+impl_async_transmit!(crate::async_io::CanSocket);
+
+#[cfg(feature = "async-io")]
+impl_async_receive!(crate::async_io::CanSocket);
+
+impl_async_transmit!(crate::virtual_bus::AsyncBusSocket);
+impl_async_receive!(crate::virtual_bus::AsyncBusSocket);
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::virtual_bus::Bus;
+
+	fn forward(from: &mut impl CanReceive, to: &mut impl CanTransmit) -> std::io::Result<()> {
+		let frame = from.recv()?;
+		to.send(&frame)
+	}
+
+	#[test]
+	fn generic_blocking_sockets() {
+		let bus_a = Bus::new();
+		let bus_b = Bus::new();
+		let sender = bus_a.socket();
+		let mut from = bus_a.socket();
+		let mut to: Box<BusSocket> = Box::new(bus_b.socket());
+		let receiver = bus_b.socket();
+
+		let_assert!(Ok(()) = sender.send(&CanFrame::new(1u8, [1, 2])));
+		let_assert!(Ok(()) = forward(&mut &mut from, &mut to));
+		let_assert!(Ok(frame) = receiver.try_recv());
+		assert!(frame.data().unwrap() == [1, 2]);
+	}
+}
@@ -0,0 +1,121 @@
+use std::task::{Context, Poll};
+
+use crate::virtual_bus::Endpoint;
+use crate::CanFilter;
+use crate::CanFrame;
+#[cfg(feature = "tokio")]
+use crate::Deadline;
+
+/// An async socket on a virtual [`Bus`][super::Bus].
+///
+/// Created with [`Bus::async_socket()`][super::Bus::async_socket].
+/// The socket is removed from the bus when it is dropped.
+///
+/// All async functions are cancel safe.
+/// The socket does not depend on a specific async runtime,
+/// but [`Self::recv_timeout()`] requires the `tokio` feature.
+///
+/// Only one task should receive from a socket at a time:
+/// if multiple tasks wait for a frame, only the task that polled the socket last is woken up.
+#[derive(Debug)]
+pub struct AsyncBusSocket {
+	pub(super) endpoint: Endpoint,
+}
+
+impl AsyncBusSocket {
+	/// Send a frame on the bus.
+	///
+	/// The frame is delivered to the receive queue of the other sockets immediately, so this never waits.
+	pub async fn send(&self, frame: &CanFrame) -> std::io::Result<()> {
+		self.try_send(frame)
+	}
+
+	/// Send a frame on the bus without waiting.
+	pub fn try_send(&self, frame: &CanFrame) -> std::io::Result<()> {
+		self.endpoint.send(frame);
+		Ok(())
+	}
+
+	/// Poll to send a frame on the bus.
+	///
+	/// This always returns `Poll::Ready`.
+	pub fn poll_send(&self, _context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>> {
+		Poll::Ready(self.try_send(frame))
+	}
+
+	/// Receive a frame from the bus.
+	pub async fn recv(&self) -> std::io::Result<CanFrame> {
+		std::future::poll_fn(|context| self.poll_recv(context)).await
+	}
+
+	/// Receive a frame from the bus with a timeout.
+	///
+	/// The timeout can be a [`std::time::Duration`], [`std::time::Instant`], [`tokio::time::Instant`] or any other implementator of the [`Deadline`] trait.
+	#[cfg(feature = "tokio")]
+	#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "tokio")))]
+	pub async fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<CanFrame> {
+		let deadline = timeout.deadline().into();
+		tokio::time::timeout_at(deadline, self.recv()).await?
+	}
+
+	/// Receive a frame from the bus, without waiting for one to become available.
+	///
+	/// If no frame is available, an error of kind [`std::io::ErrorKind::WouldBlock`] is returned.
+	pub fn try_recv(&self) -> std::io::Result<CanFrame> {
+		self.endpoint.try_recv()
+	}
+
+	/// Poll to receive a frame from the bus.
+	///
+	/// If no frame is available, the current task is scheduled to be woken up when a frame arrives,
+	/// and `Poll::Pending` is returned.
+	/// Only the waker from the most recent call is woken up.
+	pub fn poll_recv(&self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>> {
+		self.endpoint.poll_recv(context)
+	}
+
+	/// Set the list of filters on the socket.
+	///
+	/// When a socket is created, it will receive all frames from the bus.
+	/// A frame has to match only one of the filters in the list to be received by the socket.
+	pub fn set_filters(&self, filters: &[CanFilter]) -> std::io::Result<()> {
+		self.endpoint.set_filters(filters);
+		Ok(())
+	}
+
+	/// Get the list of filters on the socket.
+	pub fn get_filters(&self) -> std::io::Result<Vec<CanFilter>> {
+		Ok(self.endpoint.get_filters())
+	}
+
+	/// Check if the loopback option of the socket is enabled.
+	///
+	/// When enabled (the default), frames sent on this socket are delivered to the other sockets on the bus.
+	pub fn get_loopback(&self) -> std::io::Result<bool> {
+		Ok(self.endpoint.get_loopback())
+	}
+
+	/// Enable or disable the loopback option of the socket.
+	///
+	/// Like on a `vcan` interface, frames sent with loopback disabled are not received by any socket.
+	pub fn set_loopback(&self, enable: bool) -> std::io::Result<()> {
+		self.endpoint.set_loopback(enable);
+		Ok(())
+	}
+
+	/// Check if the receive own messages option of the socket is enabled.
+	///
+	/// When this option is enabled, frames sent on this socket are also delivered to this socket.
+	pub fn get_receive_own_messages(&self) -> std::io::Result<bool> {
+		Ok(self.endpoint.get_receive_own_messages())
+	}
+
+	/// Enable or disable the receive own messages option of the socket.
+	///
+	/// Frames sent on this socket are only received if the loopback option is enabled too,
+	/// and if they match the filters of the socket.
+	pub fn set_receive_own_messages(&self, enable: bool) -> std::io::Result<()> {
+		self.endpoint.set_receive_own_messages(enable);
+		Ok(())
+	}
+}
@@ -0,0 +1,349 @@
+//! An in-memory CAN bus for testing without CAN interfaces.
+//!
+//! A [`Bus`] behaves like a `vcan` interface that exists only inside the current process.
+//! Frames sent by one socket on the bus are delivered to all other sockets on the same bus,
+//! subject to the same filters, `loopback` and `recv_own_msgs` options as a real CAN socket.
+//!
+//! There is a blocking [`BusSocket`] and an [`AsyncBusSocket`] that works with any async runtime.
+//! Both can be attached to the same bus.
+//! The [`AsyncBusSocket`] implements the traits from the [`transport`][crate::transport] module,
+//! so it can be used in place of a [`tokio::CanSocket`][crate::tokio::CanSocket] by code that is generic over those traits.
+//!
+//! Only classic CAN frames are supported.
+//! Frames are queued without limit, so they are never dropped.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::Waker;
+use std::time::Instant;
+
+use crate::{CanFilter, CanFrame, StandardId};
+
+mod socket;
+pub use socket::BusSocket;
+
+mod async_socket;
+pub use async_socket::AsyncBusSocket;
+
+/// An in-memory CAN bus.
+///
+/// Cloning the bus gives a new handle to the same bus.
+#[derive(Debug, Clone, Default)]
+pub struct Bus {
+	shared: Arc<Shared>,
+}
+
+impl Bus {
+	/// Create a new bus without any sockets.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a new blocking socket on the bus.
+	pub fn socket(&self) -> BusSocket {
+		BusSocket {
+			endpoint: Endpoint::new(self),
+		}
+	}
+
+	/// Create a new async socket on the bus.
+	pub fn async_socket(&self) -> AsyncBusSocket {
+		AsyncBusSocket {
+			endpoint: Endpoint::new(self),
+		}
+	}
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+	state: Mutex<BusState>,
+	condvar: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct BusState {
+	next_id: u64,
+	endpoints: Vec<EndpointState>,
+}
+
+#[derive(Debug)]
+struct EndpointState {
+	id: u64,
+	queue: VecDeque<CanFrame>,
+	filters: Vec<CanFilter>,
+	loopback: bool,
+	receive_own_messages: bool,
+	waker: Option<Waker>,
+}
+
+impl EndpointState {
+	fn accepts(&self, frame: &CanFrame) -> bool {
+		self.filters.iter().any(|filter| filter.test(frame))
+	}
+}
+
+/// The part of a socket that is shared between the blocking and the async socket.
+#[derive(Debug)]
+struct Endpoint {
+	shared: Arc<Shared>,
+	id: u64,
+}
+
+impl Endpoint {
+	fn new(bus: &Bus) -> Self {
+		let mut state = lock(&bus.shared);
+		let id = state.next_id;
+		state.next_id += 1;
+		state.endpoints.push(EndpointState {
+			id,
+			queue: VecDeque::new(),
+			filters: vec![CanFilter::new_standard(StandardId::from(0u8))],
+			loopback: true,
+			receive_own_messages: false,
+			waker: None,
+		});
+		drop(state);
+		Self {
+			shared: bus.shared.clone(),
+			id,
+		}
+	}
+
+	fn with_state<R>(&self, f: impl FnOnce(&mut EndpointState) -> R) -> R {
+		let mut state = lock(&self.shared);
+		f(endpoint_state(&mut state, self.id))
+	}
+
+	fn send(&self, frame: &CanFrame) {
+		let mut wakers = Vec::new();
+		let mut state = lock(&self.shared);
+		let sender = endpoint_state(&mut state, self.id);
+		let (loopback, receive_own_messages) = (sender.loopback, sender.receive_own_messages);
+
+		// Like a `vcan` interface, frames only reach other sockets if loopback is enabled on the sending socket.
+		if loopback {
+			for endpoint in &mut state.endpoints {
+				if endpoint.id == self.id && !receive_own_messages {
+					continue;
+				}
+				if endpoint.accepts(frame) {
+					endpoint.queue.push_back(*frame);
+					wakers.extend(endpoint.waker.take());
+				}
+			}
+		}
+		drop(state);
+
+		self.shared.condvar.notify_all();
+		for waker in wakers {
+			waker.wake();
+		}
+	}
+
+	fn try_recv(&self) -> std::io::Result<CanFrame> {
+		self.with_state(|state| state.queue.pop_front())
+			.ok_or_else(|| std::io::ErrorKind::WouldBlock.into())
+	}
+
+	fn recv_blocking(&self, deadline: Option<Instant>) -> std::io::Result<CanFrame> {
+		let mut state = lock(&self.shared);
+		loop {
+			if let Some(frame) = endpoint_state(&mut state, self.id).queue.pop_front() {
+				return Ok(frame);
+			}
+			state = match deadline {
+				None => self.shared.condvar.wait(state).unwrap_or_else(PoisonError::into_inner),
+				Some(deadline) => {
+					let now = Instant::now();
+					if now >= deadline {
+						return Err(std::io::ErrorKind::TimedOut.into());
+					}
+					self.shared.condvar.wait_timeout(state, deadline - now)
+						.unwrap_or_else(PoisonError::into_inner)
+						.0
+				},
+			};
+		}
+	}
+
+	fn poll_recv(&self, context: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<CanFrame>> {
+		self.with_state(|state| {
+			if let Some(frame) = state.queue.pop_front() {
+				return std::task::Poll::Ready(Ok(frame));
+			}
+			state.waker = Some(context.waker().clone());
+			std::task::Poll::Pending
+		})
+	}
+
+	fn set_filters(&self, filters: &[CanFilter]) {
+		self.with_state(|state| state.filters = filters.to_vec())
+	}
+
+	fn get_filters(&self) -> Vec<CanFilter> {
+		self.with_state(|state| state.filters.clone())
+	}
+
+	fn set_loopback(&self, enable: bool) {
+		self.with_state(|state| state.loopback = enable)
+	}
+
+	fn get_loopback(&self) -> bool {
+		self.with_state(|state| state.loopback)
+	}
+
+	fn set_receive_own_messages(&self, enable: bool) {
+		self.with_state(|state| state.receive_own_messages = enable)
+	}
+
+	fn get_receive_own_messages(&self) -> bool {
+		self.with_state(|state| state.receive_own_messages)
+	}
+}
+
+impl Drop for Endpoint {
+	fn drop(&mut self) {
+		lock(&self.shared).endpoints.retain(|endpoint| endpoint.id != self.id);
+	}
+}
+
+fn lock(shared: &Shared) -> MutexGuard<'_, BusState> {
+	shared.state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn endpoint_state(state: &mut BusState, id: u64) -> &mut EndpointState {
+	state.endpoints.iter_mut()
+		.find(|endpoint| endpoint.id == id)
+		.expect("socket was removed from the bus while it still exists")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::{can_id, CanId};
+
+	#[test]
+	fn frames_reach_other_sockets() {
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.socket();
+		let c = bus.socket();
+
+		let_assert!(Ok(()) = a.send(&CanFrame::new(1u8, [1, 2, 3])));
+		let_assert!(Ok(frame) = b.try_recv());
+		assert!(frame.id() == CanId::new_standard(1).unwrap());
+		assert!(frame.data().unwrap() == [1, 2, 3]);
+		let_assert!(Ok(_) = c.try_recv());
+
+		let_assert!(Err(e) = a.try_recv());
+		assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+		let_assert!(Err(e) = b.try_recv());
+		assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+	}
+
+	#[test]
+	fn filters() {
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.socket();
+		let_assert!(Ok(()) = b.set_filters(&["123:7FF".parse().unwrap()]));
+
+		let_assert!(Ok(()) = a.send(&CanFrame::new(can_id!(0x124), [1])));
+		let_assert!(Ok(()) = a.send(&CanFrame::new(can_id!(0x123), [2])));
+		let_assert!(Ok(frame) = b.try_recv());
+		assert!(frame.data().unwrap() == [2]);
+		let_assert!(Err(_) = b.try_recv());
+
+		let_assert!(Ok(()) = b.set_filters(&[]));
+		let_assert!(Ok(()) = a.send(&CanFrame::new(can_id!(0x123), [3])));
+		let_assert!(Err(_) = b.try_recv());
+	}
+
+	#[test]
+	fn loopback_and_receive_own_messages() {
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.socket();
+
+		let_assert!(Ok(()) = a.set_receive_own_messages(true));
+		let_assert!(Ok(()) = a.send(&CanFrame::new(1u8, [1])));
+		let_assert!(Ok(_) = a.try_recv());
+		let_assert!(Ok(_) = b.try_recv());
+
+		let_assert!(Ok(()) = a.set_loopback(false));
+		let_assert!(Ok(()) = a.send(&CanFrame::new(1u8, [2])));
+		let_assert!(Err(_) = a.try_recv());
+		let_assert!(Err(_) = b.try_recv());
+	}
+
+	#[test]
+	fn blocking_recv() {
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.socket();
+
+		let_assert!(Err(e) = b.recv_timeout(std::time::Duration::from_millis(10)));
+		assert!(e.kind() == std::io::ErrorKind::TimedOut);
+
+		let thread = std::thread::spawn(move || b.recv());
+		let_assert!(Ok(()) = a.send(&CanFrame::new(5u8, [5])));
+		let_assert!(Ok(Ok(frame)) = thread.join());
+		assert!(frame.id() == CanId::new_standard(5).unwrap());
+	}
+
+	#[cfg(feature = "tokio")]
+	#[tokio::test]
+	async fn async_recv() {
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.async_socket();
+
+		let task = tokio::spawn(async move { b.recv().await });
+		tokio::task::yield_now().await;
+		let_assert!(Ok(()) = a.send(&CanFrame::new(6u8, [6])));
+		let_assert!(Ok(Ok(frame)) = task.await);
+		assert!(frame.id() == CanId::new_standard(6).unwrap());
+	}
+
+	#[test]
+	fn async_recv_without_tokio() {
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.async_socket();
+
+		let thread = std::thread::spawn(move || {
+			std::thread::sleep(std::time::Duration::from_millis(10));
+			a.send(&CanFrame::new(7u8, [7]))
+		});
+		let_assert!(Ok(frame) = async_io::block_on(b.recv()));
+		assert!(frame.id() == CanId::new_standard(7).unwrap());
+		let_assert!(Ok(Ok(())) = thread.join());
+	}
+
+	#[test]
+	fn poll_recv_keeps_only_last_waker() {
+		struct CountWakes(std::sync::atomic::AtomicUsize);
+
+		impl std::task::Wake for CountWakes {
+			fn wake(self: Arc<Self>) {
+				self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+			}
+		}
+
+		let bus = Bus::new();
+		let a = bus.socket();
+		let b = bus.async_socket();
+
+		let first = Arc::new(CountWakes(0.into()));
+		let second = Arc::new(CountWakes(0.into()));
+		let first_waker = Waker::from(first.clone());
+		let second_waker = Waker::from(second.clone());
+		assert!(b.poll_recv(&mut std::task::Context::from_waker(&first_waker)).is_pending());
+		assert!(b.poll_recv(&mut std::task::Context::from_waker(&second_waker)).is_pending());
+
+		let_assert!(Ok(()) = a.send(&CanFrame::new(8u8, [8])));
+		assert!(first.0.load(std::sync::atomic::Ordering::Relaxed) == 0);
+		assert!(second.0.load(std::sync::atomic::Ordering::Relaxed) == 1);
+	}
+}
@@ -0,0 +1,87 @@
+use crate::virtual_bus::Endpoint;
+use crate::CanFilter;
+use crate::CanFrame;
+use crate::Deadline;
+
+/// A blocking socket on a virtual [`Bus`][super::Bus].
+///
+/// Created with [`Bus::socket()`][super::Bus::socket].
+/// The socket is removed from the bus when it is dropped.
+#[derive(Debug)]
+pub struct BusSocket {
+	pub(super) endpoint: Endpoint,
+}
+
+impl BusSocket {
+	/// Send a frame on the bus.
+	///
+	/// The frame is delivered to the receive queue of the other sockets immediately, so this never blocks.
+	pub fn send(&self, frame: &CanFrame) -> std::io::Result<()> {
+		self.endpoint.send(frame);
+		Ok(())
+	}
+
+	/// Receive a frame from the bus, waiting until one is available.
+	pub fn recv(&self) -> std::io::Result<CanFrame> {
+		self.endpoint.recv_blocking(None)
+	}
+
+	/// Receive a frame from the bus with a timeout.
+	///
+	/// If no frame is received before the timeout expires, an error of kind [`std::io::ErrorKind::TimedOut`] is returned.
+	pub fn recv_timeout(&self, timeout: impl Deadline) -> std::io::Result<CanFrame> {
+		self.endpoint.recv_blocking(Some(timeout.deadline()))
+	}
+
+	/// Receive a frame from the bus, without waiting for one to become available.
+	///
+	/// If no frame is available, an error of kind [`std::io::ErrorKind::WouldBlock`] is returned.
+	pub fn try_recv(&self) -> std::io::Result<CanFrame> {
+		self.endpoint.try_recv()
+	}
+
+	/// Set the list of filters on the socket.
+	///
+	/// When a socket is created, it will receive all frames from the bus.
+	/// A frame has to match only one of the filters in the list to be received by the socket.
+	pub fn set_filters(&self, filters: &[CanFilter]) -> std::io::Result<()> {
+		self.endpoint.set_filters(filters);
+		Ok(())
+	}
+
+	/// Get the list of filters on the socket.
+	pub fn get_filters(&self) -> std::io::Result<Vec<CanFilter>> {
+		Ok(self.endpoint.get_filters())
+	}
+
+	/// Check if the loopback option of the socket is enabled.
+	///
+	/// When enabled (the default), frames sent on this socket are delivered to the other sockets on the bus.
+	pub fn get_loopback(&self) -> std::io::Result<bool> {
+		Ok(self.endpoint.get_loopback())
+	}
+
+	/// Enable or disable the loopback option of the socket.
+	///
+	/// Like on a `vcan` interface, frames sent with loopback disabled are not received by any socket.
+	pub fn set_loopback(&self, enable: bool) -> std::io::Result<()> {
+		self.endpoint.set_loopback(enable);
+		Ok(())
+	}
+
+	/// Check if the receive own messages option of the socket is enabled.
+	///
+	/// When this option is enabled, frames sent on this socket are also delivered to this socket.
+	pub fn get_receive_own_messages(&self) -> std::io::Result<bool> {
+		Ok(self.endpoint.get_receive_own_messages())
+	}
+
+	/// Enable or disable the receive own messages option of the socket.
+	///
+	/// Frames sent on this socket are only received if the loopback option is enabled too,
+	/// and if they match the filters of the socket.
+	pub fn set_receive_own_messages(&self, enable: bool) -> std::io::Result<()> {
+		self.endpoint.set_receive_own_messages(enable);
+		Ok(())
+	}
+}
@@ -0,0 +1,389 @@
+use crate::{error, CanData, CanFdData, StandardId};
+
+/// The maximum data length of a CAN XL frame.
+pub const MAX_XL_DATA_LEN: usize = 2048;
+
+/// A CAN XL frame as transmitted over a CAN socket.
+///
+/// CAN XL frames carry between 1 and 2048 bytes of data.
+/// Instead of a CAN ID, they have an 11 bit priority ID used for bus arbitration,
+/// an 8 bit SDU type describing the payload, and a 32 bit acceptance field that can be used for filtering.
+///
+/// The Linux kernel also stores an 8 bit virtual CAN network ID (VCID) in the frame.
+///
+/// To send or receive CAN XL frames, you have to enable CAN XL frames on the socket
+/// with [`CanSocket::set_xl_frames()`][crate::CanSocket::set_xl_frames].
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct CanXlFrame {
+	pub(crate) inner: crate::sys::CanXlFrame,
+}
+
+impl CanXlFrame {
+	/// Create a new CAN XL frame with the given priority ID and data payload.
+	///
+	/// The SDU type, acceptance field and virtual CAN network ID are set to zero.
+	/// Use the `with_*()` functions to change them.
+	///
+	/// To create a new CAN XL frame from a potentially invalid data payload,
+	/// use [`Self::try_new()`].
+	#[inline]
+	pub fn new(priority: impl Into<StandardId>, data: impl Into<CanXlData>) -> Self {
+		Self {
+			inner: crate::sys::CanXlFrame::new(priority.into(), &data.into())
+		}
+	}
+
+	/// Create a new CAN XL frame with the given priority ID and data payload.
+	///
+	/// Will report an error if the priority ID or data is invalid.
+	///
+	/// You should normally prefer [`Self::new()`] if you can guarantee that the priority ID and data are valid.
+	#[inline]
+	pub fn try_new<Priority, Data>(priority: Priority, data: Data) -> Result<Self, error::TryNewCanFrameError>
+	where
+		Priority: TryInto<StandardId>,
+		error::TryNewCanFrameError: From<Priority::Error>,
+		Data: TryInto<CanXlData>,
+		error::TryNewCanFrameError: From<Data::Error>,
+	{
+		Ok(Self::new(priority.try_into()?, data.try_into()?))
+	}
+
+	/// Get the priority ID of the frame.
+	#[inline]
+	pub fn priority(&self) -> StandardId {
+		self.inner.priority()
+	}
+
+	/// Set the priority ID of the frame.
+	#[inline]
+	pub fn set_priority(&mut self, priority: impl Into<StandardId>) {
+		self.inner.set_priority(priority.into())
+	}
+
+	/// Get the virtual CAN network ID (VCID) of the frame.
+	#[inline]
+	pub fn virtual_can_id(&self) -> u8 {
+		self.inner.virtual_can_id()
+	}
+
+	/// Set the virtual CAN network ID (VCID) of the frame.
+	///
+	/// Note that the kernel may clear or override the VCID,
+	/// depending on the VCID options of the socket.
+	#[inline]
+	pub fn set_virtual_can_id(&mut self, vcid: u8) {
+		self.inner.set_virtual_can_id(vcid)
+	}
+
+	/// Create a copy of the frame with a different virtual CAN network ID (VCID).
+	#[inline]
+	#[must_use = "this function returns a new frame, it does not modify self"]
+	pub fn with_virtual_can_id(mut self, vcid: u8) -> Self {
+		self.set_virtual_can_id(vcid);
+		self
+	}
+
+	/// Get the SDU (service data unit) type of the frame.
+	///
+	/// The SDU type describes the content of the data payload.
+	#[inline]
+	pub fn sdu_type(&self) -> u8 {
+		self.inner.sdu_type()
+	}
+
+	/// Set the SDU (service data unit) type of the frame.
+	#[inline]
+	pub fn set_sdu_type(&mut self, sdu_type: u8) {
+		self.inner.set_sdu_type(sdu_type)
+	}
+
+	/// Create a copy of the frame with a different SDU (service data unit) type.
+	#[inline]
+	#[must_use = "this function returns a new frame, it does not modify self"]
+	pub fn with_sdu_type(mut self, sdu_type: u8) -> Self {
+		self.set_sdu_type(sdu_type);
+		self
+	}
+
+	/// Get the acceptance field of the frame.
+	#[inline]
+	pub fn acceptance_field(&self) -> u32 {
+		self.inner.acceptance_field()
+	}
+
+	/// Set the acceptance field of the frame.
+	#[inline]
+	pub fn set_acceptance_field(&mut self, acceptance_field: u32) {
+		self.inner.set_acceptance_field(acceptance_field)
+	}
+
+	/// Create a copy of the frame with a different acceptance field.
+	#[inline]
+	#[must_use = "this function returns a new frame, it does not modify self"]
+	pub fn with_acceptance_field(mut self, acceptance_field: u32) -> Self {
+		self.set_acceptance_field(acceptance_field);
+		self
+	}
+
+	/// Check if the simple extended content (SEC) flag is set.
+	#[inline]
+	pub fn simple_extended_content(&self) -> bool {
+		self.inner.simple_extended_content()
+	}
+
+	/// Set or clear the simple extended content (SEC) flag.
+	#[inline]
+	pub fn set_simple_extended_content(&mut self, enable: bool) {
+		self.inner.set_simple_extended_content(enable)
+	}
+
+	/// Create a copy of the frame with the simple extended content (SEC) flag set or cleared.
+	#[inline]
+	#[must_use = "this function returns a new frame, it does not modify self"]
+	pub fn with_simple_extended_content(mut self, enable: bool) -> Self {
+		self.set_simple_extended_content(enable);
+		self
+	}
+
+	/// Get the data of the frame.
+	#[inline]
+	pub fn data(&self) -> CanXlData {
+		self.inner.data()
+	}
+}
+
+impl std::fmt::Debug for CanXlFrame {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CanXlFrame")
+			.field("priority", &format_args!("{:?}", self.priority()))
+			.field("virtual_can_id", &self.virtual_can_id())
+			.field("sdu_type", &format_args!("0x{:02X}", self.sdu_type()))
+			.field("acceptance_field", &format_args!("0x{:08X}", self.acceptance_field()))
+			.field("simple_extended_content", &self.simple_extended_content())
+			.field("data", &format_args!("{:02X?}", self.data()))
+			.finish()
+	}
+}
+
+/// The data payload of a CAN XL frame.
+///
+/// Holds between 1 and 2048 bytes.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CanXlData {
+	pub(crate) data: [u8; MAX_XL_DATA_LEN],
+	pub(crate) len: u16,
+}
+
+impl CanXlData {
+	/// Construct a CAN XL data object from a supported type.
+	///
+	/// Allows construction from any type if it implements [`Into<CanXlData>`].
+	pub fn new(data: impl Into<CanXlData>) -> Self {
+		data.into()
+	}
+
+	/// Construct a CAN XL data object from a slice or other supported type.
+	///
+	/// Allows construction from any type if it implements [`TryInto<CanXlData>`].
+	pub fn try_new<E>(data: impl TryInto<CanXlData, Error = E>) -> Result<Self, E> {
+		data.try_into()
+	}
+
+	/// Get the data as a slice of bytes.
+	#[inline]
+	pub fn as_slice(&self) -> &[u8] {
+		&self.data[..self.len.into()]
+	}
+
+	/// Get the data as a mutable slice of bytes.
+	#[inline]
+	pub fn as_slice_mut(&mut self) -> &mut [u8] {
+		&mut self.data[..self.len.into()]
+	}
+}
+
+impl std::fmt::Debug for CanXlData {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		std::fmt::Debug::fmt(self.as_slice(), f)
+	}
+}
+
+impl std::ops::Deref for CanXlData {
+	type Target = [u8];
+
+	fn deref(&self) -> &Self::Target {
+		self.as_slice()
+	}
+}
+
+impl std::ops::DerefMut for CanXlData {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.as_slice_mut()
+	}
+}
+
+impl std::borrow::Borrow<[u8]> for CanXlData {
+	fn borrow(&self) -> &[u8] {
+		self.as_slice()
+	}
+}
+
+impl std::borrow::BorrowMut<[u8]> for CanXlData {
+	fn borrow_mut(&mut self) -> &mut [u8] {
+		self.as_slice_mut()
+	}
+}
+
+impl AsRef<[u8]> for CanXlData {
+	fn as_ref(&self) -> &[u8] {
+		self.as_slice()
+	}
+}
+
+impl AsMut<[u8]> for CanXlData {
+	fn as_mut(&mut self) -> &mut [u8] {
+		self.as_slice_mut()
+	}
+}
+
+impl PartialEq<[u8]> for CanXlData {
+	fn eq(&self, other: &[u8]) -> bool {
+		self.as_slice() == other
+	}
+}
+
+impl PartialEq<CanXlData> for [u8] {
+	fn eq(&self, other: &CanXlData) -> bool {
+		self == other.as_slice()
+	}
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for CanXlData {
+	fn eq(&self, other: &[u8; N]) -> bool {
+		self.as_slice() == other
+	}
+}
+
+impl<const N: usize> PartialEq<CanXlData> for [u8; N] {
+	fn eq(&self, other: &CanXlData) -> bool {
+		other == self
+	}
+}
+
+impl<const N: usize> TryFrom<[u8; N]> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: [u8; N]) -> Result<Self, Self::Error> {
+		value.as_slice().try_into()
+	}
+}
+
+impl<const N: usize> TryFrom<&[u8; N]> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &[u8; N]) -> Result<Self, Self::Error> {
+		value.as_slice().try_into()
+	}
+}
+
+impl TryFrom<CanData> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: CanData) -> Result<Self, Self::Error> {
+		value.as_slice().try_into()
+	}
+}
+
+impl TryFrom<CanFdData> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: CanFdData) -> Result<Self, Self::Error> {
+		value.as_slice().try_into()
+	}
+}
+
+impl TryFrom<&[u8]> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+		if value.is_empty() || value.len() > MAX_XL_DATA_LEN {
+			Err(error::TryIntoCanDataError {
+				len: value.len(),
+				min_len: 1,
+				max_len: MAX_XL_DATA_LEN,
+			})
+		} else {
+			let mut data = [0; MAX_XL_DATA_LEN];
+			data[..value.len()].copy_from_slice(value);
+			Ok(Self {
+				data,
+				len: value.len() as u16,
+			})
+		}
+	}
+}
+
+impl TryFrom<&Vec<u8>> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &Vec<u8>) -> Result<Self, Self::Error> {
+		value.as_slice().try_into()
+	}
+}
+
+impl TryFrom<&Box<[u8]>> for CanXlData {
+	type Error = error::TryIntoCanDataError;
+
+	fn try_from(value: &Box<[u8]>) -> Result<Self, Self::Error> {
+		let value: &[u8] = value;
+		value.try_into()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn can_xl_frame_fields() {
+		let_assert!(Ok(frame) = CanXlFrame::try_new(0x123u16, [1, 2, 3]));
+		assert!(frame.priority().as_u16() == 0x123);
+		assert!(frame.data() == [1, 2, 3]);
+		assert!(frame.sdu_type() == 0);
+		assert!(frame.acceptance_field() == 0);
+		assert!(frame.virtual_can_id() == 0);
+		assert!(!frame.simple_extended_content());
+
+		let frame = frame
+			.with_sdu_type(0x03)
+			.with_acceptance_field(0xDEAD_BEEF)
+			.with_virtual_can_id(0xAB)
+			.with_simple_extended_content(true);
+		assert!(frame.priority().as_u16() == 0x123);
+		assert!(frame.sdu_type() == 0x03);
+		assert!(frame.acceptance_field() == 0xDEAD_BEEF);
+		assert!(frame.virtual_can_id() == 0xAB);
+		assert!(frame.simple_extended_content());
+
+		let mut frame = frame;
+		frame.set_priority(StandardId::from(0x42u8));
+		assert!(frame.priority().as_u16() == 0x42);
+		assert!(frame.virtual_can_id() == 0xAB);
+	}
+
+	#[test]
+	fn can_xl_data_length() {
+		let_assert!(Err(e) = CanXlData::try_new([]));
+		assert!(e.to_string() == "data to small for CAN frame, expected at least 1 bytes, got 0");
+		let_assert!(Err(e) = CanXlData::try_new(&vec![0; 2049]));
+		assert!(e.to_string() == "data to large for CAN frame, expected at most 2048 bytes, got 2049");
+
+		let_assert!(Ok(data) = CanXlData::try_new(&vec![0xAA; 2048]));
+		assert!(data.len() == 2048);
+		let frame = CanXlFrame::new(1u8, data);
+		assert!(frame.data().as_slice() == [0xAA; 2048]);
+	}
+}
@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use assert2::{assert, let_assert};
+use can_socket::{CanData, CanFrame, CanInterface, CanSocket};
+use can_socket::netlink::{CanLinkKind, NetlinkSocket, OperationalState, VirtualInterface};
+
+fn random_string(len: usize) -> String {
+	use rand::Rng;
+	use rand::distributions::Alphanumeric;
+
+	let mut rng = rand::thread_rng();
+	let mut string = String::with_capacity(len);
+	for _ in 0..len {
+		string.push(char::from(rng.sample(Alphanumeric)));
+	}
+	string
+}
+
+#[derive(Debug)]
+struct TempInterface {
+	name: String,
+}
+
+impl TempInterface {
+	fn new() -> Result<Self, String> {
+		Self::new_with_mtu(None)
+	}
+
+	fn new_with_mtu(mtu: Option<u32>) -> Result<Self, String> {
+		let name = format!("vcan-{}", random_string(10));
+		let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/create-vcan-interface");
+		let output = std::process::Command::new(script)
+			.arg("add")
+			.arg(&name)
+			.args(mtu.map(|mtu| mtu.to_string()))
+			.stdout(std::process::Stdio::piped())
+			.stderr(std::process::Stdio::piped())
+			.stdin(std::process::Stdio::null())
+			.output()
+			.map_err(|e| format!("failed to run `create-vcan-interface add`: {:?}", e.kind()))?;
+		if output.status.success() {
+			Ok(Self { name })
+		} else {
+			if let Ok(output) = std::str::from_utf8(&output.stdout) {
+				let output = output.trim();
+				if !output.is_empty() {
+					println!("stdout of `create-vcan-interface add`:\n {output}\n");
+				}
+			}
+			if let Ok(output) = std::str::from_utf8(&output.stderr) {
+				let output = output.trim();
+				if !output.is_empty() {
+					return Err(output.into());
+				}
+			}
+			Err(format!("ip link add: {:?}", output.status))
+		}
+	}
+
+	fn remove(mut self) -> Result<(), String> {
+		let name = std::mem::take(&mut self.name);
+		if name.is_empty() {
+			return Err("already removed".into());
+		}
+
+		let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/create-vcan-interface");
+		let output = std::process::Command::new(script)
+			.arg("del")
+			.arg(&name)
+			.stdout(std::process::Stdio::piped())
+			.stderr(std::process::Stdio::piped())
+			.stdin(std::process::Stdio::null())
+			.output()
+			.map_err(|e| format!("failed to run `create-vcan-interface del`: {:?}", e.kind()))?;
+		if output.status.success() {
+			Ok(())
+		} else {
+			if let Ok(output) = std::str::from_utf8(&output.stdout) {
+				let output = output.trim();
+				if !output.is_empty() {
+					println!("stdout of `create-vcan-interface del`:\n {output}\n");
+				}
+			}
+			if let Ok(output) = std::str::from_utf8(&output.stderr) {
+				let output = output.trim();
+				if !output.is_empty() {
+					return Err(output.into());
+				}
+			}
+			Err(format!("ip link add: {:?}", output.status))
+		}
+	}
+
+	fn name(&self) -> &str {
+		&self.name
+	}
+}
+
+impl Drop for TempInterface {
+	fn drop(&mut self) {
+		if self.name.is_empty() {
+			return;
+		}
+		let other = Self {
+			name: std::mem::take(&mut self.name),
+		};
+		other.remove().unwrap()
+	}
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn link_up_down() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(can_interface) = CanInterface::from_name(interface.name()));
+	let_assert!(Ok(mut netlink) = NetlinkSocket::new());
+	assert!(let Ok(true) = netlink.is_link_up(&can_interface));
+
+	let_assert!(Ok(()) = netlink.set_link_up(&can_interface, false));
+	assert!(let Ok(false) = netlink.is_link_up(&can_interface));
+
+	let_assert!(Ok(()) = netlink.set_link_up(&can_interface, true));
+	assert!(let Ok(true) = netlink.is_link_up(&can_interface));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn vcan_has_no_can_config() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(can_interface) = CanInterface::from_name(interface.name()));
+	let_assert!(Ok(mut netlink) = NetlinkSocket::new());
+	let_assert!(Ok(config) = netlink.get_can_config(&can_interface));
+	assert!(config == Default::default());
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn list_interfaces() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(fd_interface) = TempInterface::new_with_mtu(Some(72)));
+	let_assert!(Ok(interfaces) = CanInterface::list());
+
+	let_assert!(Some(info) = interfaces.iter().find(|info| info.name() == interface.name()));
+	assert!(info.interface() == &CanInterface::from_name(interface.name()).unwrap());
+	assert!(info.link_kind() == &CanLinkKind::Vcan);
+	assert!(info.operational_state() != OperationalState::Down);
+	assert!(info.mtu() == 16);
+	assert!(!info.supports_fd());
+
+	let_assert!(Some(info) = interfaces.iter().find(|info| info.name() == fd_interface.name()));
+	assert!(info.mtu() == 72);
+	assert!(info.supports_fd());
+	assert!(!info.supports_xl());
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn interface_stats() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(can_interface) = CanInterface::from_name(interface.name()));
+	let_assert!(Ok(stats) = can_interface.stats());
+	assert!(let None = stats.state);
+	let_assert!(Some(link_stats_before) = stats.link_stats);
+
+	let_assert!(Ok(socket) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket.send(&CanFrame::new(1u8, [1, 2, 3])));
+
+	let_assert!(Ok(stats) = can_interface.stats());
+	let_assert!(Some(link_stats_after) = stats.link_stats);
+	assert!(link_stats_after.tx_frames == link_stats_before.tx_frames + 1);
+	assert!(link_stats_after.tx_bytes == link_stats_before.tx_bytes + 3);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn create_delete_vcan() {
+	let name = format!("vcan-{}", random_string(10));
+	let_assert!(Ok(interface) = VirtualInterface::create_vcan_with_mtu(&name, Some(72)));
+	assert!(interface.name() == name);
+	assert!(let None = interface.peer());
+	assert!(let Ok(true) = NetlinkSocket::new().unwrap().is_link_up(interface.interface()));
+
+	let_assert!(Ok(interfaces) = CanInterface::list());
+	let_assert!(Some(info) = interfaces.iter().find(|info| info.name() == name));
+	assert!(info.interface() == interface.interface());
+	assert!(info.link_kind() == &CanLinkKind::Vcan);
+	assert!(info.mtu() == 72);
+
+	assert!(let Ok(()) = interface.delete());
+	assert!(let Err(_) = CanInterface::from_name(&name));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn vcan_deleted_on_drop() {
+	let name = format!("vcan-{}", random_string(10));
+	let_assert!(Ok(interface) = VirtualInterface::create_vcan(&name));
+	assert!(let Ok(_) = CanInterface::from_name(&name));
+	drop(interface);
+	assert!(let Err(_) = CanInterface::from_name(&name));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn vxcan_pair() {
+	let name = format!("vcan-{}", random_string(9));
+	let peer_name = format!("{name}p");
+	let_assert!(Ok(pair) = VirtualInterface::create_vxcan_pair(&name, &peer_name));
+	assert!(pair.peer_name() == Some(peer_name.as_str()));
+	let_assert!(Some(peer) = pair.peer());
+
+	let_assert!(Ok(interfaces) = CanInterface::list());
+	let_assert!(Some(info) = interfaces.iter().find(|info| info.name() == peer_name));
+	assert!(info.interface() == peer);
+	assert!(info.link_kind() == &CanLinkKind::Vxcan);
+
+	let_assert!(Ok(socket_a) = CanSocket::bind(&name));
+	let_assert!(Ok(socket_b) = CanSocket::bind(&peer_name));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(5u8, [1, 2])));
+	let_assert!(Ok(frame) = socket_b.recv());
+	assert!(frame.id().as_u32() == 5);
+	assert!(frame.data() == Some(CanData::new([1, 2])));
+
+	assert!(let Ok(()) = pair.delete());
+	assert!(let Err(_) = CanInterface::from_name(&peer_name));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn gateway_rule() {
+	use can_socket::{CanFilter, StandardId};
+	use can_socket::netlink::{CanGatewayRule, FrameModification, FrameModifications, XorChecksum};
+
+	let_assert!(Ok(interface_a) = TempInterface::new());
+	let_assert!(Ok(interface_b) = TempInterface::new());
+	let_assert!(Ok(source) = CanInterface::from_name(interface_a.name()));
+	let_assert!(Ok(destination) = CanInterface::from_name(interface_b.name()));
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface_a.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface_b.name()));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	let id = StandardId::new(0x100).unwrap();
+	let rule = CanGatewayRule::new(source.clone(), destination.clone())
+		.with_filter(CanFilter::new(id.into()).match_exact_id())
+		.with_modifications(FrameModifications {
+			set: Some(FrameModification::new().with_can_id(StandardId::new(0x200).unwrap())),
+			..Default::default()
+		})
+		.with_xor_checksum(XorChecksum::new(0, 2, 3));
+	let_assert!(Ok(mut netlink) = NetlinkSocket::new());
+	assert!(let Ok(()) = netlink.add_gateway_rule(&rule));
+
+	// Only frames matching the filter are routed.
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(0x101u16, [1, 2, 3, 4])));
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(id, [1, 2, 4, 0])));
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	let_assert!(Ok(frame) = socket_b.recv());
+	assert!(frame.id() == StandardId::new(0x200).unwrap());
+	assert!(frame.data() == Some(CanData::new([1, 2, 4, 7])));
+	let_assert!(Err(e) = socket_b.recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+
+	let_assert!(Ok(rules) = netlink.list_gateway_rules());
+	let_assert!(Some(info) = rules.iter().find(|info| info.rule.source == source));
+	assert!(info.rule.destination == destination);
+	assert!(info.rule.modifications == rule.modifications);
+	assert!(info.rule.xor_checksum == rule.xor_checksum);
+	assert!(info.handled == 1);
+
+	assert!(let Ok(()) = netlink.delete_gateway_rule(&rule));
+	let_assert!(Ok(rules) = netlink.list_gateway_rules());
+	assert!(!rules.iter().any(|info| info.rule.source == source));
+}
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use assert2::{assert, let_assert};
-use can_socket::{CanData, CanFilter, CanFrame, CanSocket, ExtendedId, StandardId};
+use can_socket::{AnyCanFrame, CanData, CanErrorMask, CanFdFrame, CanFilter, CanFrame, CanSocket, CanXlFrame, ExtendedId, StandardId, TimestampMode};
 
 fn random_string(len: usize) -> String {
 	use rand::Rng;
@@ -22,11 +22,24 @@ struct TempInterface {
 
 impl TempInterface {
 	fn new() -> Result<Self, String> {
+		Self::new_with_mtu(None)
+	}
+
+	fn new_fd() -> Result<Self, String> {
+		Self::new_with_mtu(Some(72))
+	}
+
+	fn new_xl() -> Result<Self, String> {
+		Self::new_with_mtu(Some(2060))
+	}
+
+	fn new_with_mtu(mtu: Option<u32>) -> Result<Self, String> {
 		let name = format!("vcan-{}", random_string(10));
 		let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/create-vcan-interface");
 		let output = std::process::Command::new(script)
 			.arg("add")
 			.arg(&name)
+			.args(mtu.map(|mtu| mtu.to_string()))
 			.stdout(std::process::Stdio::piped())
 			.stderr(std::process::Stdio::piped())
 			.stdin(std::process::Stdio::null())
@@ -319,3 +332,484 @@ fn multiple_filters() {
 	let_assert!(Err(e) = socket_b.recv());
 	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
 }
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn can_send_to_recv_from() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	let_assert!(Ok(local_addr) = socket_a.local_addr());
+
+	assert!(let Ok(()) = socket_a.send_to(&CanFrame::new(1u8, [1, 2, 3]), &local_addr));
+	let_assert!(Ok((frame, remote_addr)) = socket_b.recv_from());
+	assert!(frame.id().as_u32() == 1);
+	assert!(frame.data() == Some(CanData::new([1, 2, 3])));
+	assert!(remote_addr == local_addr);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn can_talk_fd() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	assert!(let Ok(false) = socket_a.get_fd_frames());
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(true) = socket_a.get_fd_frames());
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+
+	let data: Vec<u8> = (0..20).collect();
+	let_assert!(Ok(frame) = CanFdFrame::try_new(0x1234u16, &data));
+	assert!(let Ok(()) = socket_a.send_fd(&frame.with_bit_rate_switch(true)));
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [4, 5, 6])));
+
+	let_assert!(Ok(AnyCanFrame::Fd(frame)) = socket_b.recv_any());
+	assert!(frame.id().as_u32() == 0x1234);
+	assert!(frame.bit_rate_switch());
+	assert!(frame.data().len() == 20);
+	assert!(frame.data().as_slice() == data);
+
+	let_assert!(Ok(AnyCanFrame::Classic(frame)) = socket_b.recv_any());
+	assert!(frame.id().as_u32() == 2);
+	assert!(frame.data() == Some(CanData::new([4, 5, 6])));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn recv_rejects_fd_frame() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 12])));
+	let_assert!(Err(e) = socket_b.recv());
+	assert!(e.kind() == std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn filter_fd_frames() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+
+	assert!(let Ok(()) = socket_b.set_filters(&[
+		CanFilter::new(8u8.into()).match_exact_id()
+	]));
+
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 16])));
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(8u8, [2; 16])));
+	let_assert!(Ok((AnyCanFrame::Fd(frame), remote_addr)) = socket_b.recv_any_from());
+	assert!(frame.id().as_u32() == 8);
+	assert!(frame.data() == [2; 16]);
+	let_assert!(Ok(name) = remote_addr.get_name());
+	assert!(name == interface.name());
+
+	let_assert!(Err(e) = socket_b.recv_any());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn can_talk_xl() {
+	let_assert!(Ok(interface) = TempInterface::new_xl());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	assert!(let Ok(false) = socket_a.get_xl_frames());
+	assert!(let Ok(()) = socket_a.set_xl_frames(true));
+	assert!(let Ok(true) = socket_a.get_xl_frames());
+	assert!(let Ok(()) = socket_b.set_xl_frames(true));
+
+	let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+	let_assert!(Ok(frame) = CanXlFrame::try_new(0x123u16, &data));
+	let frame = frame
+		.with_sdu_type(0x03)
+		.with_acceptance_field(0x1234_5678);
+	assert!(let Ok(()) = socket_a.send_xl(&frame));
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [4, 5, 6])));
+
+	let_assert!(Ok(AnyCanFrame::Xl(frame)) = socket_b.recv_any());
+	assert!(frame.priority().as_u16() == 0x123);
+	assert!(frame.sdu_type() == 0x03);
+	assert!(frame.acceptance_field() == 0x1234_5678);
+	assert!(frame.data().as_slice() == data);
+
+	let_assert!(Ok(AnyCanFrame::Classic(frame)) = socket_b.recv_any());
+	assert!(frame.id().as_u32() == 2);
+	assert!(frame.data() == Some(CanData::new([4, 5, 6])));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn error_mask() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket) = CanSocket::bind(interface.name()));
+
+	assert!(let Ok(CanErrorMask::NONE) = socket.get_error_mask());
+	assert!(let Ok(()) = socket.set_error_mask(CanErrorMask::BUS_OFF | CanErrorMask::CONTROLLER));
+	let_assert!(Ok(mask) = socket.get_error_mask());
+	assert!(mask == CanErrorMask::BUS_OFF | CanErrorMask::CONTROLLER);
+	assert!(let Ok(()) = socket.set_error_mask(CanErrorMask::ALL));
+	assert!(let Ok(CanErrorMask::ALL) = socket.get_error_mask());
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn recv_with_timestamp() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	assert!(let Ok(TimestampMode::Disabled) = socket_b.get_timestamp_mode());
+	assert!(let Ok(()) = socket_b.set_timestamp_mode(TimestampMode::Software));
+	assert!(let Ok(TimestampMode::Software) = socket_b.get_timestamp_mode());
+
+	let before = std::time::SystemTime::now();
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(1u8, [1, 2, 3])));
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [4, 5, 6])));
+
+	let_assert!(Ok((frame, timestamps)) = socket_b.recv_with_timestamp());
+	assert!(frame.id().as_u32() == 1);
+	let_assert!(Some(software) = timestamps.software);
+	assert!(software >= before);
+	assert!(software <= std::time::SystemTime::now());
+
+	let_assert!(Ok((frame, metadata)) = socket_b.recv_from_with_metadata());
+	assert!(frame.id().as_u32() == 2);
+	let_assert!(Ok(local_addr) = socket_b.local_addr());
+	assert!(metadata.interface() == &local_addr);
+	let_assert!(Some(software) = metadata.timestamps().software);
+	assert!(software >= before);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn recv_any_with_metadata() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_timestamp_mode(TimestampMode::Software));
+
+	let before = std::time::SystemTime::now();
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 12])));
+
+	let_assert!(Ok((AnyCanFrame::Fd(frame), metadata)) = socket_b.recv_any_with_metadata());
+	assert!(frame.id().as_u32() == 1);
+	assert!(frame.data().as_slice() == [1; 12]);
+	let_assert!(Ok(local_addr) = socket_b.local_addr());
+	assert!(metadata.interface() == &local_addr);
+	let_assert!(Some(software) = metadata.timestamps().software);
+	assert!(software >= before);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn send_recv_batch() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	let_assert!(Ok(expected_interface) = can_socket::CanInterface::from_name(interface.name()));
+
+	let frames: Vec<CanFrame> = (0..5u8).map(|i| CanFrame::new(i, [i, i + 1])).collect();
+	assert!(let Ok(5) = socket_a.send_batch(&frames));
+
+	let mut received = [CanFrame::new(0u8, []); 3];
+	let mut interfaces = [can_socket::CanInterface::from_index(0), can_socket::CanInterface::from_index(0), can_socket::CanInterface::from_index(0)];
+	assert!(let Ok(3) = socket_b.recv_batch_from(&mut received, &mut interfaces));
+	for i in 0..3 {
+		assert!(received[i].id().as_u32() == i as u32);
+		assert!(received[i].data() == Some(CanData::new([i as u8, i as u8 + 1])));
+		assert!(interfaces[i] == expected_interface);
+	}
+
+	assert!(let Ok(2) = socket_b.recv_batch(&mut received));
+	assert!(received[0].id().as_u32() == 3);
+	assert!(received[1].id().as_u32() == 4);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn recv_batch_skips_fd_frames() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+
+	let mut received = [CanFrame::new(0u8, []); 4];
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 12])));
+	let_assert!(Err(e) = socket_b.recv_batch(&mut received));
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 12])));
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [2])));
+	assert!(let Ok(1) = socket_b.recv_batch(&mut received));
+	assert!(received[0].id().as_u32() == 2);
+
+	// The skipped CAN FD frame must not have been written to the unused frames.
+	for frame in &received[1..] {
+		assert!(frame.id().as_u32() == 0);
+		assert!(frame.data() == Some(CanData::new([])));
+	}
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn drop_count() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	assert!(let Ok(false) = socket_b.get_drop_count_enabled());
+	assert!(let Ok(()) = socket_b.set_drop_count_enabled(true));
+	assert!(let Ok(true) = socket_b.get_drop_count_enabled());
+
+	let mut tracker = can_socket::DropTracker::new();
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(1u8, [1, 2, 3])));
+	let_assert!(Ok((frame, metadata)) = socket_b.recv_from_with_metadata());
+	assert!(frame.id().as_u32() == 1);
+	assert!(metadata.drop_count() == 0);
+	assert!(tracker.update(&metadata) == 0);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn get_filters() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket) = CanSocket::bind(interface.name()));
+
+	let filters = [
+		CanFilter::new_standard(0x10.into()).match_exact_id(),
+		CanFilter::new_extended(0x1200u16.into()).match_id_mask(0xFFFFFF00).inverted(true),
+	];
+	assert!(let Ok(()) = socket.set_filters(&filters));
+	let_assert!(Ok(read_back) = socket.get_filters());
+	assert!(read_back.len() == 2);
+	for (read, written) in read_back.iter().zip(&filters) {
+		assert!(read.id() == written.id());
+		assert!(read.id_mask() == written.id_mask());
+		assert!(read.matches_standard_frames() == written.matches_standard_frames());
+		assert!(read.matches_extended_frames() == written.matches_extended_frames());
+		assert!(read.is_inverted() == written.is_inverted());
+	}
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn join_filters() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+
+	// Accept IDs 0x100 to 0x1FF, except 0x120 to 0x12F.
+	assert!(let Ok(()) = socket_b.set_filters(&[
+		CanFilter::new_standard(StandardId::new(0x100).unwrap()).match_id_mask(0x700),
+		CanFilter::new_standard(StandardId::new(0x120).unwrap()).match_id_mask(0x7F0).inverted(true),
+	]));
+	assert!(let Ok(false) = socket_b.get_join_filters());
+	assert!(let Ok(()) = socket_b.set_join_filters(true));
+	assert!(let Ok(true) = socket_b.get_join_filters());
+
+	for id in [0x100u16, 0x125, 0x130, 0x200] {
+		assert!(let Ok(()) = socket_a.send(&CanFrame::new(StandardId::new(id).unwrap(), [1])));
+	}
+
+	let_assert!(Ok(frame) = socket_b.recv());
+	assert!(frame.id().as_u32() == 0x100);
+	let_assert!(Ok(frame) = socket_b.recv());
+	assert!(frame.id().as_u32() == 0x130);
+	let_assert!(Err(e) = socket_b.recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn isotp_talk() {
+	use can_socket::IsoTpSocket;
+	use can_socket::isotp::{IsoTpConfig, IsoTpFlags};
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let mut config = IsoTpConfig::default();
+	config.options.flags = IsoTpFlags::TX_PADDING;
+	let_assert!(Ok(socket_a) = IsoTpSocket::bind_with_config(interface.name(), 0x7E8u16, 0x7E0u16, &config));
+	let_assert!(Ok(socket_b) = IsoTpSocket::bind(interface.name(), 0x7E0u16, 0x7E8u16));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	let_assert!(Ok(options) = socket_a.get_options());
+	assert!(options.flags == IsoTpFlags::TX_PADDING);
+
+	// A single frame message.
+	assert!(let Ok(()) = socket_a.send(&[1, 2, 3]));
+	std::thread::sleep(std::time::Duration::from_millis(10));
+	let_assert!(Ok(message) = socket_b.recv());
+	assert!(message == [1, 2, 3]);
+
+	// A segmented message, which requires flow control from the other socket.
+	let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+	assert!(let Ok(()) = socket_b.send(&data));
+	std::thread::sleep(std::time::Duration::from_millis(200));
+	let_assert!(Ok(message) = socket_a.recv());
+	assert!(message == data);
+
+	let_assert!(Err(e) = socket_a.recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn j1939_talk() {
+	use can_socket::J1939Socket;
+	use can_socket::j1939::{J1939Address, J1939Id, NO_ADDR};
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(raw) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_a) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x20)));
+	let_assert!(Ok(socket_b) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x30)));
+	assert!(let Ok(()) = raw.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.set_nonblocking(true));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	let_assert!(Ok((_, local)) = socket_a.local_addr());
+	assert!(local.address == 0x20);
+
+	// A single frame message to a specific destination.
+	let destination = J1939Address::from_address(0x30).with_pgn(0x0EF00);
+	assert!(let Ok(()) = socket_a.send_to(&[1, 2, 3], &destination));
+	let_assert!(Ok(message) = socket_b.recv());
+	assert!(message.data == [1, 2, 3]);
+	assert!(message.source.address == 0x20);
+	assert!(message.source.pgn == 0x0EF00);
+	assert!(message.destination_address == Some(0x30));
+	assert!(message.priority == Some(6));
+
+	let_assert!(Ok(frame) = raw.recv());
+	let_assert!(Some(id) = frame.id().as_extended());
+	let id = J1939Id::from(id);
+	assert!(id.priority() == 6);
+	assert!(id.pgn() == 0x0EF00);
+	assert!(id.destination_address() == Some(0x30));
+	assert!(id.source_address() == 0x20);
+
+	// A broadcast message.
+	assert!(let Ok(()) = socket_b.set_broadcast(true));
+	assert!(let Ok(()) = socket_b.set_send_priority(3));
+	assert!(let Ok(()) = socket_b.send_to(&[4, 5], &J1939Address::from_address(NO_ADDR).with_pgn(0x0FEF1)));
+	let_assert!(Ok(message) = socket_a.recv());
+	assert!(message.data == [4, 5]);
+	assert!(message.source.address == 0x30);
+	assert!(message.source.pgn == 0x0FEF1);
+	assert!(message.priority == Some(3));
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn j1939_recv_into() {
+	use can_socket::J1939Socket;
+	use can_socket::j1939::J1939Address;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x20)));
+	let_assert!(Ok(socket_b) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x30)));
+	assert!(let Ok(()) = socket_b.set_nonblocking(true));
+	assert!(let Ok(()) = socket_a.connect(&J1939Address::from_address(0x30).with_pgn(0x0EF00)));
+
+	let mut buffer = [0; 8];
+	assert!(let Ok(()) = socket_a.send(&[1, 2, 3]));
+	let_assert!(Ok(info) = socket_b.recv_into(&mut buffer));
+	assert!(buffer[..info.len] == [1, 2, 3]);
+	assert!(info.source.address == 0x20);
+	assert!(info.destination_address == Some(0x30));
+
+	// Messages that do not fit in the buffer are reported as an error.
+	assert!(let Ok(()) = socket_a.send(&[1; 5]));
+	let_assert!(Err(e) = socket_b.recv_into(&mut buffer[..4]));
+	assert!(e.kind() == std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn bcm_cyclic_transmission() {
+	use can_socket::BcmSocket;
+	use can_socket::bcm::TxSetup;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(bcm) = BcmSocket::connect(interface.name()));
+	let_assert!(Ok(raw) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = raw.set_nonblocking(true));
+
+	let id = StandardId::new(0x80).unwrap();
+	let setup = TxSetup::new(CanFrame::new(id, [1, 2]))
+		.with_interval(std::time::Duration::from_millis(10));
+	assert!(let Ok(()) = bcm.tx_setup(&setup));
+	std::thread::sleep(std::time::Duration::from_millis(55));
+	assert!(let Ok(()) = bcm.tx_delete(id));
+
+	let mut count = 0;
+	while let Ok(frame) = raw.recv() {
+		assert!(frame.id() == id);
+		assert!(frame.data() == Some(CanData::new([1, 2])));
+		count += 1;
+	}
+	assert!(count >= 3);
+
+	std::thread::sleep(std::time::Duration::from_millis(30));
+	let_assert!(Err(e) = raw.recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+fn bcm_change_detection() {
+	use can_socket::BcmSocket;
+	use can_socket::bcm::{BcmEvent, RxSetup};
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(bcm) = BcmSocket::connect(interface.name()));
+	let_assert!(Ok(raw) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = bcm.set_nonblocking(true));
+
+	let id = StandardId::new(0x123).unwrap();
+	let setup = RxSetup::new(id)
+		.with_mask([0xFF, 0, 0, 0, 0, 0, 0, 0]);
+	assert!(let Ok(()) = bcm.rx_setup(&setup));
+
+	// Only changes of the first byte should be reported.
+	assert!(let Ok(()) = raw.send(&CanFrame::new(id, [1, 1])));
+	assert!(let Ok(()) = raw.send(&CanFrame::new(id, [1, 2])));
+	assert!(let Ok(()) = raw.send(&CanFrame::new(id, [2, 2])));
+	std::thread::sleep(std::time::Duration::from_millis(10));
+
+	let_assert!(Ok(BcmEvent::RxChanged(frame)) = bcm.recv());
+	assert!(frame.data() == Some(CanData::new([1, 1])));
+	let_assert!(Ok(BcmEvent::RxChanged(frame)) = bcm.recv());
+	assert!(frame.data() == Some(CanData::new([2, 2])));
+	let_assert!(Err(e) = bcm.recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
@@ -2,7 +2,7 @@ use std::path::Path;
 use std::time::Duration;
 
 use assert2::{assert, let_assert};
-use can_socket::{CanData, CanFilter, CanFrame, ExtendedId, StandardId};
+use can_socket::{AnyCanFrame, CanData, CanErrorMask, CanFdFrame, CanFilter, CanFrame, CanXlFrame, ExtendedId, StandardId, TimestampMode};
 use can_socket::tokio::CanSocket;
 
 fn random_string(len: usize) -> String {
@@ -24,11 +24,24 @@ struct TempInterface {
 
 impl TempInterface {
 	fn new() -> Result<Self, String> {
+		Self::new_with_mtu(None)
+	}
+
+	fn new_fd() -> Result<Self, String> {
+		Self::new_with_mtu(Some(72))
+	}
+
+	fn new_xl() -> Result<Self, String> {
+		Self::new_with_mtu(Some(2060))
+	}
+
+	fn new_with_mtu(mtu: Option<u32>) -> Result<Self, String> {
 		let name = format!("vcan-{}", random_string(10));
 		let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/create-vcan-interface");
 		let output = std::process::Command::new(script)
 			.arg("add")
 			.arg(&name)
+			.args(mtu.map(|mtu| mtu.to_string()))
 			.stdout(std::process::Stdio::piped())
 			.stderr(std::process::Stdio::piped())
 			.stdin(std::process::Stdio::null())
@@ -155,6 +168,62 @@ async fn can_try_send_try_recv() {
 	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
 }
 
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn stream_and_sink() {
+	use futures::{SinkExt, StreamExt};
+	use can_socket::tokio::{RecvFromStream, RecvStream, SendSink};
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	let mut sink = SendSink::new(&socket_a);
+	let mut stream = RecvStream::new(&socket_b);
+	assert!(let Ok(()) = sink.send(CanFrame::new(1u8, [1, 2, 3])).await);
+	assert!(let Ok(()) = sink.send(CanFrame::new(2u8, [4, 5, 6])).await);
+	let_assert!(Some(Ok(frame)) = stream.next().await);
+	assert!(frame.id().as_u32() == 1);
+	assert!(frame.data() == Some(CanData::new([1, 2, 3])));
+	let_assert!(Some(Ok(frame)) = stream.next().await);
+	assert!(frame.id().as_u32() == 2);
+
+	let_assert!(Ok(local_addr) = socket_b.local_addr());
+	let mut stream = RecvFromStream::new(socket_b);
+	assert!(let Ok(()) = sink.send(CanFrame::new(3u8, [7])).await);
+	let_assert!(Some(Ok((frame, interface))) = stream.next().await);
+	assert!(frame.id().as_u32() == 3);
+	assert!(interface.index() == local_addr.index());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn split_and_reunite() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	let (read_a, write_a) = socket_a.split();
+	let (read_b, write_b) = socket_b.into_split();
+	let task = tokio::spawn(async move {
+		let frame = read_b.recv().await?;
+		write_b.send(&CanFrame::new(frame.id(), [4, 5, 6])).await?;
+		Ok::<_, std::io::Error>((read_b, write_b))
+	});
+
+	assert!(let Ok(()) = write_a.send(&CanFrame::new(1u8, [1, 2, 3])).await);
+	let_assert!(Ok(frame) = read_a.recv().await);
+	assert!(frame.id().as_u32() == 1);
+	assert!(frame.data() == Some(CanData::new([4, 5, 6])));
+
+	let_assert!(Ok(Ok((read_b, write_b))) = task.await);
+	let_assert!(Ok(socket_c) = CanSocket::bind(interface.name()));
+	let (read_c, write_c) = socket_c.into_split();
+	let_assert!(Err(can_socket::error::ReuniteError(read_b, write_c)) = read_b.reunite(write_c));
+	assert!(let Ok(_) = write_b.reunite(read_b));
+	assert!(let Ok(_) = read_c.reunite(write_c));
+}
+
 #[tokio::test]
 #[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
 async fn local_addr() {
@@ -329,3 +398,499 @@ async fn multiple_filters() {
 	let_assert!(Err(e) = socket_b.recv_timeout(Duration::from_millis(1)).await);
 	assert!(e.kind() == std::io::ErrorKind::TimedOut);
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn can_send_to_recv_from() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(local_addr) = socket_a.local_addr());
+
+	assert!(let Ok(()) = socket_a.send_to(&CanFrame::new(1u8, [1, 2, 3]), &local_addr).await);
+	let_assert!(Ok((frame, remote_addr)) = socket_b.recv_from().await);
+	assert!(frame.id().as_u32() == 1);
+	assert!(frame.data() == Some(CanData::new([1, 2, 3])));
+	assert!(remote_addr == local_addr);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn can_talk_fd() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	assert!(let Ok(false) = socket_a.get_fd_frames());
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(true) = socket_a.get_fd_frames());
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+
+	let data: Vec<u8> = (0..20).collect();
+	let_assert!(Ok(frame) = CanFdFrame::try_new(0x1234u16, &data));
+	assert!(let Ok(()) = socket_a.send_fd(&frame.with_bit_rate_switch(true)).await);
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [4, 5, 6])).await);
+
+	let_assert!(Ok(AnyCanFrame::Fd(frame)) = socket_b.recv_any().await);
+	assert!(frame.id().as_u32() == 0x1234);
+	assert!(frame.bit_rate_switch());
+	assert!(frame.data().len() == 20);
+	assert!(frame.data().as_slice() == data);
+
+	let_assert!(Ok(AnyCanFrame::Classic(frame)) = socket_b.recv_any().await);
+	assert!(frame.id().as_u32() == 2);
+	assert!(frame.data() == Some(CanData::new([4, 5, 6])));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn filter_fd_frames() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+
+	assert!(let Ok(()) = socket_b.set_filters(&[
+		CanFilter::new(8u8.into()).match_exact_id()
+	]));
+
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 16])).await);
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(8u8, [2; 16])).await);
+	let_assert!(Ok((AnyCanFrame::Fd(frame), remote_addr)) = socket_b.recv_any_from().await);
+	assert!(frame.id().as_u32() == 8);
+	assert!(frame.data() == [2; 16]);
+	let_assert!(Ok(name) = remote_addr.get_name());
+	assert!(name == interface.name());
+
+	let_assert!(Err(e) = socket_b.recv_any_timeout(Duration::from_millis(1)).await);
+	assert!(e.kind() == std::io::ErrorKind::TimedOut);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn can_talk_xl() {
+	let_assert!(Ok(interface) = TempInterface::new_xl());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	assert!(let Ok(false) = socket_a.get_xl_frames());
+	assert!(let Ok(()) = socket_a.set_xl_frames(true));
+	assert!(let Ok(true) = socket_a.get_xl_frames());
+	assert!(let Ok(()) = socket_b.set_xl_frames(true));
+
+	let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+	let_assert!(Ok(frame) = CanXlFrame::try_new(0x123u16, &data));
+	let frame = frame
+		.with_sdu_type(0x03)
+		.with_acceptance_field(0x1234_5678);
+	assert!(let Ok(()) = socket_a.send_xl(&frame).await);
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [4, 5, 6])).await);
+
+	let_assert!(Ok(AnyCanFrame::Xl(frame)) = socket_b.recv_any().await);
+	assert!(frame.priority().as_u16() == 0x123);
+	assert!(frame.sdu_type() == 0x03);
+	assert!(frame.acceptance_field() == 0x1234_5678);
+	assert!(frame.data().as_slice() == data);
+
+	let_assert!(Ok(AnyCanFrame::Classic(frame)) = socket_b.recv_any().await);
+	assert!(frame.id().as_u32() == 2);
+	assert!(frame.data() == Some(CanData::new([4, 5, 6])));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn error_mask() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket) = CanSocket::bind(interface.name()));
+
+	assert!(let Ok(CanErrorMask::NONE) = socket.get_error_mask());
+	assert!(let Ok(()) = socket.set_error_mask(CanErrorMask::BUS_OFF | CanErrorMask::CONTROLLER));
+	let_assert!(Ok(mask) = socket.get_error_mask());
+	assert!(mask == CanErrorMask::BUS_OFF | CanErrorMask::CONTROLLER);
+	assert!(let Ok(()) = socket.set_error_mask(CanErrorMask::ALL));
+	assert!(let Ok(CanErrorMask::ALL) = socket.get_error_mask());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn recv_with_timestamp() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	assert!(let Ok(TimestampMode::Disabled) = socket_b.get_timestamp_mode());
+	assert!(let Ok(()) = socket_b.set_timestamp_mode(TimestampMode::Software));
+	assert!(let Ok(TimestampMode::Software) = socket_b.get_timestamp_mode());
+
+	let before = std::time::SystemTime::now();
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(1u8, [1, 2, 3])).await);
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(2u8, [4, 5, 6])).await);
+
+	let_assert!(Ok((frame, timestamps)) = socket_b.recv_with_timestamp().await);
+	assert!(frame.id().as_u32() == 1);
+	let_assert!(Some(software) = timestamps.software);
+	assert!(software >= before);
+	assert!(software <= std::time::SystemTime::now());
+
+	let_assert!(Ok((frame, metadata)) = socket_b.recv_from_with_metadata().await);
+	assert!(frame.id().as_u32() == 2);
+	let_assert!(Ok(local_addr) = socket_b.local_addr());
+	assert!(metadata.interface() == &local_addr);
+	let_assert!(Some(software) = metadata.timestamps().software);
+	assert!(software >= before);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn recv_any_with_metadata() {
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	assert!(let Ok(()) = socket_a.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_fd_frames(true));
+	assert!(let Ok(()) = socket_b.set_timestamp_mode(TimestampMode::Software));
+
+	let before = std::time::SystemTime::now();
+	assert!(let Ok(()) = socket_a.send_fd(&CanFdFrame::new(1u8, [1; 12])).await);
+
+	let_assert!(Ok((AnyCanFrame::Fd(frame), metadata)) = socket_b.recv_any_with_metadata().await);
+	assert!(frame.id().as_u32() == 1);
+	assert!(frame.data().as_slice() == [1; 12]);
+	let_assert!(Ok(local_addr) = socket_b.local_addr());
+	assert!(metadata.interface() == &local_addr);
+	let_assert!(Some(software) = metadata.timestamps().software);
+	assert!(software >= before);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn send_recv_batch() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	let_assert!(Ok(expected_interface) = can_socket::CanInterface::from_name(interface.name()));
+
+	let frames: Vec<CanFrame> = (0..5u8).map(|i| CanFrame::new(i, [i, i + 1])).collect();
+	assert!(let Ok(5) = socket_a.send_batch(&frames).await);
+
+	let mut received = [CanFrame::new(0u8, []); 3];
+	let mut interfaces = [can_socket::CanInterface::from_index(0), can_socket::CanInterface::from_index(0), can_socket::CanInterface::from_index(0)];
+	assert!(let Ok(3) = socket_b.recv_batch_from(&mut received, &mut interfaces).await);
+	for i in 0..3 {
+		assert!(received[i].id().as_u32() == i as u32);
+		assert!(received[i].data() == Some(CanData::new([i as u8, i as u8 + 1])));
+		assert!(interfaces[i] == expected_interface);
+	}
+
+	assert!(let Ok(2) = socket_b.recv_batch(&mut received).await);
+	assert!(received[0].id().as_u32() == 3);
+	assert!(received[1].id().as_u32() == 4);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn drop_count() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	assert!(let Ok(false) = socket_b.get_drop_count_enabled());
+	assert!(let Ok(()) = socket_b.set_drop_count_enabled(true));
+	assert!(let Ok(true) = socket_b.get_drop_count_enabled());
+
+	let mut tracker = can_socket::DropTracker::new();
+	assert!(let Ok(()) = socket_a.send(&CanFrame::new(1u8, [1, 2, 3])).await);
+	let_assert!(Ok((frame, metadata)) = socket_b.recv_from_with_metadata().await);
+	assert!(frame.id().as_u32() == 1);
+	assert!(metadata.drop_count() == 0);
+	assert!(tracker.update(&metadata) == 0);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn get_filters() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket) = CanSocket::bind(interface.name()));
+
+	let filters = [
+		CanFilter::new_standard(0x10.into()).match_exact_id(),
+		CanFilter::new_extended(0x1200u16.into()).match_id_mask(0xFFFFFF00).inverted(true),
+	];
+	assert!(let Ok(()) = socket.set_filters(&filters));
+	let_assert!(Ok(read_back) = socket.get_filters());
+	assert!(read_back.len() == 2);
+	for (read, written) in read_back.iter().zip(&filters) {
+		assert!(read.id() == written.id());
+		assert!(read.id_mask() == written.id_mask());
+		assert!(read.matches_standard_frames() == written.matches_standard_frames());
+		assert!(read.matches_extended_frames() == written.matches_extended_frames());
+		assert!(read.is_inverted() == written.is_inverted());
+	}
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn join_filters() {
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	// Accept IDs 0x100 to 0x1FF, except 0x120 to 0x12F.
+	assert!(let Ok(()) = socket_b.set_filters(&[
+		CanFilter::new_standard(StandardId::new(0x100).unwrap()).match_id_mask(0x700),
+		CanFilter::new_standard(StandardId::new(0x120).unwrap()).match_id_mask(0x7F0).inverted(true),
+	]));
+	assert!(let Ok(false) = socket_b.get_join_filters());
+	assert!(let Ok(()) = socket_b.set_join_filters(true));
+	assert!(let Ok(true) = socket_b.get_join_filters());
+
+	for id in [0x100u16, 0x125, 0x130, 0x200] {
+		assert!(let Ok(()) = socket_a.send(&CanFrame::new(StandardId::new(id).unwrap(), [1])).await);
+	}
+
+	let_assert!(Ok(frame) = socket_b.recv().await);
+	assert!(frame.id().as_u32() == 0x100);
+	let_assert!(Ok(frame) = socket_b.recv().await);
+	assert!(frame.id().as_u32() == 0x130);
+	let_assert!(Err(e) = socket_b.try_recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn isotp_talk() {
+	use can_socket::isotp::{IsoTpConfig, LinkLayerOptions};
+	use can_socket::tokio::IsoTpSocket;
+
+	let_assert!(Ok(interface) = TempInterface::new_fd());
+	let config = IsoTpConfig {
+		link_layer: LinkLayerOptions::fd(64, false),
+		..Default::default()
+	};
+	let_assert!(Ok(socket_a) = IsoTpSocket::bind_with_config(interface.name(), 0x7E8u16, 0x7E0u16, &config));
+	let_assert!(Ok(socket_b) = IsoTpSocket::bind_with_config(interface.name(), 0x7E0u16, 0x7E8u16, &config));
+	assert!(let Ok(LinkLayerOptions { mtu: 72, tx_dl: 64, .. }) = socket_a.get_link_layer_options());
+
+	let data: Vec<u8> = (0..=255).cycle().take(4000).collect();
+	let (sent, received) = tokio::join!(
+		socket_a.send(&data),
+		socket_b.recv_timeout(Duration::from_secs(2)),
+	);
+	assert!(let Ok(()) = sent);
+	let_assert!(Ok(message) = received);
+	assert!(message == data);
+
+	assert!(let Ok(()) = socket_b.send(&[4, 5, 6]).await);
+	let_assert!(Ok(message) = socket_a.recv_timeout(Duration::from_secs(1)).await);
+	assert!(message == [4, 5, 6]);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn j1939_transport_protocol() {
+	use can_socket::j1939::J1939Address;
+	use can_socket::tokio::J1939Socket;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x20)));
+	let_assert!(Ok(socket_b) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x30)));
+	assert!(let Ok(()) = socket_a.connect(&J1939Address::from_address(0x30).with_pgn(0x0EF00)));
+
+	// Larger messages are sent with the transport protocol.
+	let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+	let (sent, received) = tokio::join!(
+		socket_a.send(&data),
+		socket_b.recv_timeout(Duration::from_secs(5)),
+	);
+	assert!(let Ok(()) = sent);
+	let_assert!(Ok(message) = received);
+	assert!(message.data == data);
+	assert!(message.source.address == 0x20);
+	assert!(message.source.pgn == 0x0EF00);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn j1939_extended_transport_protocol() {
+	use can_socket::j1939::{J1939Address, MAX_TP_MESSAGE_SIZE};
+	use can_socket::tokio::J1939Socket;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x20)));
+	let_assert!(Ok(socket_b) = J1939Socket::bind(interface.name(), &J1939Address::from_address(0x30)));
+	assert!(let Ok(()) = socket_a.connect(&J1939Address::from_address(0x30).with_pgn(0x0EF00)));
+
+	// Messages larger than 1785 bytes are sent with the extended transport protocol.
+	let data: Vec<u8> = (0..=255).cycle().take(3000).collect();
+	assert!(data.len() > MAX_TP_MESSAGE_SIZE);
+	let mut buffer = vec![0; 4096];
+	let (sent, received) = tokio::join!(
+		socket_a.send(&data),
+		socket_b.recv_into_timeout(&mut buffer, Duration::from_secs(5)),
+	);
+	assert!(let Ok(()) = sent);
+	let_assert!(Ok(info) = received);
+	assert!(buffer[..info.len] == data[..]);
+	assert!(info.source.address == 0x20);
+	assert!(info.destination_address == Some(0x30));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn bcm_timeout_events() {
+	use can_socket::bcm::{BcmEvent, RxSetup};
+	use can_socket::tokio::BcmSocket;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(bcm) = BcmSocket::connect(interface.name()));
+	let_assert!(Ok(raw) = CanSocket::bind(interface.name()));
+
+	let id = ExtendedId::new(0x1234).unwrap();
+	let setup = RxSetup::new(id)
+		.with_timeout(Duration::from_millis(50));
+	assert!(let Ok(()) = bcm.rx_setup(&setup).await);
+
+	assert!(let Ok(()) = raw.send(&CanFrame::new(id, [1, 2, 3])).await);
+	let_assert!(Ok(BcmEvent::RxChanged(frame)) = bcm.recv_timeout(Duration::from_millis(50)).await);
+	assert!(frame.id() == id);
+	let_assert!(Ok(BcmEvent::RxTimeout(timeout_id)) = bcm.recv_timeout(Duration::from_millis(500)).await);
+	assert!(timeout_id == id);
+
+	// Poll for the next event like a stream.
+	assert!(let Ok(()) = raw.send(&CanFrame::new(id, [4])).await);
+	let_assert!(Ok(BcmEvent::RxChanged(frame)) = std::future::poll_fn(|context| bcm.poll_recv(context)).await);
+	assert!(frame.data() == Some(CanData::new([4])));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn bcm_stream() {
+	use can_socket::bcm::{BcmEvent, RxSetup};
+	use can_socket::tokio::{BcmSocket, BcmStream};
+	use futures::StreamExt;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(bcm) = BcmSocket::connect(interface.name()));
+	let_assert!(Ok(raw) = CanSocket::bind(interface.name()));
+
+	let id = ExtendedId::new(0x1234).unwrap();
+	let setup = RxSetup::new(id)
+		.with_timeout(Duration::from_millis(50));
+	assert!(let Ok(()) = bcm.rx_setup(&setup).await);
+
+	let mut events = BcmStream::new(&bcm);
+	assert!(let Ok(()) = raw.send(&CanFrame::new(id, [1, 2, 3])).await);
+	let_assert!(Ok(Some(Ok(BcmEvent::RxChanged(frame)))) = tokio::time::timeout(Duration::from_millis(500), events.next()).await);
+	assert!(frame.id() == id);
+	assert!(frame.data() == Some(CanData::new([1, 2, 3])));
+	let_assert!(Ok(Some(Ok(BcmEvent::RxTimeout(timeout_id)))) = tokio::time::timeout(Duration::from_millis(500), events.next()).await);
+	assert!(timeout_id == id);
+}
+
+#[tokio::test(start_paused = true)]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn replay_trace() {
+	use can_socket::log::LogEntry;
+	use can_socket::tokio::TraceReplayer;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(local_addr) = socket_a.local_addr());
+
+	let start = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+	let trace = [
+		LogEntry::new(start, "can0", CanFrame::new(0x100u16, [1])),
+		LogEntry::new(start + Duration::from_millis(100), "can1", CanFrame::new(0x200u16, [2])),
+		LogEntry::new(start + Duration::from_millis(200), "can0", CanFrame::new(0x300u16, [3])),
+		LogEntry::new(start + Duration::from_millis(300), "can0", CanFrame::new(0x101u16, [4])),
+	];
+
+	let replayer = TraceReplayer::new()
+		.with_speed(2.0)
+		.with_interface("can0", local_addr)
+		.with_filters([CanFilter::new_standard(StandardId::new(0x100).unwrap()).match_id_mask(0x700)]);
+	let replay_start = tokio::time::Instant::now();
+	assert!(let Ok(()) = replayer.replay(&socket_a, &trace).await);
+	assert!(replay_start.elapsed() == Duration::from_millis(150));
+
+	let_assert!(Ok(frame) = socket_b.recv_timeout(Duration::from_millis(10)).await);
+	assert!(frame.data() == Some(CanData::new([1])));
+	let_assert!(Ok(frame) = socket_b.recv_timeout(Duration::from_millis(10)).await);
+	assert!(frame.data() == Some(CanData::new([4])));
+	let_assert!(Err(e) = socket_b.try_recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[tokio::test(start_paused = true)]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn replay_trace_skipped_first_entry() {
+	use can_socket::log::LogEntry;
+	use can_socket::tokio::TraceReplayer;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(local_addr) = socket_a.local_addr());
+
+	let start = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+	let trace = [
+		LogEntry::new(start, "can1", CanFrame::new(0x100u16, [1])),
+		LogEntry::new(start + Duration::from_millis(100), "can0", CanFrame::new(0x200u16, [2])),
+		LogEntry::new(start + Duration::from_millis(300), "can0", CanFrame::new(0x300u16, [3])),
+	];
+
+	// The first entry is not mapped, so the second entry must be sent immediately.
+	let replayer = TraceReplayer::new()
+		.with_interface("can0", local_addr);
+	let replay_start = tokio::time::Instant::now();
+	assert!(let Ok(()) = replayer.replay(&socket_a, &trace).await);
+	assert!(replay_start.elapsed() == Duration::from_millis(200));
+
+	let_assert!(Ok(frame) = socket_b.recv_timeout(Duration::from_millis(10)).await);
+	assert!(frame.data() == Some(CanData::new([2])));
+	let_assert!(Ok(frame) = socket_b.recv_timeout(Duration::from_millis(10)).await);
+	assert!(frame.data() == Some(CanData::new([3])));
+	let_assert!(Err(e) = socket_b.try_recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
+
+#[tokio::test(start_paused = true)]
+#[cfg_attr(not(feature = "vcan-tests"), ignore = "enable the \"vcan-tests\" feature to enable this test")]
+async fn replay_trace_skips_error_frames() {
+	use can_socket::log::LogEntry;
+	use can_socket::tokio::TraceReplayer;
+
+	let_assert!(Ok(interface) = TempInterface::new());
+	let_assert!(Ok(socket_a) = CanSocket::bind(interface.name()));
+	let_assert!(Ok(socket_b) = CanSocket::bind(interface.name()));
+
+	let start = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+	let_assert!(Ok(error_frame) = "(1700000000.500000) can0 20000004#0004".parse::<LogEntry>());
+	let trace = [
+		LogEntry::new(start, "can0", CanFrame::new(0x100u16, [1])),
+		LogEntry::new(start + Duration::from_millis(100), "can0", CanFrame::new(0x200u16, [2])),
+		error_frame,
+	];
+
+	// The trailing error frame must not delay the replay, or the next iteration of a loop.
+	let replayer = TraceReplayer::new();
+	let replay_start = tokio::time::Instant::now();
+	assert!(let Ok(()) = replayer.replay(&socket_a, &trace).await);
+	assert!(replay_start.elapsed() == Duration::from_millis(100));
+
+	let replayer = TraceReplayer::new().with_loop(true);
+	let replay_start = tokio::time::Instant::now();
+	let_assert!(Err(_) = tokio::time::timeout(Duration::from_millis(250), replayer.replay(&socket_a, &trace)).await);
+	assert!(replay_start.elapsed() == Duration::from_millis(250));
+
+	// The first replay sent 2 frames, the loop sent frames at 0, 100, 100, 200 and 200 ms.
+	for data in [1, 2, 1, 2, 1, 2, 1] {
+		let_assert!(Ok(frame) = socket_b.recv_timeout(Duration::from_millis(10)).await);
+		assert!(frame.data() == Some(CanData::new([data])));
+	}
+	let_assert!(Err(e) = socket_b.try_recv());
+	assert!(e.kind() == std::io::ErrorKind::WouldBlock);
+}
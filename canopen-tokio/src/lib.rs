@@ -4,6 +4,7 @@
 #![warn(missing_debug_implementations)]
 
 use can_socket::tokio::CanSocket;
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use can_socket::{CanFrame, StandardId};
 use std::num::NonZeroU8;
 use std::time::{Duration, Instant};
@@ -19,9 +20,13 @@ pub mod sdo;
 /// A CANopen socket.
 ///
 /// Wrapper around a [`CanSocket`] that implements the `CANopen` protocol.
+///
+/// The socket can also wrap any other type that implements [`AsyncCanTransmit`] and [`AsyncCanReceive`],
+/// such as an [`AsyncBusSocket`](can_socket::virtual_bus::AsyncBusSocket) on an in-memory bus,
+/// or your own wrapper that records frames or injects faults.
 #[allow(missing_debug_implementations)]
-pub struct CanOpenSocket {
-	socket: CanSocket,
+pub struct CanOpenSocket<S = CanSocket> {
+	socket: S,
 	// TODO: Save messages for later delivery?
 	// read_queue: Vec<CanFrame>,
 }
@@ -36,9 +41,9 @@ pub struct ObjectIndex {
 	pub subindex: u8,
 }
 
-impl CanOpenSocket {
-	/// Create a new CANopen socket from a [`CanSocket`].
-	pub fn new(can_socket: CanSocket) -> Self {
+impl<S: AsyncCanTransmit + AsyncCanReceive> CanOpenSocket<S> {
+	/// Create a new CANopen socket from a [`CanSocket`] or another transport.
+	pub fn new(can_socket: S) -> Self {
 		Self {
 			socket: can_socket,
 		}
@@ -55,7 +60,7 @@ impl CanOpenSocket {
 		if Instant::now() >= deadline {
 			return None;
 		}
-		tokio::time::timeout_at(deadline.into(), self.socket.recv()).await.ok()
+		tokio::time::timeout_at(deadline.into(), self.recv_frame()).await.ok()
 	}
 
 	/// Send a raw CAN frame.
@@ -63,7 +68,7 @@ impl CanOpenSocket {
 		&mut self,
 		frame: &CanFrame,
 	) -> std::io::Result<()> {
-		self.socket.send(frame).await
+		std::future::poll_fn(|context| self.socket.poll_send(context, frame)).await
 	}
 
 	/// Send an NMT command and wait for the device to go into the specified state.
@@ -211,6 +216,11 @@ impl CanOpenSocket {
 		sync::send_sync(self, counter).await
 	}
 
+	/// Receive a raw CAN frame without a deadline.
+	async fn recv_frame(&mut self) -> std::io::Result<CanFrame> {
+		std::future::poll_fn(|context| self.socket.poll_recv(context)).await
+	}
+
 	/// Receive a new message from the CAN bus that that matches the given predicate.
 	///
 	/// Messages already in the read queue are not returned.
@@ -226,7 +236,7 @@ impl CanOpenSocket {
 		let receive_loop = async move {
 			let mut predicate = predicate;
 			loop {
-				let frame = self.socket.recv().await?;
+				let frame = self.recv_frame().await?;
 				if predicate(&frame) {
 					return Ok(frame);
 				} else {
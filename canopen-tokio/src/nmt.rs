@@ -4,6 +4,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::time::Duration;
 
 use can_socket::{CanFrame, StandardId};
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::CanOpenSocket;
 
 const NMT_COB_ID: u8 = 0x000;
@@ -97,13 +98,13 @@ pub struct UnexpectedState {
 }
 
 /// Send an NMT command and wait for the device to go into the specified state.
-pub async fn send_nmt_command(bus: &mut CanOpenSocket, node_id: u8, command: NmtCommand, timeout: Duration) -> Result<(), NmtError> {
+pub async fn send_nmt_command<S: AsyncCanTransmit + AsyncCanReceive>(bus: &mut CanOpenSocket<S>, node_id: u8, command: NmtCommand, timeout: Duration) -> Result<(), NmtError> {
 	log::debug!("Sending NMT command {command:?} to node 0x{node_id:02X} (timeout {timeout:?})");
 	let command_frame = CanFrame::new(
 		NMT_COB_ID,
 		[command as u8, node_id],
 	);
-	bus.socket.send(&command_frame)
+	bus.send_frame(&command_frame)
 		.await
 		.map_err(NmtError::SendFailed)?;
 
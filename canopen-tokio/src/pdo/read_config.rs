@@ -3,6 +3,7 @@ use std::time::Duration;
 use can_socket::CanId;
 
 use crate::sdo::SdoAddress;
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::{ObjectIndex, CanOpenSocket};
 
 use super::{
@@ -17,8 +18,8 @@ use super::{
 };
 
 /// Read the configuration of an RPDO.
-pub(crate) async fn read_rpdo_configuration(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn read_rpdo_configuration<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -35,8 +36,8 @@ pub(crate) async fn read_rpdo_configuration(
 }
 
 /// Read the configuration of a TPDO.
-pub(crate) async fn read_tpdo_configuration(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn read_tpdo_configuration<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -53,8 +54,8 @@ pub(crate) async fn read_tpdo_configuration(
 }
 
 /// Read the communication parameters of an RPDO.
-pub(crate) async fn read_rpdo_communication_parameters(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn read_rpdo_communication_parameters<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -90,8 +91,8 @@ pub(crate) async fn read_rpdo_communication_parameters(
 }
 
 /// Read the communication parameters of a TPDO.
-pub(crate) async fn read_tpdo_communication_parameters(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn read_tpdo_communication_parameters<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -135,8 +136,8 @@ pub(crate) async fn read_tpdo_communication_parameters(
 }
 
 /// Read the mapping of a PDO object (RPDO or TPDO).
-pub(crate) async fn read_pdo_mapping(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn read_pdo_mapping<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	object_index: u16,
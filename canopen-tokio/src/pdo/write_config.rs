@@ -3,6 +3,7 @@ use std::time::Duration;
 use can_socket::CanId;
 
 use crate::sdo::SdoAddress;
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::{ObjectIndex, CanOpenSocket};
 
 use super::{
@@ -15,8 +16,8 @@ use super::{
 };
 
 /// Enable or disable an RPDO.
-pub(crate) async fn enable_rpdo(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn enable_rpdo<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -36,8 +37,8 @@ pub(crate) async fn enable_rpdo(
 }
 
 /// Enable or disable an RPDO.
-pub(crate) async fn enable_tpdo(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn enable_tpdo<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -57,8 +58,8 @@ pub(crate) async fn enable_tpdo(
 }
 
 /// Set the full configuration of an RPDO.
-pub(crate) async fn configure_rpdo(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn configure_rpdo<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -79,8 +80,8 @@ pub(crate) async fn configure_rpdo(
 }
 
 /// Read the configuration of a TPDO.
-pub(crate) async fn configure_tpdo(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn configure_tpdo<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -101,8 +102,8 @@ pub(crate) async fn configure_tpdo(
 }
 
 /// Read the communication parameters of an RPDO.
-pub(crate) async fn write_rpdo_communication_parameters(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn write_rpdo_communication_parameters<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -140,8 +141,8 @@ pub(crate) async fn write_rpdo_communication_parameters(
 }
 
 /// Read the communication parameters of a TPDO.
-pub(crate) async fn write_tpdo_communication_parameters(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn write_tpdo_communication_parameters<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	pdo: u16,
@@ -190,8 +191,8 @@ pub(crate) async fn write_tpdo_communication_parameters(
 }
 
 /// Configure the mapping of a PDO object (RPDO or TPDO).
-pub(crate) async fn configure_pdo_mapping(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn configure_pdo_mapping<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	object_index: u16,
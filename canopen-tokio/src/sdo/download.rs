@@ -1,6 +1,7 @@
 use can_socket::CanFrame;
 use std::time::Duration;
 
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::{CanOpenSocket, ObjectIndex};
 
 use super::{
@@ -21,8 +22,8 @@ pub trait DownloadObject {
 }
 
 /// Perform an SDO download (write) to the server.
-pub(crate) async fn sdo_download(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn sdo_download<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	address: SdoAddress,
 	object: ObjectIndex,
@@ -52,8 +53,8 @@ pub(crate) async fn sdo_download(
 }
 
 /// Perform an expedited SDO download (write) to the server.
-async fn sdo_download_expedited(
-	bus: &mut CanOpenSocket,
+async fn sdo_download_expedited<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	address: SdoAddress,
 	object: ObjectIndex,
@@ -67,7 +68,7 @@ async fn sdo_download_expedited(
 	log::debug!("├─ Data: {data:02X?}");
 	log::debug!("└─ Timeout: {timeout:?}");
 	let command = make_sdo_expedited_download_command(node_id, address, object, data);
-	bus.socket.send(&command).await
+	bus.send_frame(&command).await
 		.map_err(SdoError::SendFailed)?;
 
 	let response = bus.recv_new_by_can_id(address.response_id(node_id), timeout)
@@ -80,8 +81,8 @@ async fn sdo_download_expedited(
 }
 
 /// Perform an segmented SDO download (write) to the server.
-async fn sdo_download_segmented(
-	bus: &mut CanOpenSocket,
+async fn sdo_download_segmented<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	address: SdoAddress,
 	object: ObjectIndex,
@@ -100,7 +101,7 @@ async fn sdo_download_segmented(
 
 	// Send command to iniate segmented download to server.
 	let command = make_sdo_initiate_segmented_download_command(node_id, address, object, data_len);
-	bus.socket.send(&command).await
+	bus.send_frame(&command).await
 		.map_err(SdoError::SendFailed)?;
 
 	// Parse response from server.
@@ -123,7 +124,7 @@ async fn sdo_download_segmented(
 			let complete = i + 1 == chunk_count;
 			let toggle = i % 2 == 1;
 			let command = make_sdo_segment_download_command(node_id, address, toggle, complete, data);
-			bus.socket.send(&command).await.map_err(SdoError::SendFailed)?;
+			bus.send_frame(&command).await.map_err(SdoError::SendFailed)?;
 
 			// Parse response.
 			let response = bus.recv_new_by_can_id(address.response_id(node_id), timeout)
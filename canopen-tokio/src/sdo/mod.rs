@@ -2,6 +2,7 @@
 
 use can_socket::CanFrame;
 
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::{CanOpenSocket, ObjectIndex};
 
 mod address;
@@ -191,8 +192,8 @@ fn check_server_command(frame: &CanFrame, expected: ServerCommand) -> Result<[u8
 }
 
 /// Send an abort command to an SDO server.
-async fn send_abort_transfer_command(
-	bus: &mut CanOpenSocket,
+async fn send_abort_transfer_command<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	address: SdoAddress,
 	node_id: u8,
 	object: ObjectIndex,
@@ -211,7 +212,7 @@ async fn send_abort_transfer_command(
 		reason[3],
 	];
 	let command = CanFrame::new(address.command_id(node_id), data);
-	bus.socket.send(&command).await
+	bus.send_frame(&command).await
 		.map_err(SdoError::SendFailed)
 }
 
@@ -1,6 +1,7 @@
 use can_socket::{CanData, CanFrame};
 use std::{time::Duration, convert::Infallible};
 
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::{CanOpenSocket, ObjectIndex};
 use super::{
 	SdoAddress,
@@ -39,8 +40,8 @@ pub trait UploadBuffer {
 }
 
 /// Perform a SDO upload from the server.
-pub(crate) async fn sdo_upload<Buffer: UploadBuffer>(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn sdo_upload<S: AsyncCanTransmit + AsyncCanReceive, Buffer: UploadBuffer>(
+	bus: &mut CanOpenSocket<S>,
 	node_id: u8,
 	sdo: SdoAddress,
 	object: ObjectIndex,
@@ -53,7 +54,7 @@ pub(crate) async fn sdo_upload<Buffer: UploadBuffer>(
 	log::debug!("├─ Object: index = 0x{:04X}, subindex = 0x{:02X}", object.index, object.subindex);
 	log::debug!("└─ Timeout: {timeout:?}");
 	let command = make_sdo_initiate_upload_request(node_id, sdo, object);
-	bus.socket.send(&command).await
+	bus.send_frame(&command).await
 		.map_err(SdoError::SendFailed)?;
 
 	let result: Result<usize, SdoError> = async {
@@ -83,7 +84,7 @@ pub(crate) async fn sdo_upload<Buffer: UploadBuffer>(
 		loop {
 			log::debug!("Sending SDO segment upload request to node 0x{node_id:02X}");
 			let command = make_sdo_upload_segment_request(sdo, node_id, toggle);
-			bus.socket.send(&command)
+			bus.send_frame(&command)
 				.await
 				.map_err(SdoError::SendFailed)?;
 			let response = bus.recv_new_by_can_id(sdo.response_id(node_id), timeout)
@@ -2,13 +2,14 @@
 use std::num::NonZeroU8;
 
 use can_socket::CanFrame;
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
 use crate::CanOpenSocket;
 
 const SYNC_DEFAULT_COB_ID: u8 = 0x80;
 
 /// Send a SYNC command to the CAN network.
-pub(crate) async fn send_sync(
-	bus: &mut CanOpenSocket,
+pub(crate) async fn send_sync<S: AsyncCanTransmit + AsyncCanReceive>(
+	bus: &mut CanOpenSocket<S>,
 	counter: Option<NonZeroU8>,
 ) -> Result<(), std::io::Error> {
 	log::debug!("Sending SYNC");
@@ -23,5 +24,5 @@ pub(crate) async fn send_sync(
 		}
 	};
 
-	bus.socket.send(&frame).await
+	bus.send_frame(&frame).await
 }
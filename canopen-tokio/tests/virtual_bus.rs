@@ -0,0 +1,70 @@
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use can_socket::transport::{AsyncCanReceive, AsyncCanTransmit};
+use can_socket::virtual_bus::{AsyncBusSocket, Bus};
+use can_socket::{CanFrame, StandardId};
+use canopen_tokio::sdo::SdoAddress;
+use canopen_tokio::{CanOpenSocket, ObjectIndex};
+
+#[tokio::test]
+async fn sdo_upload_over_virtual_bus() {
+	let bus = Bus::new();
+	let mut canopen = CanOpenSocket::new(bus.async_socket());
+	let node = bus.async_socket();
+
+	// Answer a single expedited upload request like node 5 would.
+	let server = tokio::spawn(async move {
+		let request = node.recv().await.unwrap();
+		assert_eq!(request.id(), StandardId::new(0x605).unwrap());
+		assert_eq!(request.data().unwrap().as_slice()[..4], [0x40, 0x18, 0x10, 0x01]);
+		let response = CanFrame::new(StandardId::new(0x585).unwrap(), [0x43, 0x18, 0x10, 0x01, 0x78, 0x56, 0x34, 0x12]);
+		node.send(&response).await.unwrap();
+	});
+
+	let value: u32 = canopen.sdo_upload(5, SdoAddress::standard(), ObjectIndex::new(0x1018, 1), Duration::from_secs(1))
+		.await
+		.unwrap();
+	assert_eq!(value, 0x1234_5678);
+	server.await.unwrap();
+}
+
+/// Transport that records all frames sent through it.
+struct Recorder {
+	inner: AsyncBusSocket,
+	sent: Vec<CanFrame>,
+}
+
+impl AsyncCanTransmit for Recorder {
+	fn poll_send(&mut self, context: &mut Context<'_>, frame: &CanFrame) -> Poll<std::io::Result<()>> {
+		let result = std::task::ready!(self.inner.poll_send(context, frame));
+		if result.is_ok() {
+			self.sent.push(*frame);
+		}
+		Poll::Ready(result)
+	}
+}
+
+impl AsyncCanReceive for Recorder {
+	fn poll_recv(&mut self, context: &mut Context<'_>) -> Poll<std::io::Result<CanFrame>> {
+		self.inner.poll_recv(context)
+	}
+}
+
+#[tokio::test]
+async fn custom_transport() {
+	let bus = Bus::new();
+	let mut recorder = Recorder {
+		inner: bus.async_socket(),
+		sent: Vec::new(),
+	};
+	let node = bus.async_socket();
+
+	let mut canopen = CanOpenSocket::new(&mut recorder);
+	canopen.send_sync(None).await.unwrap();
+
+	let frame = node.recv().await.unwrap();
+	assert_eq!(frame.id(), StandardId::new(0x80).unwrap());
+	assert_eq!(recorder.sent.len(), 1);
+	assert_eq!(recorder.sent[0].id(), frame.id());
+}